@@ -0,0 +1,236 @@
+//! SetTimerResolution.exe backend
+//!
+//! Applies the resolution by keeping a SetTimerResolution.exe child process alive
+//! and releases it by killing every instance of that process.
+
+use std::io::{self, Error, ErrorKind, Read};
+use std::path::{Path, PathBuf};
+use std::process::{Child, Command, Stdio};
+use std::sync::Mutex;
+use std::thread;
+use std::time::Duration;
+
+use crate::backend::{helper_command, ResolutionLimits, TimerBackend};
+use crate::ui::language::Language;
+use crate::ui::localization::{Localization, LocalizationKey};
+
+/// Backend driving the bundled SetTimerResolution.exe
+pub struct ExternalTimerBackend {
+    set_timer_path: PathBuf,
+    localization: Localization,
+    child: Mutex<Option<Child>>,
+}
+
+impl ExternalTimerBackend {
+    /// Create a backend for the executable at `set_timer_path`
+    pub fn new(set_timer_path: PathBuf, language: Language) -> Self {
+        Self {
+            set_timer_path,
+            localization: Localization::new(language),
+            child: Mutex::new(None),
+        }
+    }
+
+    /// Path of the SetTimerResolution.exe being driven
+    pub fn path(&self) -> &Path {
+        &self.set_timer_path
+    }
+}
+
+impl TimerBackend for ExternalTimerBackend {
+    fn name(&self) -> &'static str {
+        "SetTimerResolution.exe"
+    }
+
+    fn apply_resolution(&self, resolution_ms: f64) -> io::Result<()> {
+        let localization = &self.localization;
+        let resolution = (resolution_ms * 10_000.0) as i32;
+
+        self.release()?;
+        thread::sleep(Duration::from_millis(200));
+
+        let mut timer_child = helper_command(&self.set_timer_path)
+            .args(["--resolution", &resolution.to_string(), "--no-console"])
+            .stderr(Stdio::piped())
+            .stdout(Stdio::piped())
+            .spawn()
+            .map_err(|e| Error::other(format!("Failed to spawn SetTimerResolution: {}", e)))?;
+
+        thread::sleep(Duration::from_millis(50));
+
+        match timer_child.try_wait() {
+            Ok(Some(_exit_status)) => {
+                let mut stderr_output = String::new();
+                let mut stdout_output = String::new();
+                if let Some(mut stderr) = timer_child.stderr.take() {
+                    let _ = stderr.read_to_string(&mut stderr_output);
+                }
+                if let Some(mut stdout) = timer_child.stdout.take() {
+                    let _ = stdout.read_to_string(&mut stdout_output);
+                }
+                let error_msg = format!("{}{}", stderr_output, stdout_output);
+                if error_msg.contains("already running") || error_msg.contains("Another instance") {
+                    eprintln!("{}", localization.get(LocalizationKey::ErrorMutexConflict));
+                    eprintln!("{}", localization.get_mutex_error_message(error_msg.trim()));
+                    eprintln!("{}", localization.get(LocalizationKey::MutexErrorHint));
+                    eprintln!("{}", localization.get(LocalizationKey::MutexErrorRestart));
+                    kill_all_timer_processes()?;
+                    return Err(Error::new(ErrorKind::AlreadyExists, localization.get(LocalizationKey::ErrorMutexRunning)));
+                }
+                return Err(Error::other(localization.get_error_process_exited(&error_msg)));
+            },
+            Ok(None) => {},
+            Err(e) => {
+                eprintln!("{}", localization.get_warning_cannot_check_process(&e.to_string()));
+            }
+        }
+
+        *self.child.lock().unwrap() = Some(timer_child);
+        Ok(())
+    }
+
+    fn current_resolution(&self) -> io::Result<Option<f64>> {
+        Ok(query_timer_resolution()?.map(|(_, _, current)| current))
+    }
+
+    fn resolution_limits(&self) -> io::Result<Option<ResolutionLimits>> {
+        Ok(query_timer_resolution()?.map(|(finest_ms, coarsest_ms, _)| ResolutionLimits { finest_ms, coarsest_ms }))
+    }
+
+    fn release(&self) -> io::Result<()> {
+        if let Some(mut timer_child) = self.child.lock().unwrap().take() {
+            if let Err(e) = timer_child.kill() {
+                eprintln!("{}", self.localization.get_warning_kill_child(&e.to_string()));
+            }
+            let _ = timer_child.wait();
+        }
+        kill_all_timer_processes()
+    }
+}
+
+impl Drop for ExternalTimerBackend {
+    fn drop(&mut self) {
+        if let Some(mut timer_child) = self.child.lock().unwrap().take() {
+            let _ = timer_child.kill();
+        }
+    }
+}
+
+/// Query (finest, coarsest, current) timer resolution in ms via NtQueryTimerResolution
+#[cfg(windows)]
+fn query_timer_resolution() -> io::Result<Option<(f64, f64, f64)>> {
+    use windows::Wdk::System::SystemInformation::NtQueryTimerResolution;
+
+    let (mut maximum, mut minimum, mut current) = (0u32, 0u32, 0u32);
+    let status = unsafe { NtQueryTimerResolution(&mut maximum, &mut minimum, &mut current) };
+    if status.is_err() {
+        return Err(Error::other(format!("NtQueryTimerResolution failed: 0x{:08X}", status.0)));
+    }
+    // Values are reported in 100 ns units
+    let to_ms = |v: u32| v as f64 / 10_000.0;
+    Ok(Some((to_ms(maximum.min(minimum)), to_ms(maximum.max(minimum)), to_ms(current))))
+}
+
+#[cfg(not(windows))]
+fn query_timer_resolution() -> io::Result<Option<(f64, f64, f64)>> {
+    Ok(None)
+}
+
+/// Force kill all SetTimerResolution.exe instances using multiple methods (quiet version for internal use)
+pub fn kill_all_timer_processes() -> io::Result<()> {
+    // Silent version without output
+    let _ = Command::new("powershell")
+        .args(["-NoProfile", "-ExecutionPolicy", "Bypass", "-Command",
+            "Get-Process -Name SetTimerResolution -ErrorAction SilentlyContinue | Stop-Process -Force"])
+        .output();
+    thread::sleep(Duration::from_millis(200));
+    Ok(())
+}
+
+/// Force kill all SetTimerResolution.exe instances using multiple methods
+pub fn force_kill_all_timer_processes() -> io::Result<()> {
+    println!("   Attempting to kill SetTimerResolution.exe processes...");
+
+    // Method 1: PowerShell (more reliable if taskkill is disabled)
+    let ps_result = Command::new("powershell")
+        .args([
+            "-NoProfile",
+            "-ExecutionPolicy", "Bypass",
+            "-Command",
+            "Get-Process -Name SetTimerResolution -ErrorAction SilentlyContinue | ForEach-Object { Stop-Process -Id $_.Id -Force }"
+        ])
+        .output();
+    match ps_result {
+        Ok(output) if output.status.success() => {
+            println!("   ✓ PowerShell kill method succeeded");
+        },
+        Ok(output) => {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            if !stderr.is_empty() && !stderr.contains("Cannot find") {
+                eprintln!("   ⚠️ PowerShell warning: {}", stderr);
+            }
+        },
+        Err(e) => {
+            eprintln!("   ⚠️ PowerShell method failed: {}", e);
+        }
+    }
+    thread::sleep(Duration::from_millis(300));
+
+    // Method 2: taskkill (if service is running)
+    let taskkill_result = Command::new("taskkill")
+        .args(["/F", "/IM", "SetTimerResolution.exe", "/T"])
+        .output();
+    match taskkill_result {
+        Ok(output) if output.status.success() => {
+            println!("   ✓ taskkill method succeeded");
+        },
+        Ok(output) => {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            if stderr.contains("disabled") || stderr.contains("отключена") {
+                println!("   ℹ️ taskkill service is disabled (using PowerShell only)");
+            } else if !stderr.contains("not found") && !stderr.contains("не найден") {
+                eprintln!("   ⚠️ taskkill warning: {}", stderr);
+            }
+        },
+        Err(_) => {
+            println!("   ℹ️ taskkill not available");
+        }
+    }
+    thread::sleep(Duration::from_millis(500));
+
+    // Method 3: wmic (last resort)
+    let wmic_result = Command::new("wmic")
+        .args(["process", "where", "name='SetTimerResolution.exe'", "delete"])
+        .output();
+    if let Ok(output) = wmic_result {
+        if output.status.success() {
+            println!("   ✓ wmic method succeeded");
+        }
+    }
+    thread::sleep(Duration::from_millis(300));
+
+    // Final check
+    let remaining = count_timer_processes();
+    if remaining > 0 {
+        println!("   ⚠️ {} instance(s) still remain after cleanup", remaining);
+        Err(Error::other(
+            format!("{} SetTimerResolution.exe instance(s) could not be killed", remaining)))
+    } else {
+        println!("   ✓ All instances successfully killed");
+        Ok(())
+    }
+}
+
+/// Count running SetTimerResolution.exe processes for diagnostics
+pub fn count_timer_processes() -> usize {
+    let output = Command::new("tasklist")
+        .arg("/FI")
+        .arg("IMAGENAME eq SetTimerResolution.exe")
+        .output();
+    if let Ok(output) = output {
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        stdout.matches("SetTimerResolution.exe").count()
+    } else {
+        0
+    }
+}
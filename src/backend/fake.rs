@@ -0,0 +1,80 @@
+//! In-memory timer backend
+//!
+//! Records every applied resolution without touching the system timer. Used to run
+//! the search methods on machines without SetTimerResolution.exe (CI, Linux).
+
+use std::io::{self, Error, ErrorKind};
+use std::sync::Mutex;
+
+use crate::backend::{ResolutionLimits, TimerBackend};
+
+/// Fake backend that only remembers what it was asked to do
+#[derive(Debug)]
+pub struct FakeTimerBackend {
+    limits: ResolutionLimits,
+    current: Mutex<Option<f64>>,
+    history: Mutex<Vec<f64>>,
+}
+
+impl FakeTimerBackend {
+    /// Create a fake backend with typical Windows limits (0.5 - 15.625 ms)
+    pub fn new() -> Self {
+        Self::with_limits(ResolutionLimits { finest_ms: 0.5, coarsest_ms: 15.625 })
+    }
+
+    /// Create a fake backend that rejects resolutions outside `limits`
+    pub fn with_limits(limits: ResolutionLimits) -> Self {
+        Self {
+            limits,
+            current: Mutex::new(None),
+            history: Mutex::new(Vec::new()),
+        }
+    }
+
+    /// Every resolution applied so far, in order
+    pub fn history(&self) -> Vec<f64> {
+        self.history.lock().unwrap().clone()
+    }
+}
+
+impl Default for FakeTimerBackend {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl TimerBackend for FakeTimerBackend {
+    fn name(&self) -> &'static str {
+        "fake"
+    }
+
+    fn apply_resolution(&self, resolution_ms: f64) -> io::Result<()> {
+        if !(self.limits.finest_ms..=self.limits.coarsest_ms).contains(&resolution_ms) {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                format!("Resolution {:.4} ms is outside [{:.4}, {:.4}] ms",
+                    resolution_ms, self.limits.finest_ms, self.limits.coarsest_ms),
+            ));
+        }
+        *self.current.lock().unwrap() = Some(resolution_ms);
+        self.history.lock().unwrap().push(resolution_ms);
+        Ok(())
+    }
+
+    fn current_resolution(&self) -> io::Result<Option<f64>> {
+        Ok(*self.current.lock().unwrap())
+    }
+
+    fn resolution_limits(&self) -> io::Result<Option<ResolutionLimits>> {
+        Ok(Some(self.limits))
+    }
+
+    fn release(&self) -> io::Result<()> {
+        *self.current.lock().unwrap() = None;
+        Ok(())
+    }
+
+    fn needs_settling(&self) -> bool {
+        false
+    }
+}
@@ -0,0 +1,59 @@
+//! In-process sleep probe
+//!
+//! Times `std::thread::sleep` with `Instant` on the calling thread. Works on any
+//! platform, so the measurement pipeline runs without MeasureSleep.exe.
+
+use std::io;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use crate::backend::{ProbeReport, SleepProbe};
+
+/// Probe that measures `thread::sleep` overshoot in the current process
+#[derive(Debug, Clone)]
+pub struct InProcessProbe {
+    sleep_duration: Duration,
+}
+
+impl InProcessProbe {
+    /// Create a probe sleeping 1 ms per sample (same as MeasureSleep's Sleep(1))
+    pub fn new() -> Self {
+        Self::with_sleep_duration(Duration::from_millis(1))
+    }
+
+    /// Create a probe sleeping `sleep_duration` per sample
+    pub fn with_sleep_duration(sleep_duration: Duration) -> Self {
+        Self { sleep_duration }
+    }
+}
+
+impl Default for InProcessProbe {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl SleepProbe for InProcessProbe {
+    fn name(&self) -> &'static str {
+        "in-process"
+    }
+
+    fn sample(&self, samples: usize) -> io::Result<ProbeReport> {
+        let requested_ms = self.sleep_duration.as_secs_f64() * 1000.0;
+        let mut deltas_ms = Vec::with_capacity(samples);
+        let mut offsets_ms = Vec::with_capacity(samples);
+        let sampling_start = Instant::now();
+        for _ in 0..samples {
+            let start = Instant::now();
+            thread::sleep(self.sleep_duration);
+            let slept_ms = start.elapsed().as_secs_f64() * 1000.0;
+            deltas_ms.push(slept_ms - requested_ms);
+            offsets_ms.push(sampling_start.elapsed().as_secs_f64() * 1000.0);
+        }
+        Ok(ProbeReport {
+            deltas_ms,
+            offsets_ms,
+            reported_resolution_ms: None,
+        })
+    }
+}
@@ -0,0 +1,219 @@
+//! MeasureSleep.exe sleep probe
+//!
+//! Runs the bundled MeasureSleep.exe and parses every per-sample
+//! "Resolution: ..., Sleep(1) slept ...ms (delta: ...)" line from its output.
+
+use std::io::{self, Error, ErrorKind, Read};
+use std::path::{Path, PathBuf};
+use std::process::Stdio;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use crate::backend::{helper_command, ProbeReport, SleepProbe};
+use crate::ui::language::Language;
+use crate::ui::localization::{Localization, LocalizationKey};
+
+/// Maximum time a single MeasureSleep.exe run may take
+const MEASURE_SLEEP_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Probe driving the bundled MeasureSleep.exe
+pub struct MeasureSleepProbe {
+    measure_sleep_path: PathBuf,
+    localization: Localization,
+}
+
+impl MeasureSleepProbe {
+    /// Create a probe for the executable at `measure_sleep_path`
+    pub fn new(measure_sleep_path: PathBuf, language: Language) -> Self {
+        Self {
+            measure_sleep_path,
+            localization: Localization::new(language),
+        }
+    }
+
+    /// Path of the MeasureSleep.exe being driven
+    pub fn path(&self) -> &Path {
+        &self.measure_sleep_path
+    }
+}
+
+impl SleepProbe for MeasureSleepProbe {
+    fn name(&self) -> &'static str {
+        "MeasureSleep.exe"
+    }
+
+    fn sample(&self, samples: usize) -> io::Result<ProbeReport> {
+        let localization = &self.localization;
+
+        let mut child = helper_command(&self.measure_sleep_path)
+            .arg("--samples")
+            .arg(samples.to_string())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .inspect_err(|e| eprintln!("{}", localization.get_measure_sleep_error(&e.to_string())))?;
+
+        // Drain pipes on separate threads so a chatty child never blocks on a full pipe
+        let mut stdout_pipe = child.stdout.take();
+        let mut stderr_pipe = child.stderr.take();
+        let stdout_reader = thread::spawn(move || {
+            let mut buf = Vec::new();
+            if let Some(pipe) = stdout_pipe.as_mut() {
+                let _ = pipe.read_to_end(&mut buf);
+            }
+            buf
+        });
+        let stderr_reader = thread::spawn(move || {
+            let mut buf = Vec::new();
+            if let Some(pipe) = stderr_pipe.as_mut() {
+                let _ = pipe.read_to_end(&mut buf);
+            }
+            buf
+        });
+
+        let deadline = Instant::now() + MEASURE_SLEEP_TIMEOUT;
+        let status = loop {
+            if let Some(status) = child.try_wait()? {
+                break status;
+            }
+            if Instant::now() >= deadline {
+                let _ = child.kill();
+                let _ = child.wait();
+                eprintln!("{}", localization.get(LocalizationKey::TimeoutError));
+                return Err(Error::new(ErrorKind::TimedOut, "MeasureSleep timeout"));
+            }
+            thread::sleep(Duration::from_millis(10));
+        };
+
+        let stdout = stdout_reader.join().map_err(|_| Error::other("MeasureSleep stdout reader panicked"))?;
+        let stderr = stderr_reader.join().map_err(|_| Error::other("MeasureSleep stderr reader panicked"))?;
+
+        if !status.success() {
+            eprintln!("{}", localization.get(LocalizationKey::ErrorMeasureSleepFailed));
+            eprintln!("{}", String::from_utf8_lossy(&stderr));
+            return Err(Error::other("MeasureSleep execution failed"));
+        }
+
+        let report = parse_measure_sleep_output(&stdout)?;
+        if report.reported_resolution_ms.is_none() {
+            eprintln!("{}", localization.get(LocalizationKey::WarningParseResolution));
+            eprintln!("{}",
+                localization.get_output_preview(
+                    String::from_utf8_lossy(&stdout).lines().next().unwrap_or(localization.get(LocalizationKey::Empty))
+                )
+            );
+        }
+        Ok(report)
+    }
+}
+
+/// Parse MeasureSleep.exe output into per-sample deltas
+/// Example sample line: "Resolution: 0.5186ms, Sleep(1) slept 1.0310ms (delta: 0.0310)"
+/// Output with only the "Avg: " summary line is rejected: an average is not a sample.
+/// Wakeup offsets are the running total of the "slept" times (empty if any is missing).
+pub fn parse_measure_sleep_output(output: &[u8]) -> io::Result<ProbeReport> {
+    let output_str = std::str::from_utf8(output).map_err(|e| Error::new(ErrorKind::InvalidData, format!("UTF-8 decode error: {}", e)))?;
+
+    let mut deltas_ms = Vec::new();
+    let mut slept_ms = Vec::new();
+    let mut has_avg = false;
+    let mut resolution_ms = None;
+
+    for line in output_str.lines() {
+        let trimmed = line.trim();
+
+        // Extract "0.5186" from "Resolution: 0.5186ms"
+        if resolution_ms.is_none() && trimmed.contains("Resolution: ") {
+            if let Some(res_part) = trimmed.split("Resolution: ").nth(1) {
+                if let Some(res_str) = res_part.split("ms").next() {
+                    resolution_ms = res_str.trim().parse::<f64>().ok();
+                }
+            }
+        }
+
+        // Extract "0.0310" from "(delta: 0.0310)"
+        if let Some(delta_part) = trimmed.split("(delta: ").nth(1) {
+            if let Some(delta) = delta_part.split(')').next().and_then(|d| d.trim().parse::<f64>().ok()) {
+                deltas_ms.push(delta);
+                // Extract "1.0310" from "slept 1.0310ms"
+                if let Some(slept) = trimmed.split("slept ").nth(1)
+                    .and_then(|part| part.split("ms").next())
+                    .and_then(|v| v.trim().parse::<f64>().ok())
+                {
+                    slept_ms.push(slept);
+                }
+            }
+        }
+
+        has_avg |= trimmed.starts_with("Avg: ");
+    }
+
+    if deltas_ms.is_empty() {
+        eprintln!("Failed to parse MeasureSleep output:");
+        eprintln!("{}", output_str);
+        let message = if has_avg {
+            "MeasureSleep output has an Avg summary but no per-sample lines"
+        } else {
+            "Invalid MeasureSleep output format"
+        };
+        return Err(Error::new(ErrorKind::InvalidData, message));
+    }
+
+    let offsets_ms = if slept_ms.len() == deltas_ms.len() {
+        slept_ms.iter()
+            .scan(0.0, |total, slept| {
+                *total += slept;
+                Some(*total)
+            })
+            .collect()
+    } else {
+        Vec::new()
+    };
+
+    Ok(ProbeReport {
+        deltas_ms,
+        offsets_ms,
+        reported_resolution_ms: resolution_ms,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_sample_lines() {
+        let output = b"Resolution: 0.5186ms, Sleep(1) slept 1.0310ms (delta: 0.0310)\r\n\
+            Resolution: 0.5186ms, Sleep(1) slept 1.0125ms (delta: 0.0125)\r\n\
+            Resolution: 0.5186ms, Sleep(1) slept 1.0500ms (delta: 0.0500)\r\n\
+            Avg: 1.0312ms\r\n";
+        let report = parse_measure_sleep_output(output).unwrap();
+        assert_eq!(report.deltas_ms, vec![0.0310, 0.0125, 0.0500]);
+        assert_eq!(report.reported_resolution_ms, Some(0.5186));
+        assert_eq!(report.offsets_ms.len(), 3);
+        assert!((report.offsets_ms[2] - 3.0935).abs() < 1e-9);
+    }
+
+    #[test]
+    fn missing_slept_times_drop_the_offsets() {
+        let output = b"Resolution: 0.5000ms, Sleep(1) slept 1.0310ms (delta: 0.0310)\n(delta: 0.0200)\n";
+        let report = parse_measure_sleep_output(output).unwrap();
+        assert_eq!(report.deltas_ms, vec![0.0310, 0.0200]);
+        assert!(report.offsets_ms.is_empty());
+    }
+
+    #[test]
+    fn average_only_output_is_rejected() {
+        let error = parse_measure_sleep_output(b"Resolution: 0.5186ms\nAvg: 1.0312ms\n").unwrap_err();
+        assert_eq!(error.kind(), ErrorKind::InvalidData);
+        assert!(error.to_string().contains("Avg summary"), "{}", error);
+    }
+
+    #[test]
+    fn garbage_is_rejected() {
+        for output in [&b""[..], b"not a measurement\n(delta: abc)\n", &[0xff, 0xfe, 0x00]] {
+            let error = parse_measure_sleep_output(output).unwrap_err();
+            assert_eq!(error.kind(), ErrorKind::InvalidData);
+        }
+    }
+}
@@ -0,0 +1,151 @@
+//! Timer backends and sleep probes for timer resolution benchmarking
+//!
+//! A backend applies a requested timer resolution and keeps it active until it is
+//! released; a probe measures how far sleeps overshoot while it is active. The
+//! search methods only talk to the `TimerBackend` and `SleepProbe` traits, so the
+//! same orchestration runs against the bundled executables or in-process fakes.
+
+pub mod external;
+pub mod fake;
+pub mod in_process;
+pub mod measure_sleep;
+pub mod simulated;
+#[cfg(target_os = "linux")]
+pub mod timer_slack;
+
+use std::ffi::OsStr;
+use std::io;
+use std::process::Command;
+use std::sync::Arc;
+
+use serde::{Deserialize, Serialize};
+
+pub use external::ExternalTimerBackend;
+pub use fake::FakeTimerBackend;
+pub use in_process::InProcessProbe;
+pub use measure_sleep::MeasureSleepProbe;
+pub use simulated::{SimulatedTimer, SimulationModel};
+#[cfg(target_os = "linux")]
+pub use timer_slack::TimerSlackBackend;
+
+/// Backend selected in appsettings.json
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum BackendKind {
+    #[default]
+    Auto,        // timer-slack on Linux, external executables elsewhere
+    External,    // SetTimerResolution.exe + MeasureSleep.exe
+    TimerSlack,  // Linux PR_SET_TIMERSLACK + clock_nanosleep
+    Simulated,   // Deterministic simulated timer
+}
+
+impl BackendKind {
+    /// Resolve `Auto` to the native backend of the current platform
+    pub fn resolve(self) -> Self {
+        match self {
+            BackendKind::Auto if cfg!(target_os = "linux") => BackendKind::TimerSlack,
+            BackendKind::Auto => BackendKind::External,
+            other => other,
+        }
+    }
+}
+
+/// `Command` for a bundled helper executable, started in its own process group
+///
+/// Children share the console's process group by default, so on Windows the
+/// first Ctrl+C would kill them while the search is still finishing the
+/// current point.
+pub(crate) fn helper_command(program: impl AsRef<OsStr>) -> Command {
+    #[cfg_attr(not(windows), allow(unused_mut))]
+    let mut command = Command::new(program);
+    #[cfg(windows)]
+    {
+        use std::os::windows::process::CommandExt;
+        use windows_sys::Win32::System::Threading::CREATE_NEW_PROCESS_GROUP;
+        command.creation_flags(CREATE_NEW_PROCESS_GROUP);
+    }
+    command
+}
+
+/// Resolution limits reported by a backend (milliseconds)
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ResolutionLimits {
+    pub finest_ms: f64,    // Smallest supported resolution
+    pub coarsest_ms: f64,  // Largest supported resolution
+}
+
+/// Backend that applies, queries and releases the timer resolution
+pub trait TimerBackend {
+    /// Short backend name for logs and reports
+    fn name(&self) -> &'static str;
+
+    /// Apply the requested resolution and keep it active until `release`
+    fn apply_resolution(&self, resolution_ms: f64) -> io::Result<()>;
+
+    /// Currently effective resolution, if the backend can query it
+    fn current_resolution(&self) -> io::Result<Option<f64>>;
+
+    /// Finest and coarsest supported resolution, if the backend can query them
+    fn resolution_limits(&self) -> io::Result<Option<ResolutionLimits>>;
+
+    /// Release the applied resolution (safe to call when nothing is applied)
+    fn release(&self) -> io::Result<()>;
+
+    /// Whether the system needs settle time after applying or releasing a resolution
+    fn needs_settling(&self) -> bool {
+        true
+    }
+}
+
+/// Raw result of one probe run
+#[derive(Debug, Clone)]
+pub struct ProbeReport {
+    pub deltas_ms: Vec<f64>,                  // Per-sample sleep overshoot
+    pub offsets_ms: Vec<f64>,                 // Wakeup time of each sample from the start of sampling (empty if unknown)
+    pub reported_resolution_ms: Option<f64>,  // Resolution seen by the probe, if it reports one
+}
+
+/// Probe that measures sleep overshoot under the currently applied resolution
+pub trait SleepProbe {
+    /// Short probe name for logs and reports
+    fn name(&self) -> &'static str;
+
+    /// Take `samples` sleep measurements and return every individual delta
+    fn sample(&self, samples: usize) -> io::Result<ProbeReport>;
+}
+
+impl<T: TimerBackend + ?Sized> TimerBackend for Arc<T> {
+    fn name(&self) -> &'static str {
+        (**self).name()
+    }
+
+    fn apply_resolution(&self, resolution_ms: f64) -> io::Result<()> {
+        (**self).apply_resolution(resolution_ms)
+    }
+
+    fn current_resolution(&self) -> io::Result<Option<f64>> {
+        (**self).current_resolution()
+    }
+
+    fn resolution_limits(&self) -> io::Result<Option<ResolutionLimits>> {
+        (**self).resolution_limits()
+    }
+
+    fn release(&self) -> io::Result<()> {
+        (**self).release()
+    }
+
+    fn needs_settling(&self) -> bool {
+        (**self).needs_settling()
+    }
+}
+
+impl<T: SleepProbe + ?Sized> SleepProbe for Arc<T> {
+    fn name(&self) -> &'static str {
+        (**self).name()
+    }
+
+    fn sample(&self, samples: usize) -> io::Result<ProbeReport> {
+        (**self).sample(samples)
+    }
+}
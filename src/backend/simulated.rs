@@ -0,0 +1,215 @@
+//! Deterministic simulated timer
+//!
+//! Models Sleep(1) overshoot as a function of the applied resolution so the search
+//! methods can be run offline against a known ground-truth optimum. The overshoot of
+//! each sample is built from:
+//! - a sawtooth: the wakeup lands on the first tick boundary after the target sleep
+//! - a bowl: extra latency growing with distance from `optimum_ms`
+//! - Gaussian jitter (half-normal, wider away from the optimum)
+//! - occasional heavy-tail (Pareto) spikes that do not depend on the resolution
+//! - optionally, a warm-up transient after each resolution change
+//!
+//! All randomness comes from a seeded generator, so identical seeds reproduce
+//! identical runs.
+
+use std::io::{self, Error, ErrorKind};
+use std::sync::Mutex;
+
+use serde::{Deserialize, Serialize};
+
+use crate::backend::{ProbeReport, ResolutionLimits, SleepProbe, TimerBackend};
+use crate::utils::rng::SplitMix64;
+
+/// Parameters of the simulated overshoot distribution (all times in ms)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "PascalCase", default)]
+pub struct SimulationModel {
+    pub target_sleep_ms: f64,     // Requested sleep per sample (1 ms = Sleep(1))
+    pub base_latency_ms: f64,     // Constant wakeup latency added to every sample
+    pub tick_weight: f64,         // Scale of the tick-boundary sawtooth (0 disables it)
+    pub optimum_ms: f64,          // Resolution where bowl and jitter are smallest
+    pub bowl_slope: f64,          // Extra latency per ms away from the optimum
+    pub jitter_ms: f64,           // Jitter standard deviation at the optimum
+    pub jitter_slope: f64,        // Relative jitter growth per ms away from the optimum
+    pub spike_probability: f64,   // Chance that a sample gets a heavy-tail spike
+    pub spike_scale_ms: f64,      // Minimum size of a spike (Pareto scale)
+    pub warmup_ms: f64,           // Simulated time after a resolution change with extra overshoot (0 disables it)
+    pub warmup_excess_ms: f64,    // Extra overshoot right after the change, fading out linearly over `warmup_ms`
+    pub finest_ms: f64,           // Finest resolution the simulated timer accepts
+    pub coarsest_ms: f64,         // Coarsest resolution (also used when nothing is applied)
+    pub seed: u64,
+}
+
+impl Default for SimulationModel {
+    fn default() -> Self {
+        Self {
+            target_sleep_ms: 1.0,
+            base_latency_ms: 0.010,
+            tick_weight: 0.25,
+            optimum_ms: 0.5040,
+            bowl_slope: 0.5,
+            jitter_ms: 0.005,
+            jitter_slope: 20.0,
+            spike_probability: 0.01,
+            spike_scale_ms: 0.2,
+            warmup_ms: 0.0,
+            warmup_excess_ms: 0.05,
+            finest_ms: 0.5,
+            coarsest_ms: 15.625,
+            seed: 42,
+        }
+    }
+}
+
+impl SimulationModel {
+    /// Sawtooth overshoot caused by waking on the first tick at or after the target
+    fn tick_overshoot(&self, resolution_ms: f64) -> f64 {
+        let ticks = (self.target_sleep_ms / resolution_ms - 1e-9).ceil();
+        ticks * resolution_ms - self.target_sleep_ms
+    }
+
+    /// Noise-free part of the overshoot at `resolution_ms`
+    fn deterministic_overshoot(&self, resolution_ms: f64) -> f64 {
+        self.base_latency_ms
+            + self.tick_weight * self.tick_overshoot(resolution_ms)
+            + self.bowl_slope * (resolution_ms - self.optimum_ms).abs()
+    }
+
+    /// Jitter standard deviation at `resolution_ms`
+    fn jitter_at(&self, resolution_ms: f64) -> f64 {
+        self.jitter_ms * (1.0 + self.jitter_slope * (resolution_ms - self.optimum_ms).abs())
+    }
+
+    /// Expected overshoot without spikes (half-normal jitter has mean σ·√(2/π))
+    pub fn expected_overshoot(&self, resolution_ms: f64) -> f64 {
+        self.deterministic_overshoot(resolution_ms)
+            + self.jitter_at(resolution_ms) * (2.0 / std::f64::consts::PI).sqrt()
+    }
+
+    /// Resolution on the `[start, end]` grid with the lowest expected overshoot
+    pub fn ground_truth(&self, start: f64, end: f64, step: f64) -> f64 {
+        let points = ((end - start) / step).round() as usize;
+        (0..=points)
+            .map(|i| start + i as f64 * step)
+            .filter(|&x| x <= end + step * 1e-6)
+            .min_by(|a, b| {
+                self.expected_overshoot(*a)
+                    .partial_cmp(&self.expected_overshoot(*b))
+                    .unwrap_or(std::cmp::Ordering::Equal)
+            })
+            .unwrap_or(start)
+    }
+}
+
+#[derive(Debug)]
+struct SimulationState {
+    rng: SplitMix64,
+    applied_ms: Option<f64>,
+    since_apply_ms: f64,  // Simulated time slept since the last resolution change
+}
+
+/// Simulated timer acting as both timer backend and sleep probe
+#[derive(Debug)]
+pub struct SimulatedTimer {
+    model: SimulationModel,
+    state: Mutex<SimulationState>,
+}
+
+impl SimulatedTimer {
+    /// Create a simulated timer seeded from `model.seed`
+    pub fn new(model: SimulationModel) -> Self {
+        let rng = SplitMix64::new(model.seed);
+        Self {
+            model,
+            state: Mutex::new(SimulationState { rng, applied_ms: None, since_apply_ms: 0.0 }),
+        }
+    }
+
+    /// Overshoot model driving this timer
+    pub fn model(&self) -> &SimulationModel {
+        &self.model
+    }
+}
+
+impl Default for SimulatedTimer {
+    fn default() -> Self {
+        Self::new(SimulationModel::default())
+    }
+}
+
+impl TimerBackend for SimulatedTimer {
+    fn name(&self) -> &'static str {
+        "simulated"
+    }
+
+    fn apply_resolution(&self, resolution_ms: f64) -> io::Result<()> {
+        if !(self.model.finest_ms..=self.model.coarsest_ms).contains(&resolution_ms) {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                format!("Resolution {:.4} ms is outside [{:.4}, {:.4}] ms",
+                    resolution_ms, self.model.finest_ms, self.model.coarsest_ms),
+            ));
+        }
+        let mut state = self.state.lock().unwrap();
+        state.applied_ms = Some(resolution_ms);
+        state.since_apply_ms = 0.0;
+        Ok(())
+    }
+
+    fn current_resolution(&self) -> io::Result<Option<f64>> {
+        Ok(Some(self.state.lock().unwrap().applied_ms.unwrap_or(self.model.coarsest_ms)))
+    }
+
+    fn resolution_limits(&self) -> io::Result<Option<ResolutionLimits>> {
+        Ok(Some(ResolutionLimits {
+            finest_ms: self.model.finest_ms,
+            coarsest_ms: self.model.coarsest_ms,
+        }))
+    }
+
+    fn release(&self) -> io::Result<()> {
+        self.state.lock().unwrap().applied_ms = None;
+        Ok(())
+    }
+
+    fn needs_settling(&self) -> bool {
+        false
+    }
+}
+
+impl SleepProbe for SimulatedTimer {
+    fn name(&self) -> &'static str {
+        "simulated"
+    }
+
+    fn sample(&self, samples: usize) -> io::Result<ProbeReport> {
+        let model = &self.model;
+        let mut state = self.state.lock().unwrap();
+        let resolution_ms = state.applied_ms.unwrap_or(model.coarsest_ms);
+        let base = model.deterministic_overshoot(resolution_ms);
+        let jitter = model.jitter_at(resolution_ms);
+
+        let sampling_start_ms = state.since_apply_ms;
+        let mut deltas_ms = Vec::with_capacity(samples);
+        let mut offsets_ms = Vec::with_capacity(samples);
+        for _ in 0..samples {
+            let mut delta = base + jitter * state.rng.next_gaussian().abs();
+            if state.rng.next_f64() < model.spike_probability {
+                // Pareto(alpha = 1.5) spike
+                delta += model.spike_scale_ms / state.rng.next_f64().powf(1.0 / 1.5);
+            }
+            if state.since_apply_ms < model.warmup_ms {
+                delta += model.warmup_excess_ms * (1.0 - state.since_apply_ms / model.warmup_ms);
+            }
+            state.since_apply_ms += model.target_sleep_ms + delta;
+            deltas_ms.push(delta);
+            offsets_ms.push(state.since_apply_ms - sampling_start_ms);
+        }
+
+        Ok(ProbeReport {
+            deltas_ms,
+            offsets_ms,
+            reported_resolution_ms: Some(resolution_ms),
+        })
+    }
+}
@@ -0,0 +1,240 @@
+//! Command-line interface
+//!
+//! Without a subcommand the program runs the interactive benchmark as before.
+//! Every value given on the command line skips the matching prompt, so a run
+//! with all flags set needs no terminal at all.
+
+use std::io;
+use std::path::PathBuf;
+
+use clap::{Args, Parser, Subcommand};
+
+use crate::core::{run_benchmark, run_check, OptimizationMethod, RunOptions};
+use crate::optimization::acquisition::AcquisitionKind;
+use crate::optimization::criteria::{CriteriaPreset, WeightedCriterion, WeightingMethod};
+use crate::report::history::HISTORY_DIR;
+use crate::report::{compare_runs, print_history, print_report, resolve_run, ChartFormat, OutputFormat};
+use crate::stats::quantile::QuantileEstimator;
+use crate::ui::language::Language;
+use crate::ui::localization::Localization;
+
+#[derive(Debug, Parser)]
+#[command(name = "timer_res_benchmark", version = crate::VERSION, about = "Find the optimal system timer resolution")]
+pub struct Cli {
+    #[command(subcommand)]
+    pub command: Option<Command>,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum Command {
+    /// Run the benchmark
+    Run(Box<RunArgs>),
+    /// Check privileges, HPET and the timer backend without benchmarking
+    Check(CommonArgs),
+    /// Print the ranking from a saved results file
+    Report(ReportArgs),
+    /// List stored runs and their recommended resolution
+    History(HistoryArgs),
+    /// Compare two stored runs resolution by resolution
+    Compare(CompareArgs),
+}
+
+#[derive(Debug, Args)]
+pub struct CommonArgs {
+    /// Interface language (en, uk, ru, zh)
+    #[arg(long, value_parser = parse_language)]
+    pub lang: Option<Language>,
+    /// Configuration file
+    #[arg(long, default_value = "appsettings.json")]
+    pub config: PathBuf,
+}
+
+#[derive(Debug, Args)]
+pub struct RunArgs {
+    #[command(flatten)]
+    pub common: CommonArgs,
+    /// Search method
+    #[arg(long, value_enum)]
+    pub method: Option<OptimizationMethod>,
+    /// First resolution to test, in ms
+    #[arg(long)]
+    pub start: Option<f64>,
+    /// Last resolution to test, in ms
+    #[arg(long)]
+    pub end: Option<f64>,
+    /// Step between resolutions, in ms
+    #[arg(long)]
+    pub step: Option<f64>,
+    /// Sleep samples per measurement run
+    #[arg(long)]
+    pub samples: Option<i32>,
+    /// Points without improvement before the fast search stops
+    #[arg(long)]
+    pub early_stop: Option<usize>,
+    /// Top candidates re-measured after the sweep until they separate (0 disables racing)
+    #[arg(long)]
+    pub race: Option<usize>,
+    /// Time budget for racing, in seconds
+    #[arg(long)]
+    pub race_budget: Option<u64>,
+    /// Acquisition function of the hybrid search
+    #[arg(long, value_enum)]
+    pub acquisition: Option<AcquisitionKind>,
+    /// Points the hybrid search suggests at once
+    #[arg(long)]
+    pub batch: Option<usize>,
+    /// Coarse grid points of the coarse-to-fine search
+    #[arg(long)]
+    pub coarse_points: Option<usize>,
+    /// Named TOPSIS criteria set
+    #[arg(long, value_enum)]
+    pub preset: Option<CriteriaPreset>,
+    /// Explicit TOPSIS criteria, e.g. p99=0.5,mad=0.3,ci_width=0.2 (append :benefit for higher-is-better)
+    #[arg(long, value_delimiter = ',')]
+    pub criteria: Option<Vec<WeightedCriterion>>,
+    /// Derive TOPSIS weights from the data instead of using the configured ones
+    #[arg(long, value_enum)]
+    pub weighting: Option<WeightingMethod>,
+    /// Random weightings for the weight-sensitivity report (0 disables it)
+    #[arg(long)]
+    pub sensitivity: Option<usize>,
+    /// Percentile estimator for P50, P95, P99 and MAD
+    #[arg(long, value_enum)]
+    pub quantiles: Option<QuantileEstimator>,
+    /// Bootstrap resamples for the confidence intervals of P50, P95 and P99 (0 disables them)
+    #[arg(long)]
+    pub bootstrap: Option<usize>,
+    /// Histogram bins per resolution in the results (0 disables the histograms)
+    #[arg(long)]
+    pub histogram_bins: Option<usize>,
+    /// Answer yes to confirmation prompts
+    #[arg(long, short = 'y')]
+    pub yes: bool,
+    /// Do not wait for Enter before starting and before exiting
+    #[arg(long)]
+    pub no_pause: bool,
+    /// Result format written next to results.txt
+    #[arg(long, value_enum, default_value_t = OutputFormat::Text)]
+    pub output_format: OutputFormat,
+    /// Render charts into charts/ (comma-separated: svg,png)
+    #[arg(long, value_enum, value_delimiter = ',')]
+    pub charts: Vec<ChartFormat>,
+    /// File each completed or failed point is appended to, for --resume (asks before replacing an existing file)
+    #[arg(long, value_name = "FILE")]
+    pub checkpoint: Option<PathBuf>,
+    /// Continue an interrupted run from its checkpoint file (method and parameters come from the file)
+    #[arg(long, value_name = "FILE")]
+    pub resume: Option<PathBuf>,
+    /// Directory finished runs are stored in
+    #[arg(long, default_value = HISTORY_DIR)]
+    pub history_dir: PathBuf,
+}
+
+#[derive(Debug, Args)]
+pub struct ReportArgs {
+    /// Interface language (en, uk, ru, zh)
+    #[arg(long, value_parser = parse_language)]
+    pub lang: Option<Language>,
+    /// Results file to read
+    #[arg(long, default_value = "results.txt")]
+    pub input: PathBuf,
+}
+
+#[derive(Debug, Args)]
+pub struct HistoryArgs {
+    /// Interface language (en, uk, ru, zh)
+    #[arg(long, value_parser = parse_language)]
+    pub lang: Option<Language>,
+    /// Directory runs are stored in
+    #[arg(long, default_value = HISTORY_DIR)]
+    pub dir: PathBuf,
+}
+
+#[derive(Debug, Args)]
+pub struct CompareArgs {
+    /// Baseline run: id, id prefix, `latest`, `previous` or path to a results.json
+    pub run_a: String,
+    /// Run compared against the baseline
+    pub run_b: String,
+    /// Interface language (en, uk, ru, zh)
+    #[arg(long, value_parser = parse_language)]
+    pub lang: Option<Language>,
+    /// Directory runs are stored in
+    #[arg(long, default_value = HISTORY_DIR)]
+    pub dir: PathBuf,
+}
+
+fn parse_language(code: &str) -> Result<Language, String> {
+    Language::from_code(code).ok_or_else(|| {
+        let codes: Vec<&str> = Language::all().iter().map(|lang| lang.code()).collect();
+        format!("unknown language '{}', expected one of: {}", code, codes.join(", "))
+    })
+}
+
+impl CommonArgs {
+    fn run_options(&self) -> RunOptions {
+        RunOptions {
+            language: self.lang,
+            config_path: self.config.clone(),
+            ..RunOptions::default()
+        }
+    }
+}
+
+impl From<&RunArgs> for RunOptions {
+    fn from(args: &RunArgs) -> Self {
+        RunOptions {
+            method: args.method,
+            start_value: args.start,
+            end_value: args.end,
+            increment_value: args.step,
+            sample_value: args.samples,
+            early_stop_threshold: args.early_stop,
+            racing_candidates: args.race,
+            racing_budget_secs: args.race_budget,
+            acquisition: args.acquisition,
+            acquisition_batch: args.batch,
+            coarse_points: args.coarse_points,
+            ranking_preset: args.preset,
+            ranking_criteria: args.criteria.clone(),
+            ranking_weighting: args.weighting,
+            sensitivity_samples: args.sensitivity,
+            quantile_estimator: args.quantiles,
+            bootstrap_resamples: args.bootstrap,
+            histogram_bins: args.histogram_bins,
+            assume_yes: args.yes,
+            no_pause: args.no_pause,
+            output_format: args.output_format,
+            chart_formats: args.charts.clone(),
+            checkpoint_path: args.checkpoint.clone(),
+            resume_from: args.resume.clone(),
+            history_dir: Some(args.history_dir.clone()),
+            ..args.common.run_options()
+        }
+    }
+}
+
+impl Cli {
+    /// Dispatch the parsed command
+    pub async fn execute(&self) -> io::Result<()> {
+        match &self.command {
+            None => run_benchmark(&RunOptions::default()).await,
+            Some(Command::Run(args)) => run_benchmark(&args.as_ref().into()).await,
+            Some(Command::Check(args)) => run_check(&args.run_options()).await,
+            Some(Command::Report(args)) => {
+                let localization = Localization::new(args.lang.unwrap_or(Language::English));
+                print_report(&args.input, &localization)
+            },
+            Some(Command::History(args)) => {
+                let localization = Localization::new(args.lang.unwrap_or(Language::English));
+                print_history(&args.dir, &localization)
+            },
+            Some(Command::Compare(args)) => {
+                let localization = Localization::new(args.lang.unwrap_or(Language::English));
+                let run_a = resolve_run(&args.dir, &args.run_a)?;
+                let run_b = resolve_run(&args.dir, &args.run_b)?;
+                compare_runs(&run_a, &run_b, &localization)
+            },
+        }
+    }
+}
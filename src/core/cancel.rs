@@ -0,0 +1,86 @@
+//! Ctrl+C handling
+//!
+//! The first Ctrl+C cancels the token: the search finishes the current point,
+//! ranks what was measured and saves partial results. A second Ctrl+C releases
+//! the timer backend and exits immediately.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+use tokio::task::JoinHandle;
+
+use crate::backend::TimerBackend;
+use crate::ui::localization::{Localization, LocalizationKey};
+
+/// Exit code for a run aborted with Ctrl+C (128 + SIGINT)
+const ABORT_EXIT_CODE: i32 = 130;
+
+/// Shared flag telling the search loops to stop after the current point
+#[derive(Debug, Clone, Default)]
+pub struct CancellationToken {
+    cancelled: Arc<AtomicBool>,
+}
+
+impl CancellationToken {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Request cancellation; returns false if it was already requested
+    pub fn cancel(&self) -> bool {
+        !self.cancelled.swap(true, Ordering::SeqCst)
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::SeqCst)
+    }
+}
+
+/// Listen for Ctrl+C for the rest of the process
+///
+/// Cancel the token on the first press; release `backend` and exit on the next
+/// one, or on the first one once the token is already cancelled (e.g. after the
+/// search has finished).
+pub fn install_ctrl_c_handler(
+    token: CancellationToken,
+    backend: Arc<dyn TimerBackend + Send + Sync>,
+    localization: &Localization,
+) -> JoinHandle<()> {
+    let [stopping, again, aborted, release_failed] = [
+        LocalizationKey::CtrlCStopping,
+        LocalizationKey::CtrlCAgain,
+        LocalizationKey::CtrlCAborted,
+        LocalizationKey::CtrlCReleaseFailed,
+    ].map(|key| localization.get(key));
+    tokio::spawn(async move {
+        while tokio::signal::ctrl_c().await.is_ok() {
+            if token.cancel() {
+                eprintln!("{}", stopping);
+                eprintln!("{}", again);
+                continue;
+            }
+            eprintln!("{}", aborted);
+            if let Err(e) = backend.release() {
+                eprintln!("{}", release_failed.replace("{}", &e.to_string()));
+            }
+            std::process::exit(ABORT_EXIT_CODE);
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn only_the_first_cancel_reports_true() {
+        let token = CancellationToken::new();
+        let shared = token.clone();
+        assert!(!token.is_cancelled());
+        assert!(shared.cancel());
+        assert!(token.is_cancelled());
+        assert!(!token.cancel());
+        assert!(!shared.cancel());
+        assert!(shared.is_cancelled());
+    }
+}
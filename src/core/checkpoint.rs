@@ -0,0 +1,186 @@
+//! Checkpoint file for resuming interrupted runs
+//!
+//! The file is JSON lines: a header with the method and parameters, then one
+//! line per completed `TimerMeasurement` or per point skipped with the reason,
+//! appended and synced as soon as the point finishes. On resume the recorded
+//! points are replayed in place of new ones, failures included, so the search
+//! retraces its path and only measures the points it never reached.
+
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, Error, ErrorKind, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use serde::{Deserialize, Serialize};
+
+use crate::core::{grid_key, BenchmarkingParameters, FailedPoint, OptimizationMethod};
+use crate::stats::timer_measurement::TimerMeasurement;
+
+/// Bumped whenever the record layout changes
+const CHECKPOINT_VERSION: u32 = 2;
+
+/// Method and parameters of the checkpointed run
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CheckpointHeader {
+    pub version: u32,
+    pub method: OptimizationMethod,
+    pub parameters: BenchmarkingParameters,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(tag = "record", rename_all = "lowercase")]
+enum CheckpointRecord {
+    Header(CheckpointHeader),
+    Measurement(TimerMeasurement),
+    Failed(FailedPoint),
+}
+
+/// Outcome of a point recorded in the checkpoint
+#[derive(Debug)]
+pub enum RecordedPoint {
+    Measured(Box<TimerMeasurement>),
+    Failed(FailedPoint),
+}
+
+impl RecordedPoint {
+    fn resolution_ms(&self) -> f64 {
+        match self {
+            RecordedPoint::Measured(m) => m.resolution_ms,
+            RecordedPoint::Failed(f) => f.resolution_ms,
+        }
+    }
+}
+
+/// Appends measured and failed points to a checkpoint file and replays them on resume
+pub struct Checkpoint {
+    path: Option<PathBuf>,
+    file: Mutex<Option<File>>,
+    replay: Mutex<Vec<RecordedPoint>>,
+}
+
+impl Checkpoint {
+    /// Checkpoint that records nothing
+    pub fn disabled() -> Self {
+        Self {
+            path: None,
+            file: Mutex::new(None),
+            replay: Mutex::new(Vec::new()),
+        }
+    }
+
+    /// Start a new checkpoint file, replacing any previous one at `path`
+    pub fn create(path: impl AsRef<Path>, method: OptimizationMethod, parameters: &BenchmarkingParameters) -> io::Result<Self> {
+        let path = path.as_ref();
+        let mut file = File::create(path)?;
+        let header = CheckpointRecord::Header(CheckpointHeader {
+            version: CHECKPOINT_VERSION,
+            method,
+            parameters: parameters.clone(),
+        });
+        write_record(&mut file, &header)?;
+        Ok(Self {
+            path: Some(path.to_path_buf()),
+            file: Mutex::new(Some(file)),
+            replay: Mutex::new(Vec::new()),
+        })
+    }
+
+    /// Open an existing checkpoint file and load its points for replay
+    ///
+    /// A partially written last line (from a crash mid-write) is dropped.
+    pub fn resume(path: impl AsRef<Path>) -> io::Result<(Self, CheckpointHeader)> {
+        let path = path.as_ref();
+        let content = fs::read_to_string(path)?;
+
+        let mut header = None;
+        let mut points = Vec::new();
+        let mut valid_len = 0;
+        let mut lines = content.split_inclusive('\n').peekable();
+        let mut line_no = 0;
+        while let Some(line) = lines.next() {
+            line_no += 1;
+            if line.trim().is_empty() {
+                valid_len += line.len();
+                continue;
+            }
+            match serde_json::from_str::<CheckpointRecord>(line) {
+                Ok(CheckpointRecord::Header(h)) if header.is_none() => header = Some(h),
+                Ok(CheckpointRecord::Header(_)) => {
+                    return Err(Error::new(ErrorKind::InvalidData, format!("{}:{}: duplicate header", path.display(), line_no)));
+                }
+                Ok(CheckpointRecord::Measurement(m)) => points.push(RecordedPoint::Measured(Box::new(m))),
+                Ok(CheckpointRecord::Failed(f)) => points.push(RecordedPoint::Failed(f)),
+                Err(_) if lines.peek().is_none() && header.is_some() => break,
+                Err(e) => {
+                    return Err(Error::new(ErrorKind::InvalidData, format!("{}:{}: {}", path.display(), line_no, e)));
+                }
+            }
+            valid_len += line.len();
+        }
+
+        let header = header.ok_or_else(|| {
+            Error::new(ErrorKind::InvalidData, format!("{} is not a checkpoint file", path.display()))
+        })?;
+        if header.version > CHECKPOINT_VERSION {
+            return Err(Error::new(ErrorKind::InvalidData,
+                format!("unsupported checkpoint version {} (expected at most {})", header.version, CHECKPOINT_VERSION)));
+        }
+
+        let mut file = OpenOptions::new().append(true).open(path)?;
+        if valid_len < content.len() {
+            file.set_len(valid_len as u64)?;
+        }
+        if !content[..valid_len].ends_with('\n') && valid_len > 0 {
+            file.write_all(b"\n")?;
+        }
+
+        let checkpoint = Self {
+            path: Some(path.to_path_buf()),
+            file: Mutex::new(Some(file)),
+            replay: Mutex::new(points),
+        };
+        Ok((checkpoint, header))
+    }
+
+    pub fn path(&self) -> Option<&Path> {
+        self.path.as_deref()
+    }
+
+    /// Number of recorded points not yet replayed
+    pub fn pending(&self) -> usize {
+        self.replay.lock().unwrap().len()
+    }
+
+    /// Take the earliest recorded point at `resolution_ms`, if any
+    pub fn take_recorded(&self, resolution_ms: f64) -> Option<RecordedPoint> {
+        let key = grid_key(resolution_ms);
+        let mut replay = self.replay.lock().unwrap();
+        let index = replay.iter().position(|p| grid_key(p.resolution_ms()) == key)?;
+        Some(replay.remove(index))
+    }
+
+    /// Append a completed measurement and flush it to disk
+    pub fn record(&self, measurement: &TimerMeasurement) -> io::Result<()> {
+        self.append(CheckpointRecord::Measurement(measurement.clone()))
+    }
+
+    /// Append a point skipped because its samples were unusable and flush it to disk
+    pub fn record_failure(&self, failed_point: &FailedPoint) -> io::Result<()> {
+        self.append(CheckpointRecord::Failed(failed_point.clone()))
+    }
+
+    fn append(&self, record: CheckpointRecord) -> io::Result<()> {
+        let mut file = self.file.lock().unwrap();
+        match file.as_mut() {
+            Some(file) => write_record(file, &record),
+            None => Ok(()),
+        }
+    }
+}
+
+fn write_record(file: &mut File, record: &CheckpointRecord) -> io::Result<()> {
+    let mut line = serde_json::to_vec(record)?;
+    line.push(b'\n');
+    file.write_all(&line)?;
+    file.sync_data()
+}
@@ -1,47 +1,120 @@
-use std::io::{self, Error, ErrorKind, Write, Read};
-use std::path::PathBuf;
-use std::process::{Command, Stdio};
-use std::time::Duration;
-use std::{env, fs};
-use tokio::time::{sleep, timeout};
+pub mod cancel;
+pub mod checkpoint;
+pub mod racing;
+pub mod refine;
+
+use std::collections::BTreeMap;
+use std::io::{self, Error, ErrorKind, IsTerminal, Write};
+use std::path::{Path, PathBuf};
+#[cfg(windows)]
+use std::process::Command;
+use std::time::{Duration, SystemTime};
+use std::{env, fmt, fs};
+use tokio::time::sleep;
 use serde::{Deserialize, Serialize};
 use serde_json;
 use os_info;
 use raw_cpuid;
-use std::mem;
-use std::ptr;
-use std::mem::size_of;
-use windows_sys::Win32::Foundation::HANDLE;
-use windows_sys::Win32::Security::{GetTokenInformation, TokenElevation, TOKEN_ELEVATION, TOKEN_QUERY};
-use windows_sys::Win32::System::Threading::{GetCurrentProcess, OpenProcessToken};
 use std::sync::atomic::{AtomicBool, Ordering};
-use std::sync::Once;
+use std::sync::{Arc, Once};
 use indicatif::{ProgressBar, ProgressStyle};
+#[cfg(windows)]
 use std::sync::Mutex;
 
-use crate::stats::robust_statistics::{RobustStatistics, PerformanceWeights};
+use crate::backend::{SleepProbe, TimerBackend};
+use crate::backend::external::{ExternalTimerBackend, force_kill_all_timer_processes, count_timer_processes};
+use crate::backend::{BackendKind, MeasureSleepProbe, SimulatedTimer, SimulationModel};
+#[cfg(target_os = "linux")]
+use crate::backend::TimerSlackBackend;
+use crate::stats::accumulator::SampleAccumulator;
+use crate::stats::distribution::{Histogram, BIMODALITY_THRESHOLD};
+use crate::stats::drift::{analyze_run, suggested_settle_ms, Stationarity};
+use crate::stats::quantile::QuantileEstimator;
+use crate::stats::robust_statistics::{RobustStatistics, StatisticsError};
 use crate::stats::timer_measurement::TimerMeasurement;
 use crate::optimization::bayesian_optimizer::BayesianOptimizer;
-use crate::optimization::topsis::{topsis_ranking, TopsisScore};
+use crate::optimization::acquisition::{ucb_kappa, AcquisitionKind};
+use crate::optimization::criteria::{CriteriaPreset, RankingCriteria, WeightedCriterion, WeightingMethod};
+use crate::optimization::equivalence::{format_resolutions, winner_significance, Verdict, WinnerSignificance, RUNNERS_UP_TESTED};
+use crate::optimization::gaussian_process::SurrogateCurve;
+use crate::optimization::rankers::{compare_rankers, RankerComparison};
+use crate::optimization::sensitivity::{weight_sensitivity, WeightSensitivity};
+use crate::optimization::topsis::{resolve_weights, topsis_ranking, TopsisModel, TopsisScore};
+use crate::report::{print_top_ranking, render_charts, save_csv_results, save_json_results, save_detailed_results, save_to_history};
+use crate::report::history::HISTORY_DIR;
+use crate::report::{ChartFormat, OutputFormat, ResultDocument, RunMetadata};
+use crate::ui::language::Language;
 use crate::ui::localization::{Localization, LocalizationKey, select_language};
 
+pub use cancel::CancellationToken;
+pub use checkpoint::{Checkpoint, RecordedPoint};
+
 // ============================================================================ 
 // CONFIGURATION STRUCTURES
 // ============================================================================
 
-#[derive(Debug, Deserialize, Serialize)]
-struct BenchmarkingParameters {
+/// Benchmark parameters loaded from appsettings.json
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct BenchmarkingParameters {
     #[serde(rename = "StartValue", deserialize_with = "validate_positive_f64")]
-    start_value: f64,
+    pub start_value: f64,
     #[serde(rename = "IncrementValue", deserialize_with = "validate_positive_f64")]
-    increment_value: f64,
+    pub increment_value: f64,
     #[serde(rename = "EndValue", deserialize_with = "validate_positive_f64")]
-    end_value: f64,
+    pub end_value: f64,
     #[serde(rename = "SampleValue", deserialize_with = "validate_positive_i32")]
-    sample_value: i32,
+    pub sample_value: i32,
     // ✅ НОВЫЙ ПАРАМЕТР!
     #[serde(rename = "EarlyStopThreshold", default = "default_early_stop_threshold")]
-    early_stop_threshold: usize,
+    pub early_stop_threshold: usize,
+    // Adaptive racing of the top candidates after the sweep (0 disables it)
+    #[serde(rename = "RacingCandidates", default = "default_racing_candidates")]
+    pub racing_candidates: usize,
+    #[serde(rename = "RacingBudgetSeconds", default = "default_racing_budget_secs")]
+    pub racing_budget_secs: u64,
+    // Acquisition function and batch size of the hybrid search
+    #[serde(rename = "Acquisition", default)]
+    pub acquisition: AcquisitionKind,
+    #[serde(rename = "AcquisitionBatch", default = "default_acquisition_batch")]
+    pub acquisition_batch: usize,
+    // Coarse grid points of the coarse-to-fine search
+    #[serde(rename = "CoarsePoints", default = "default_coarse_points")]
+    pub coarse_points: usize,
+    // TOPSIS criteria: a named preset, or an explicit list that replaces it
+    #[serde(rename = "RankingPreset", default)]
+    pub ranking_preset: CriteriaPreset,
+    #[serde(rename = "RankingCriteria", default, skip_serializing_if = "Vec::is_empty")]
+    pub ranking_criteria: Vec<WeightedCriterion>,
+    #[serde(rename = "RankingWeighting", default)]
+    pub ranking_weighting: WeightingMethod,
+    // Random weightings for the weight-sensitivity report (0 disables it)
+    #[serde(rename = "SensitivitySamples", default)]
+    pub sensitivity_samples: usize,
+    // Percentile estimator and bootstrap resamples for the quantile intervals (0 disables them)
+    #[serde(rename = "QuantileEstimator", default)]
+    pub quantile_estimator: QuantileEstimator,
+    #[serde(rename = "BootstrapResamples", default = "default_bootstrap_resamples")]
+    pub bootstrap_resamples: usize,
+    // Histogram bins per resolution in the final results (0 disables them)
+    #[serde(rename = "HistogramBins", default = "default_histogram_bins")]
+    pub histogram_bins: usize,
+    #[serde(rename = "Backend", default)]
+    pub backend: BackendKind,
+    // Overshoot model for the simulated backend (defaults apply when omitted)
+    #[serde(rename = "Simulation", default, skip_serializing_if = "Option::is_none")]
+    pub simulation: Option<SimulationModel>,
+}
+
+impl BenchmarkingParameters {
+    /// Criteria and weights the results are ranked with
+    pub fn ranking(&self) -> io::Result<RankingCriteria> {
+        let criteria = if self.ranking_criteria.is_empty() {
+            RankingCriteria::from_preset(self.ranking_preset)
+        } else {
+            RankingCriteria::custom(&self.ranking_criteria)?
+        };
+        Ok(criteria.with_weighting(self.ranking_weighting))
+    }
 }
 
 // Default value если параметр отсутствует в JSON
@@ -49,6 +122,30 @@ fn default_early_stop_threshold() -> usize {
     30
 }
 
+fn default_racing_candidates() -> usize {
+    5
+}
+
+fn default_racing_budget_secs() -> u64 {
+    120
+}
+
+fn default_acquisition_batch() -> usize {
+    1
+}
+
+fn default_coarse_points() -> usize {
+    21
+}
+
+fn default_histogram_bins() -> usize {
+    20
+}
+
+fn default_bootstrap_resamples() -> usize {
+    1000
+}
+
 fn validate_positive_f64<'de, D>(deserializer: D) -> Result<f64, D::Error>
 where
     D: serde::Deserializer<'de>,
@@ -73,6 +170,150 @@ where
     }
 }
 
+/// Search method offered in the method menu
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, clap::ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub enum OptimizationMethod {
+    Linear,  // 1. Exhaustive linear search
+    Hybrid,  // 2. LHS + Bayesian optimization
+    Fast,    // 3. Fast linear search with early stopping
+    Refine,  // 4. Coarse grid + golden-section refinement
+}
+
+impl OptimizationMethod {
+    /// Map a menu choice ("1" to "4" or empty for the default)
+    fn from_menu_choice(choice: &str) -> Option<Self> {
+        match choice {
+            "1" => Some(OptimizationMethod::Linear),
+            "2" => Some(OptimizationMethod::Hybrid),
+            "3" | "" => Some(OptimizationMethod::Fast),
+            "4" => Some(OptimizationMethod::Refine),
+            _ => None,
+        }
+    }
+}
+
+/// Options for a benchmark run
+///
+/// `None` values are prompted for when stdin is a terminal and taken from the
+/// config file otherwise.
+#[derive(Debug, Clone)]
+pub struct RunOptions {
+    pub method: Option<OptimizationMethod>,
+    pub start_value: Option<f64>,
+    pub end_value: Option<f64>,
+    pub increment_value: Option<f64>,
+    pub sample_value: Option<i32>,
+    pub early_stop_threshold: Option<usize>,
+    pub racing_candidates: Option<usize>,
+    pub racing_budget_secs: Option<u64>,
+    pub acquisition: Option<AcquisitionKind>,
+    pub acquisition_batch: Option<usize>,
+    pub coarse_points: Option<usize>,
+    pub ranking_preset: Option<CriteriaPreset>,
+    pub ranking_criteria: Option<Vec<WeightedCriterion>>,
+    pub ranking_weighting: Option<WeightingMethod>,
+    pub sensitivity_samples: Option<usize>,
+    pub quantile_estimator: Option<QuantileEstimator>,
+    pub bootstrap_resamples: Option<usize>,
+    pub histogram_bins: Option<usize>,
+    pub language: Option<Language>,
+    pub config_path: PathBuf,
+    pub assume_yes: bool,     // Answer yes to confirmation prompts
+    pub no_pause: bool,       // Skip "press Enter" pauses
+    pub output_format: OutputFormat,
+    pub chart_formats: Vec<ChartFormat>,  // Charts written to charts/
+    pub checkpoint_path: Option<PathBuf>, // Measurements appended as they complete
+    pub resume_from: Option<PathBuf>,     // Checkpoint of an interrupted run to continue
+    pub history_dir: Option<PathBuf>,     // Finished runs are stored here for `compare`
+    pub interactive: bool,    // Prompt for missing values
+}
+
+impl Default for RunOptions {
+    fn default() -> Self {
+        Self {
+            method: None,
+            start_value: None,
+            end_value: None,
+            increment_value: None,
+            sample_value: None,
+            early_stop_threshold: None,
+            racing_candidates: None,
+            racing_budget_secs: None,
+            acquisition: None,
+            acquisition_batch: None,
+            coarse_points: None,
+            ranking_preset: None,
+            ranking_criteria: None,
+            ranking_weighting: None,
+            sensitivity_samples: None,
+            quantile_estimator: None,
+            bootstrap_resamples: None,
+            histogram_bins: None,
+            language: None,
+            config_path: PathBuf::from("appsettings.json"),
+            assume_yes: false,
+            no_pause: false,
+            output_format: OutputFormat::default(),
+            chart_formats: Vec::new(),
+            checkpoint_path: None,
+            resume_from: None,
+            history_dir: Some(PathBuf::from(HISTORY_DIR)),
+            interactive: io::stdin().is_terminal(),
+        }
+    }
+}
+
+// ============================================================================ 
+// PROMPTS
+// ============================================================================
+
+// Prompt behaviour for the current run, set from RunOptions
+static INTERACTIVE: AtomicBool = AtomicBool::new(true);
+static ASSUME_YES: AtomicBool = AtomicBool::new(false);
+static NO_PAUSE: AtomicBool = AtomicBool::new(false);
+
+fn set_prompt_mode(options: &RunOptions) {
+    INTERACTIVE.store(options.interactive, Ordering::Relaxed);
+    ASSUME_YES.store(options.assume_yes, Ordering::Relaxed);
+    NO_PAUSE.store(options.no_pause, Ordering::Relaxed);
+}
+
+fn is_interactive() -> bool {
+    INTERACTIVE.load(Ordering::Relaxed)
+}
+
+/// Ask a yes/no question; `--yes` answers yes, headless runs answer no
+fn confirm(question: &str, default: bool) -> io::Result<bool> {
+    if ASSUME_YES.load(Ordering::Relaxed) {
+        println!("{} y", question);
+        return Ok(true);
+    }
+    if !is_interactive() {
+        println!("{} n (non-interactive, use --yes to confirm)", question);
+        return Ok(false);
+    }
+    print!("{} ", question);
+    io::stdout().flush()?;
+    let mut input = String::new();
+    io::stdin().read_line(&mut input)?;
+    Ok(match input.trim().to_lowercase().as_str() {
+        "y" | "yes" => true,
+        "n" | "no" => false,
+        _ => default,
+    })
+}
+
+/// Wait for Enter unless pauses are disabled or nobody is there to press it
+fn pause(message: &str) -> io::Result<()> {
+    if NO_PAUSE.load(Ordering::Relaxed) || !is_interactive() {
+        return Ok(());
+    }
+    println!("{}", message);
+    io::stdin().read_line(&mut String::new())?;
+    Ok(())
+}
+
 // ============================================================================ 
 // ADMIN PRIVILEGES CHECK
 // ============================================================================
@@ -80,7 +321,14 @@ where
 static IS_ADMIN: AtomicBool = AtomicBool::new(false);
 static INIT: Once = Once::new();
 
+#[cfg(windows)]
 fn is_admin() -> bool {
+    use std::mem::{self, size_of};
+    use std::ptr;
+    use windows_sys::Win32::Foundation::HANDLE;
+    use windows_sys::Win32::Security::{GetTokenInformation, TokenElevation, TOKEN_ELEVATION, TOKEN_QUERY};
+    use windows_sys::Win32::System::Threading::{GetCurrentProcess, OpenProcessToken};
+
     INIT.call_once(|| {
         unsafe {
             let mut token: HANDLE = ptr::null_mut();
@@ -106,14 +354,31 @@ fn is_admin() -> bool {
     IS_ADMIN.load(Ordering::Relaxed)
 }
 
+#[cfg(not(windows))]
+fn is_admin() -> bool {
+    // Elevation only matters for the Windows executables
+    INIT.call_once(|| IS_ADMIN.store(true, Ordering::Relaxed));
+    IS_ADMIN.load(Ordering::Relaxed)
+}
+
 // ============================================================================ 
 // SYSTEM CONFIGURATION
 // ============================================================================
 
+#[cfg(windows)]
 lazy_static::lazy_static! {
     static ref HPET_STATUS: Mutex<Option<String>> = Mutex::new(None);
 }
 
+/// HPET status from the last check, if one was made
+fn cached_hpet_status() -> Option<String> {
+    #[cfg(windows)]
+    return HPET_STATUS.lock().ok().and_then(|status| status.clone());
+    #[cfg(not(windows))]
+    None
+}
+
+#[cfg(windows)]
 fn check_hpet_status(localization: &Localization) -> io::Result<()> {
     let mut status = HPET_STATUS.lock().unwrap();
 
@@ -129,7 +394,7 @@ fn check_hpet_status(localization: &Localization) -> io::Result<()> {
 
     if !output.status.success() {
         eprintln!("{}", localization.get(LocalizationKey::ErrorHpetStatus));
-        return Err(Error::new(ErrorKind::Other, localization.get(LocalizationKey::ErrorHpetStatus)));
+        return Err(Error::other(localization.get(LocalizationKey::ErrorHpetStatus)));
     }
 
     let output_str = String::from_utf8_lossy(&output.stdout);
@@ -160,14 +425,17 @@ fn check_hpet_status(localization: &Localization) -> io::Result<()> {
     if hpet_status == "enabled" {
         println!("{}", localization.get(LocalizationKey::HpetEnabledWarning));
         println!("{}", localization.get(LocalizationKey::HpetTroubleshooting));
-        print!("{}", localization.get(LocalizationKey::HpetDisablePrompt));
-        io::stdout().flush()?;
+        // Boot configuration is never changed without an explicit answer
         let mut input = String::new();
-        io::stdin().read_line(&mut input)?;
+        if is_interactive() {
+            print!("{}", localization.get(LocalizationKey::HpetDisablePrompt));
+            io::stdout().flush()?;
+            io::stdin().read_line(&mut input)?;
+        }
         if input.trim().eq_ignore_ascii_case("y") {
             if let Err(e) = disable_hpet(localization) {
                 eprintln!("{}", localization.get_error_hpet_disable(&e.to_string()));
-                return Err(e.into());
+                return Err(e);
             }
             println!("{}", localization.get(LocalizationKey::HpetDisabledSuccess));
         }
@@ -177,10 +445,11 @@ fn check_hpet_status(localization: &Localization) -> io::Result<()> {
     Ok(())
 }
 
+#[cfg(windows)]
 fn disable_hpet(localization: &Localization) -> io::Result<()> {
     if let Err(e) = apply_registry_tweak(localization) {
         eprintln!("{}", localization.get(LocalizationKey::ErrorRegistryTweak));
-        return Err(e.into());
+        return Err(e);
     }
 
     let commands = vec![
@@ -190,10 +459,9 @@ fn disable_hpet(localization: &Localization) -> io::Result<()> {
 
     for (command, args) in commands {
         let output = Command::new(command).args(&args).output()
-            .map_err(|e| io::Error::new(io::ErrorKind::Other, localization.get_error_hpet_disable(&e.to_string())))?;
+            .map_err(|e| io::Error::other(localization.get_error_hpet_disable(&e.to_string())))?;
         if !output.status.success() {
-            return Err(io::Error::new(
-                io::ErrorKind::Other,
+            return Err(io::Error::other(
                 format!("{} {}", localization.get(LocalizationKey::ErrorHpetDisable), output.status),
             ));
         }
@@ -202,9 +470,10 @@ fn disable_hpet(localization: &Localization) -> io::Result<()> {
     Ok(())
 }
 
+#[cfg(windows)]
 fn apply_registry_tweak(localization: &Localization) -> io::Result<()> {
     let output = Command::new("reg")
-        .args(&[
+        .args([
             "add",
             r"HKEY_LOCAL_MACHINE\SYSTEM\CurrentControlSet\Control\Session Manager\kernel",
             "/v",
@@ -218,8 +487,7 @@ fn apply_registry_tweak(localization: &Localization) -> io::Result<()> {
         .output()?;
 
     if !output.status.success() {
-        return Err(Error::new(
-            ErrorKind::Other,
+        return Err(Error::other(
             localization.get(LocalizationKey::ErrorRegistryTweak),
         ));
     }
@@ -231,85 +499,289 @@ fn apply_registry_tweak(localization: &Localization) -> io::Result<()> {
 // UTILITY FUNCTIONS
 // ============================================================================
 
-/// Parse MeasureSleep.exe output including resolution verification
-/// Example input: "Resolution: 0.5186ms, Sleep(1) slept 1.0310ms (delta: 0.0310)"
-/// Returns: (delta_ms, stdev_ms, actual_resolution_ms)
-fn parse_measurement_output_with_resolution(output: &[u8]) -> io::Result<(f64, f64, Option<f64>)> {
-    let output_str = std::str::from_utf8(output).map_err(|e| Error::new(ErrorKind::InvalidData, format!("UTF-8 decode error: {}", e)))?;
+fn cleanup_processes() -> io::Result<()> {
+    // Placeholder for actual process cleanup implementation
+    Ok(())
+}
+
+// ============================================================================ 
+// OPTIMIZATION FUNCTIONS
+// ============================================================================
+
+pub struct OptimizationResult {
+    pub optimal_resolution: f64,
+    pub topsis_score: f64,
+    pub(crate) aggregated_measurements: Vec<TimerMeasurement>,
+    pub(crate) topsis_rankings: Vec<TopsisScore>,
+    pub partial: bool,  // Search was cancelled before it finished
+    pub significance: Option<WinnerSignificance>,
+    pub rankers: Option<RankerComparison>,  // Winners of the alternative rankers and the Pareto front
+    pub sensitivity: Option<WeightSensitivity>,  // Robustness of the winner to the weights
+    pub surrogate: Option<SurrogateCurve>,  // GP posterior of the hybrid and coarse-to-fine searches
+    pub criteria: RankingCriteria,          // Criteria and weights of the ranking
+    pub failed_points: Vec<FailedPoint>,    // Points skipped because their samples were unusable
+}
 
-    let mut avg = None;
-    let mut stdev = None;
-    let mut resolution_ms = None;
+impl OptimizationResult {
+    /// TOPSIS ranking of every aggregated resolution, best first
+    pub fn rankings(&self) -> &[TopsisScore] {
+        &self.topsis_rankings
+    }
+}
 
-    // Parse line by line
-    for line in output_str.lines() {
-        let trimmed = line.trim();
-        
-        // Parse line: "Resolution: 0.5186ms, Sleep(1) slept 1.0310ms (delta: 0.0310)"
-        if resolution_ms.is_none() && trimmed.contains("Resolution: ") {
-            // Extract "0.5186" from "Resolution: 0.5186ms"
-            if let Some(res_part) = trimmed.split("Resolution: ").nth(1) {
-                // Take everything before first "ms"
-                if let Some(res_str) = res_part.split("ms").next() {
-                    resolution_ms = res_str.trim().parse::<f64>().ok();
+#[cfg(test)]
+impl OptimizationResult {
+    /// Finished, uncancelled result ranking `measurements` on the default criteria
+    pub(crate) fn ranked(measurements: Vec<TimerMeasurement>) -> Self {
+        let criteria = RankingCriteria::default();
+        let topsis_rankings = topsis_ranking(&measurements, &criteria);
+        Self {
+            optimal_resolution: topsis_rankings[0].resolution_ms,
+            topsis_score: topsis_rankings[0].closeness_coefficient,
+            aggregated_measurements: measurements,
+            topsis_rankings,
+            partial: false,
+            significance: None,
+            rankers: None,
+            sensitivity: None,
+            surrogate: None,
+            criteria,
+            failed_points: Vec::new(),
+        }
+    }
+}
+
+/// Point whose samples could not be summarised; the search skipped it
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FailedPoint {
+    pub resolution_ms: f64,
+    pub reason: String,
+}
+
+impl fmt::Display for FailedPoint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.reason)
+    }
+}
+
+/// Error of a failed point replayed from a checkpoint
+impl std::error::Error for FailedPoint {}
+
+/// Run the benchmark: system checks, parameters, search and result saving
+pub async fn run_benchmark(options: &RunOptions) -> io::Result<()> {
+    set_prompt_mode(options);
+    let localization = select_localization(options);
+
+    print_system_overview(&localization)?;
+
+    let (optimization_method, parameters, checkpoint) = match &options.resume_from {
+        Some(path) => {
+            // Method and parameters must match the interrupted run for its path to be retraced
+            let (checkpoint, header) = Checkpoint::resume(path)?;
+            println!("{}", localization.get_resuming_run(
+                &format!("{:?}", header.method), &path.display().to_string(), checkpoint.pending()));
+            println!("{}\n", localization.get_resuming_range(
+                header.parameters.start_value, header.parameters.end_value,
+                header.parameters.increment_value, header.parameters.sample_value));
+            (header.method, header.parameters, checkpoint)
+        },
+        None => {
+            let optimization_method = match options.method {
+                Some(method) => method,
+                None if is_interactive() => prompt_optimization_method(&localization)?,
+                None => OptimizationMethod::Fast,
+            };
+            let parameters = load_parameters(options, optimization_method, &localization)?;
+            let checkpoint = match &options.checkpoint_path {
+                Some(path) => {
+                    if path.exists() && !confirm(&format!("{} already exists. Overwrite it? (y/N):", path.display()), false)? {
+                        return Err(Error::new(ErrorKind::AlreadyExists,
+                            format!("{} already exists; continue it with --resume or choose another --checkpoint", path.display())));
+                    }
+                    Checkpoint::create(path, optimization_method, &parameters)?
+                },
+                None => Checkpoint::disabled(),
+            };
+            (optimization_method, parameters, checkpoint)
+        },
+    };
+
+    let (shared_backend, probe) = prepare_timer_backend(&parameters, &localization).await?;
+    let (backend, probe) = (shared_backend.as_ref(), probe.as_ref());
+    
+    pause(localization.get(LocalizationKey::PressEnter))?;
+
+    let cancel = CancellationToken::new();
+    cancel::install_ctrl_c_handler(cancel.clone(), shared_backend.clone(), &localization);
+
+    let started_at = SystemTime::now();
+    let result = match optimization_method {
+        OptimizationMethod::Linear => {
+            match linear_exhaustive_search(
+                &parameters,
+                backend,
+                probe,
+                &localization,
+                &checkpoint,
+                &cancel,
+            ).await {
+                Ok(r) => r,
+                Err(e) => {
+                    eprintln!("{}", localization.get_error_linear_search(&e.to_string()));
+                    backend.release()?;
+                    return Err(e);
                 }
             }
+        },
+        OptimizationMethod::Hybrid => {
+            match optimize_timer_resolution(
+                &parameters,
+                backend,
+                probe,
+                &localization,
+                &checkpoint,
+                &cancel,
+            ).await {
+                Ok(r) => r,
+                Err(e) => {
+                    eprintln!("{}", localization.get_error_optimization(&e.to_string()));
+                    backend.release()?;
+                    return Err(e);
+                }
+            }
+        },
+        OptimizationMethod::Fast => {
+            // ✅ NEW: FAST LINEAR SEARCH (recommended, early stopping + 1 run)
+            match fast_linear_search(
+                &parameters,
+                backend,
+                probe,
+                &localization,
+                &checkpoint,
+                &cancel,
+            ).await {
+                Ok(r) => r,
+                Err(e) => {
+                    eprintln!("\n❌ FAST LINEAR SEARCH FAILED: {}", e);
+                    backend.release()?;
+                    return Err(e);
+                }
+            }
+        },
+        OptimizationMethod::Refine => {
+            match refine::coarse_to_fine_search(
+                &parameters,
+                backend,
+                probe,
+                &localization,
+                &checkpoint,
+                &cancel,
+            ).await {
+                Ok(r) => r,
+                Err(e) => {
+                    eprintln!("\n❌ COARSE-TO-FINE SEARCH FAILED: {}", e);
+                    backend.release()?;
+                    return Err(e);
+                }
+            }
+        },
+    };
+
+    let result = racing::race_top_candidates(
+        result,
+        &parameters,
+        backend,
+        probe,
+        &localization,
+        &checkpoint,
+        &cancel,
+    ).await?;
+
+    // Nothing left to stop: from here on Ctrl+C aborts straight away
+    cancel.cancel();
+    backend.release()?;
+    if result.partial {
+        println!("⚠️  Run cancelled: the ranking above covers only the points measured so far");
+    }
+    if !result.failed_points.is_empty() {
+        let skipped: Vec<f64> = result.failed_points.iter().map(|f| f.resolution_ms).collect();
+        println!("⚠️  {} point(s) skipped because their samples were unusable: {} ms",
+            skipped.len(), format_resolutions(&skipped));
+    }
+    print_distribution(&result, &localization);
+    print_stationarity(&result, &localization);
+
+    save_detailed_results(&result, "results.txt")?;
+    let metadata = RunMetadata::new(optimization_method, backend, probe, started_at, cached_hpet_status());
+    match options.output_format {
+        OutputFormat::Text => {},
+        OutputFormat::Json => {
+            save_json_results(&result, &parameters, &metadata, "results.json")?;
+            println!("   JSON results saved to results.json");
+        },
+        OutputFormat::Csv => {
+            save_csv_results(&result, &parameters, &metadata, "results.csv")?;
+            println!("   CSV results saved to results.csv, results_samples.csv and results.meta.json");
+        },
+    }
+    if let Some(history_dir) = &options.history_dir {
+        let document = ResultDocument::new(&result, &parameters, &metadata);
+        match save_to_history(&document, history_dir) {
+            Ok(id) => println!("   Run saved to history as {}", id),
+            Err(e) => eprintln!("⚠️ Failed to save run history: {}", e),
         }
-        
-        // Parse line: "Avg: 0.1439"
-        if avg.is_none() && trimmed.starts_with("Avg: ") {
-            avg = trimmed[5..].trim().parse().ok();
-        }
-        
-        // Parse line: "STDEV: 0.0029"
-        if stdev.is_none() && trimmed.starts_with("STDEV: ") {
-            stdev = trimmed[7..].trim().parse().ok();
-        }
-        
-        // Optimization: exit if everything is found
-        if avg.is_some() && stdev.is_some() && resolution_ms.is_some() {
-            break;
+    }
+    if !options.chart_formats.is_empty() {
+        match render_charts(&result, "charts", &options.chart_formats) {
+            Ok(files) => println!("   {} charts saved to charts/", files.len()),
+            Err(e) => eprintln!("⚠️ Chart rendering failed: {}", e),
         }
     }
+    println!("{}", localization.get(LocalizationKey::BenchmarkComplete));
 
-    match (avg, stdev) {
-        (Some(a), Some(s)) => Ok((a, s, resolution_ms)),
-        _ => {
-            eprintln!("Failed to parse MeasureSleep output:");
-            eprintln!("{}", output_str);
-            Err(Error::new(ErrorKind::InvalidData,"Invalid MeasureSleep output format"))
-        }
+    if let Err(e) = cleanup_processes() {
+        eprintln!("{}", localization.get_warning_cleanup(&e.to_string()));
     }
-}
 
-// Return the old function for compatibility with the rest of the code
-fn parse_measurement_output(output: &[u8]) -> io::Result<(f64, f64)> {
-    let (avg, stdev, _) = parse_measurement_output_with_resolution(output)?;
-    Ok((avg, stdev))
-}
+    pause(localization.get(LocalizationKey::GetExitPrompt))?;
 
-fn cleanup_processes() -> io::Result<()> {
-    // Placeholder for actual process cleanup implementation
     Ok(())
 }
 
-// ============================================================================ 
-// OPTIMIZATION FUNCTIONS
-// ============================================================================
+/// Run only the system checks and the timer backend self-test
+pub async fn run_check(options: &RunOptions) -> io::Result<()> {
+    set_prompt_mode(options);
+    let localization = select_localization(options);
 
-pub struct OptimizationResult {
-    pub optimal_resolution: f64,
-    pub topsis_score: f64,
-    aggregated_measurements: Vec<TimerMeasurement>,
-    topsis_rankings: Vec<TopsisScore>,
+    print_system_overview(&localization)?;
+
+    let parameters = read_parameters(&options.config_path, &localization)?;
+    let (backend, _probe) = prepare_timer_backend(&parameters, &localization).await?;
+    if let Some(limits) = backend.resolution_limits()? {
+        println!("   Supported range: [{:.4}, {:.4}] ms", limits.finest_ms, limits.coarsest_ms);
+    }
+    if let Some(current) = backend.current_resolution()? {
+        println!("   Current resolution: {:.4} ms", current);
+    }
+    backend.release()?;
+    println!("✅ All checks passed");
+    Ok(())
+}
+
+/// Use the language from the options, asking for it only in interactive runs
+fn select_localization(options: &RunOptions) -> Localization {
+    let language = match options.language {
+        Some(language) => language,
+        None if is_interactive() => select_language(),
+        None => Language::English,
+    };
+    Localization::new(language)
 }
 
-pub async fn run_benchmark() -> io::Result<()> {
+/// Print the title, privileges, system information and HPET status
+fn print_system_overview(localization: &Localization) -> io::Result<()> {
     use colored::*;
 
-    let selected_language = select_language();
-    let localization = Localization::new(selected_language);
-    
     let separator = "=".repeat(60);
     
     println!("\n{}", separator);
@@ -347,8 +819,17 @@ pub async fn run_benchmark() -> io::Result<()> {
 
     println!("{}", localization.get(LocalizationKey::SystemConfig).bold().yellow());
     println!("━━━━━━━━━━━━━━━━━━━━");
-    check_hpet_status(&localization)?;
+    #[cfg(windows)]
+    check_hpet_status(localization)?;
+    #[cfg(not(windows))]
+    println!("   HPET check skipped (Windows only)");
     println!();
+    Ok(())
+}
+
+/// Show the method menu and read the choice
+fn prompt_optimization_method(localization: &Localization) -> io::Result<OptimizationMethod> {
+    use colored::*;
 
     println!("{}", localization.get(LocalizationKey::OptimizationMethod).bold().yellow());
     println!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
@@ -372,91 +853,194 @@ pub async fn run_benchmark() -> io::Result<()> {
     println!("     • {}", localization.get(LocalizationKey::FastLinearMethodDesc3));
     println!("     • {}", localization.get(LocalizationKey::FastLinearMethodDesc4));
     println!();
+    println!("  {}  {}", "4.".bold().cyan(), localization.get(LocalizationKey::RefineMethod).bold());
+    println!("     • {}", localization.get(LocalizationKey::RefineMethodDesc1));
+    println!("     • {}", localization.get(LocalizationKey::RefineMethodDesc2));
+    println!("     • {}", localization.get(LocalizationKey::RefineMethodDesc3));
+    println!();
     let mut method_input = String::new();
     print!("{}", localization.get(LocalizationKey::MethodChoice));
     io::stdout().flush()?;
     io::stdin().read_line(&mut method_input)?;
-    let optimization_method = method_input.trim();
     println!();
 
-    let parameters = match fs::read_to_string("appsettings.json")
+    OptimizationMethod::from_menu_choice(method_input.trim()).ok_or_else(|| {
+        eprintln!("{}", localization.get(LocalizationKey::ErrorInvalidMethod));
+        Error::new(ErrorKind::InvalidInput, "Invalid method")
+    })
+}
+
+/// Read benchmark parameters from the config file
+fn read_parameters(config_path: &Path, localization: &Localization) -> io::Result<BenchmarkingParameters> {
+    fs::read_to_string(config_path)
         .and_then(|content| serde_json::from_str::<BenchmarkingParameters>(&content)
             .map_err(|e| Error::new(ErrorKind::InvalidData, e)))
-    {
-        Ok(mut params) => {
-            let mut input = String::new();
-            let mut prompt = |desc: &str, current: &str| -> io::Result<Option<String>> {
-                println!("▸ {}: {}{}", desc, current, localization.get(LocalizationKey::KeepCurrent));
-                println!("{}", localization.get(LocalizationKey::EnterNewValue));
-                input.clear();
-                io::stdin().read_line(&mut input)?;
-                let trimmed = input.trim();
-                Ok(if trimmed.is_empty() { None } else { Some(trimmed.to_string()) })
-            };
+        .inspect_err(|e| eprintln!("{}", localization.get_error_configuration(&e.to_string())))
+}
 
-            println!("{}", localization.get(LocalizationKey::BenchmarkParams));
-            println!("━━━━━━━━━━━━━━━━━━━");
+/// Load parameters, apply command-line overrides and prompt for the rest when interactive
+fn load_parameters(
+    options: &RunOptions,
+    optimization_method: OptimizationMethod,
+    localization: &Localization,
+) -> io::Result<BenchmarkingParameters> {
+    let mut params = read_parameters(&options.config_path, localization)?;
+    let mut edited = false;
+
+    let mut input = String::new();
+    let mut prompt = |desc: &str, current: &str, overridden: bool| -> io::Result<Option<String>> {
+        println!("▸ {}: {}{}", desc, current, localization.get(LocalizationKey::KeepCurrent));
+        if overridden || !is_interactive() {
+            return Ok(None);
+        }
+        println!("{}", localization.get(LocalizationKey::EnterNewValue));
+        input.clear();
+        io::stdin().read_line(&mut input)?;
+        let trimmed = input.trim();
+        Ok(if trimmed.is_empty() { None } else { Some(trimmed.to_string()) })
+    };
 
-            if let Some(new_value) = prompt(&localization.get(LocalizationKey::StartValue), &format!("{:.4} ms", params.start_value))? {
-                params.start_value = new_value.parse().map_err(|e| Error::new(ErrorKind::InvalidInput, e))?;
-            }
-            if optimization_method == "1" {
-                if let Some(new_value) = prompt(&localization.get(LocalizationKey::IncrementValue), &format!("{:.4} ms", params.increment_value))? {
-                    params.increment_value = new_value.parse().map_err(|e| Error::new(ErrorKind::InvalidInput, e))?;
-                }
-            } else {
-                println!("▸ {}: {:.4} ms {}", localization.get(LocalizationKey::IncrementValue), params.increment_value, localization.get(LocalizationKey::IncrementNotUsed));
-            }
-            if let Some(new_value) = prompt(&localization.get(LocalizationKey::EndValue), &format!("{:.4} ms", params.end_value))? {
-                params.end_value = new_value.parse().map_err(|e| Error::new(ErrorKind::InvalidInput, e))?;
-            }
-            if let Some(new_value) = prompt(&localization.get(LocalizationKey::SampleValue), &params.sample_value.to_string())? {
-                params.sample_value = new_value.parse().map_err(|e| Error::new(ErrorKind::InvalidInput, e))?;
-            }
+    if let Some(value) = options.start_value { params.start_value = value; }
+    if let Some(value) = options.increment_value { params.increment_value = value; }
+    if let Some(value) = options.end_value { params.end_value = value; }
+    if let Some(value) = options.sample_value { params.sample_value = value; }
+    if let Some(value) = options.early_stop_threshold { params.early_stop_threshold = value; }
+    if let Some(value) = options.racing_candidates { params.racing_candidates = value; }
+    if let Some(value) = options.racing_budget_secs { params.racing_budget_secs = value; }
+    if let Some(value) = options.acquisition { params.acquisition = value; }
+    if let Some(value) = options.acquisition_batch { params.acquisition_batch = value; }
+    if let Some(value) = options.coarse_points { params.coarse_points = value; }
+    if let Some(value) = options.ranking_preset {
+        // A preset chosen on the command line replaces criteria listed in the config
+        params.ranking_preset = value;
+        params.ranking_criteria.clear();
+    }
+    if let Some(value) = &options.ranking_criteria { params.ranking_criteria = value.clone(); }
+    if let Some(value) = options.ranking_weighting { params.ranking_weighting = value; }
+    if let Some(value) = options.sensitivity_samples { params.sensitivity_samples = value; }
+    if let Some(value) = options.quantile_estimator { params.quantile_estimator = value; }
+    if let Some(value) = options.bootstrap_resamples { params.bootstrap_resamples = value; }
+    if let Some(value) = options.histogram_bins { params.histogram_bins = value; }
+
+    println!("{}", localization.get(LocalizationKey::BenchmarkParams));
+    println!("━━━━━━━━━━━━━━━━━━━");
 
-            // ✅ НОВЫЙ БЛОК: Early Stop Threshold (только для метода 3)
-            if optimization_method == "3" {
-                println!("\n▸ Early Stop Threshold: {} (current)", params.early_stop_threshold);
-                println!("   • 30:  Fast (~5 min, checks ~60-150 points)");
-                println!("   • 50:  Balanced (~10 min, checks ~100-200 points)");
-                println!("   • 100: Thorough (~20 min, checks ~200-300 points)");
-                println!("   • 1000: No early stop (full search, ~33 min)");
-                if let Some(new_value) = prompt(&localization.get(LocalizationKey::EarlyStopThreshold),
-                    &params.early_stop_threshold.to_string())? {
-                    let parsed: usize = new_value.parse().map_err(|e| Error::new(ErrorKind::InvalidInput, e))?;
-                    if parsed < 10 {
-                        eprintln!("⚠️ Warning: Threshold < 10 may stop too early");
-                    }
-                    params.early_stop_threshold = parsed;
-                }
-            }
+    if let Some(new_value) = prompt(localization.get(LocalizationKey::StartValue), &format!("{:.4} ms", params.start_value), options.start_value.is_some())? {
+        params.start_value = new_value.parse().map_err(|e| Error::new(ErrorKind::InvalidInput, e))?;
+        edited = true;
+    }
+    if optimization_method == OptimizationMethod::Linear {
+        if let Some(new_value) = prompt(localization.get(LocalizationKey::IncrementValue), &format!("{:.4} ms", params.increment_value), options.increment_value.is_some())? {
+            params.increment_value = new_value.parse().map_err(|e| Error::new(ErrorKind::InvalidInput, e))?;
+            edited = true;
+        }
+    } else {
+        println!("▸ {}: {:.4} ms {}", localization.get(LocalizationKey::IncrementValue), params.increment_value, localization.get(LocalizationKey::IncrementNotUsed));
+    }
+    if let Some(new_value) = prompt(localization.get(LocalizationKey::EndValue), &format!("{:.4} ms", params.end_value), options.end_value.is_some())? {
+        params.end_value = new_value.parse().map_err(|e| Error::new(ErrorKind::InvalidInput, e))?;
+        edited = true;
+    }
+    if let Some(new_value) = prompt(localization.get(LocalizationKey::SampleValue), &params.sample_value.to_string(), options.sample_value.is_some())? {
+        params.sample_value = new_value.parse().map_err(|e| Error::new(ErrorKind::InvalidInput, e))?;
+        edited = true;
+    }
 
-            match optimization_method {
-                "1" => {
-                    let iterations = ((params.end_value - params.start_value) / params.increment_value).ceil();
-                    println!("▸ {}\n", localization.get_iterations_linear(iterations as i32));
-                },
-                _ => {
-                    println!("▸ {}\n", localization.get(LocalizationKey::IterationsHybrid));
-                }
-            }
+    // ✅ НОВЫЙ БЛОК: Early Stop Threshold (только для метода 3)
+    if optimization_method == OptimizationMethod::Fast {
+        println!("\n▸ Early Stop Threshold: {} (current)", params.early_stop_threshold);
+        println!("   • 30:  Fast (~5 min, checks ~60-150 points)");
+        println!("   • 50:  Balanced (~10 min, checks ~100-200 points)");
+        println!("   • 100: Thorough (~20 min, checks ~200-300 points)");
+        println!("   • 1000: No early stop (full search, ~33 min)");
+        if let Some(new_value) = prompt(localization.get(LocalizationKey::EarlyStopThreshold),
+            &params.early_stop_threshold.to_string(), options.early_stop_threshold.is_some())? {
+            params.early_stop_threshold = new_value.parse().map_err(|e| Error::new(ErrorKind::InvalidInput, e))?;
+            edited = true;
+        }
+        if params.early_stop_threshold < 10 {
+            eprintln!("⚠️ Warning: Threshold < 10 may stop too early");
+        }
+    }
 
-            if let Err(e) = fs::write("appsettings.json", serde_json::to_string_pretty(&params)?) {
-                eprintln!("{}", localization.get_error_save_parameters(&e.to_string()));
-            }
+    for (name, value) in [("start", params.start_value), ("end", params.end_value), ("step", params.increment_value)] {
+        if value <= 0.0 {
+            return Err(Error::new(ErrorKind::InvalidInput, format!("{} value must be positive", name)));
+        }
+    }
+    if params.sample_value <= 0 {
+        return Err(Error::new(ErrorKind::InvalidInput, "Sample value must be positive"));
+    }
+    let criteria = params.ranking()?;
+    println!("{}", localization.get_ranking_criteria(&criteria));
+    match params.bootstrap_resamples {
+        0 => println!("▸ Quantiles: {}, mean CI: Student t", params.quantile_estimator.name()),
+        resamples => println!("▸ Quantiles: {}, mean CI: Student t, quantile CIs: bootstrap ({} resamples)",
+            params.quantile_estimator.name(), resamples),
+    }
 
-            params
+    match optimization_method {
+        OptimizationMethod::Linear => {
+            let iterations = ((params.end_value - params.start_value) / params.increment_value).ceil();
+            println!("▸ {}\n", localization.get_iterations_linear(iterations as i32));
         },
-        Err(e) => {
-            eprintln!("{}", localization.get_error_configuration(&e.to_string()));
-            return Err(e);
+        OptimizationMethod::Refine => {
+            println!("▸ Coarse grid: {} points, then golden-section refinement\n", params.coarse_points);
+        },
+        _ => {
+            println!("▸ {}\n", localization.get(LocalizationKey::IterationsHybrid));
         }
-    };
+    }
+
+    if edited {
+        if let Err(e) = fs::write(&options.config_path, serde_json::to_string_pretty(&params)?) {
+            eprintln!("{}", localization.get_error_save_parameters(&e.to_string()));
+        }
+    }
+
+    Ok(params)
+}
+
+/// Timer backend and the sleep probe measuring under it
+type TimerRig = (Arc<dyn TimerBackend + Send + Sync>, Box<dyn SleepProbe>);
 
+/// Create the backend selected in appsettings.json and check that it can measure
+async fn prepare_timer_backend(params: &BenchmarkingParameters, localization: &Localization) -> io::Result<TimerRig> {
+    match params.backend.resolve() {
+        BackendKind::External => {
+            let (backend, probe) = prepare_external_timer(params, localization).await?;
+            Ok((Arc::new(backend), Box::new(probe)))
+        },
+        BackendKind::TimerSlack => prepare_timer_slack(params, localization),
+        BackendKind::Simulated => {
+            let model = params.simulation.clone().unwrap_or_default();
+            let timer = Arc::new(SimulatedTimer::new(model));
+            println!("\n🔍 Timer backend: {} (seed {})", TimerBackend::name(&timer), timer.model().seed);
+            println!("━━━━━━━━━━━━━━━━━━━━━");
+            println!("   Ground-truth optimum: {:.4} ms",
+                timer.model().ground_truth(params.start_value, params.end_value, params.increment_value));
+            test_probe(timer.as_ref(), params.quantile_estimator, localization)?;
+            Ok((timer.clone(), Box::new(timer)))
+        },
+        BackendKind::Auto => unreachable!("BackendKind::resolve never returns Auto"),
+    }
+}
+
+/// Take a few samples to make sure the probe works before the benchmark starts
+fn test_probe(probe: &dyn SleepProbe, estimator: QuantileEstimator, localization: &Localization) -> io::Result<()> {
+    let test_report = probe.sample(5)?;
+    let test_stats = RobustStatistics::try_from_samples(test_report.deltas_ms, estimator)?;
+    println!("{}", localization.get_test_passed(test_stats.mean, test_stats.stdev));
+    println!();
+    Ok(())
+}
+
+/// Locate and verify the bundled executables, then clean up leftover SetTimerResolution.exe instances
+async fn prepare_external_timer(params: &BenchmarkingParameters, localization: &Localization) -> io::Result<(ExternalTimerBackend, MeasureSleepProbe)> {
     let exe_dir = env::current_exe()?.parent()
         .ok_or_else(|| {
             eprintln!("{}", localization.get(LocalizationKey::ErrorGetExePath));
-            Error::new(ErrorKind::Other, localization.get(LocalizationKey::ErrorGetExePath))
+            Error::other(localization.get(LocalizationKey::ErrorGetExePath))
         })?
         .to_path_buf();
 
@@ -489,17 +1073,12 @@ pub async fn run_benchmark() -> io::Result<()> {
     println!();
 
     println!("{}", localization.get(LocalizationKey::MeasureSleepTest));
-    let test_output = Command::new(&measure_sleep_path)
-        .arg("--samples")
-        .arg("5")
-        .output()?;
-    if !test_output.status.success() {
+    let probe = MeasureSleepProbe::new(measure_sleep_path, localization.language);
+    let test_report = probe.sample(5).inspect_err(|_| {
         eprintln!("{}", localization.get(LocalizationKey::ErrorMeasureSleep));
-        eprintln!("{}", String::from_utf8_lossy(&test_output.stderr));
-        return Err(Error::new(ErrorKind::Other, "MeasureSleep.exe failed"));
-    }
-    let (test_delta, test_stdev) = parse_measurement_output(&test_output.stdout)?;
-    println!("{}", localization.get_test_passed(test_delta, test_stdev));
+    })?;
+    let test_stats = RobustStatistics::try_from_samples(test_report.deltas_ms, params.quantile_estimator)?;
+    println!("{}", localization.get_test_passed(test_stats.mean, test_stats.stdev));
 
     println!("{}", localization.get(LocalizationKey::CleaningUp));
     force_kill_all_timer_processes()?;
@@ -513,96 +1092,45 @@ pub async fn run_benchmark() -> io::Result<()> {
         eprintln!("{}", localization.get(LocalizationKey::ManualCleanup2));
         eprintln!("{}", localization.get(LocalizationKey::ManualCleanup3));
         eprintln!("{}", localization.get(LocalizationKey::ManualCleanup4));
-        return Err(Error::new(ErrorKind::Other, localization.get(LocalizationKey::ErrorCannotProceed)));
+        return Err(Error::other(localization.get(LocalizationKey::ErrorCannotProceed)));
     }
     println!("{}", localization.get(LocalizationKey::CleanupCompleted));
-    
-    prompt_user(&localization.get(LocalizationKey::PressEnter))?;
-    
-    fn prompt_user(message: &str) -> io::Result<()> {
-        println!("{}", message);
-        io::stdin().read_line(&mut String::new())?;
-        Ok(())
-    }
 
-    let result = match optimization_method {
-        "1" => {
-            match linear_exhaustive_search(
-                &parameters,
-                &set_timer_resolution_path,
-                &measure_sleep_path,
-                &localization,
-            ).await {
-                Ok(r) => r,
-                Err(e) => {
-                    eprintln!("{}", localization.get_error_linear_search(&e.to_string()));
-                    kill_all_timer_processes()?;
-                    return Err(e);
-                }
-            }
-        },
-        "2" => {
-            match optimize_timer_resolution(
-                &parameters,
-                &set_timer_resolution_path,
-                &measure_sleep_path,
-                &localization,
-            ).await {
-                Ok(r) => r,
-                Err(e) => {
-                    eprintln!("{}", localization.get_error_optimization(&e.to_string()));
-                    kill_all_timer_processes()?;
-                    return Err(e);
-                }
-            }
-        },
-        "3" | "" => {
-            // ✅ NEW: FAST LINEAR SEARCH (recommended, early stopping + 1 run)
-            match fast_linear_search(
-                &parameters,
-                &set_timer_resolution_path,
-                &measure_sleep_path,
-                &localization,
-            ).await {
-                Ok(r) => r,
-                Err(e) => {
-                    eprintln!("\n❌ FAST LINEAR SEARCH FAILED: {}", e);
-                    kill_all_timer_processes()?;
-                    return Err(e);
-                }
-            }
-        },
-        _ => {
-            eprintln!("{}", localization.get(LocalizationKey::ErrorInvalidMethod));
-            return Err(Error::new(ErrorKind::InvalidInput, "Invalid method"));
-        }
-    };
-
-    save_detailed_results(&result, "results.txt")?;
-    println!("{}", localization.get(LocalizationKey::BenchmarkComplete));
 
-    if let Err(e) = cleanup_processes() {
-        eprintln!("{}", localization.get_warning_cleanup(&e.to_string()));
-    }
+    Ok((ExternalTimerBackend::new(set_timer_resolution_path, localization.language), probe))
+}
 
-    prompt_exit(&localization)?;
-    
-    fn prompt_exit(localization: &Localization) -> io::Result<()> {
-        println!("{}", localization.get(LocalizationKey::GetExitPrompt));
-        io::stdin().read_line(&mut String::new())?;
-        Ok(())
+/// Verify that clock_nanosleep can be sampled with timer slack on the probe thread
+#[cfg(target_os = "linux")]
+fn prepare_timer_slack(params: &BenchmarkingParameters, localization: &Localization) -> io::Result<TimerRig> {
+    let timer_slack = Arc::new(TimerSlackBackend::new());
+    println!("\n🔍 Timer backend: {} + {}", TimerBackend::name(&timer_slack), SleepProbe::name(&timer_slack));
+    println!("━━━━━━━━━━━━━━━━━━━━━");
+    if let Some(current) = timer_slack.current_resolution()? {
+        println!("   Default timer slack: {:.4} ms", current);
     }
+    test_probe(timer_slack.as_ref(), params.quantile_estimator, localization)?;
+    Ok((timer_slack.clone(), Box::new(timer_slack)))
+}
 
-    Ok(())
+#[cfg(not(target_os = "linux"))]
+fn prepare_timer_slack(_params: &BenchmarkingParameters, _localization: &Localization) -> io::Result<TimerRig> {
+    Err(Error::new(ErrorKind::Unsupported, "The timer-slack backend is only available on Linux"))
 }
 
-async fn optimize_timer_resolution(
+/// Points at which the surrogate posterior of the GP-based searches is reported
+const SURROGATE_CURVE_POINTS: usize = 200;
+
+pub async fn optimize_timer_resolution(
     params: &BenchmarkingParameters,
-    set_timer_path: &PathBuf,
-    measure_sleep_path: &PathBuf,
+    backend: &dyn TimerBackend,
+    probe: &dyn SleepProbe,
     localization: &Localization,
+    checkpoint: &Checkpoint,
+    cancel: &CancellationToken,
 ) -> io::Result<OptimizationResult> {
-    let weights = PerformanceWeights::default();
+    let criteria = params.ranking()?;
+    let weights = criteria.performance_weights();
     let bounds = (params.start_value, params.end_value);
     let max_iterations = 15;
     let samples_per_run = params.sample_value;
@@ -616,6 +1144,7 @@ async fn optimize_timer_resolution(
         println!("   {}", localization.get(LocalizationKey::LinearMethodRuns));
         println!("   {}", localization.get_linear_method_samples(samples_per_run));
     println!("   {}", localization.get_weights(weights.accuracy * 100.0, weights.consistency * 100.0, weights.worst_case * 100.0));
+    println!("   Acquisition: {:?}, batch of {}", params.acquisition, params.acquisition_batch.max(1));
     println!();
 
     let range = bounds.1 - bounds.0;
@@ -636,22 +1165,37 @@ async fn optimize_timer_resolution(
     let init_pb = ProgressBar::new(initial_points.len() as u64);
     init_pb.set_style(
         ProgressStyle::default_bar()
-            .template(&localization.get(LocalizationKey::InitProgressBar))
+            .template(localization.get(LocalizationKey::InitProgressBar))
             .unwrap()
             .progress_chars("##-")
     );
     
+    let mut cancelled = false;
+    let mut failed_points = Vec::new();
     for (i, &x) in initial_points.iter().enumerate() {
+        if cancel.is_cancelled() {
+            cancelled = true;
+            break;
+        }
         init_pb.set_message(localization.get_init_point_message(x));
         println!("{}", localization.get_point_info(i + 1, initial_points.len(), x));
-        let measurement = measure_resolution_robust(
+        let measurement = match measure_point(
             x,
-            samples_per_run,
+            params,
             runs_per_measurement,
-            set_timer_path,
-            measure_sleep_path,
+            backend,
+            probe,
             localization,
-        ).await?;
+            checkpoint,
+        ).await {
+            Ok(measurement) => measurement,
+            Err(e) => {
+                skip_failed_point(x, e, cancel, &mut failed_points)?;
+                optimizer.exclude(x);
+                init_pb.inc(1);
+                continue;
+            }
+        };
         optimizer.add_observation(measurement);
         init_pb.inc(1);
     }
@@ -664,58 +1208,99 @@ async fn optimize_timer_resolution(
     let opt_pb = ProgressBar::new(total_iterations as u64);
     opt_pb.set_style(
         ProgressStyle::default_bar()
-            .template(&localization.get(LocalizationKey::OptProgressBar))
+            .template(localization.get(LocalizationKey::OptProgressBar))
             .unwrap()
             .progress_chars("##-")
     );
     
-    for iter in initial_points.len()..max_iterations as usize {
-        let kappa = 2.5 - (2.0 * (iter - initial_points.len()) as f64 / (max_iterations as usize - initial_points.len()) as f64);
-        let next_x = optimizer.suggest_next(bounds, 200, kappa);
-        println!("  {}", localization.get_iterations_with_kappa(iter + 1, max_iterations as usize, next_x, kappa));
-        let measurement = measure_resolution_robust(
-            next_x,
-            samples_per_run,
-            runs_per_measurement,
-            set_timer_path,
-            measure_sleep_path,
-            localization,
-        ).await?;
-        optimizer.add_observation(measurement);
-        
-        let current_best = optimizer.observations.iter()
-            .min_by(|a, b| {
-                let score_a = a.statistics.performance_score(&weights);
-                let score_b = b.statistics.performance_score(&weights);
-                score_a.partial_cmp(&score_b).unwrap()
-            })
-            .unwrap();
-        println!("       {}", localization.get_current_best(current_best.resolution_ms, current_best.statistics.performance_score(&weights)));
-        
-        kill_all_timer_processes()?;
-        sleep(Duration::from_millis(300)).await;
-        opt_pb.inc(1);
+    let mut iter = initial_points.len();
+    'search: while iter < max_iterations as usize {
+        if cancelled || cancel.is_cancelled() {
+            cancelled = true;
+            break;
+        }
+        let progress = (iter - initial_points.len()) as f64 / total_iterations as f64;
+        let acquisition = params.acquisition.function(progress);
+        let batch_size = params.acquisition_batch.min(max_iterations as usize - iter);
+        let batch = optimizer.suggest_batch(bounds, 200, acquisition.as_ref(), batch_size);
+        if batch.len() > 1 {
+            println!("{}", localization.get_acquisition_batch(batch.len(), &format_resolutions(&batch)));
+        }
+
+        for next_x in batch {
+            if cancel.is_cancelled() {
+                cancelled = true;
+                break 'search;
+            }
+            match params.acquisition {
+                AcquisitionKind::Ucb => println!("  {}",
+                    localization.get_iterations_with_kappa(iter + 1, max_iterations as usize, next_x, ucb_kappa(progress))),
+                _ => println!("  {} of {}: x={:.4}, {}", iter + 1, max_iterations, next_x, acquisition.describe()),
+            }
+            let measurement = match measure_point(
+                next_x,
+                params,
+                runs_per_measurement,
+                backend,
+                probe,
+                localization,
+                checkpoint,
+            ).await {
+                Ok(measurement) => measurement,
+                Err(e) => {
+                    // The iteration is spent so a point that keeps failing cannot stall the search
+                    skip_failed_point(next_x, e, cancel, &mut failed_points)?;
+                    optimizer.exclude(next_x);
+                    opt_pb.inc(1);
+                    iter += 1;
+                    continue;
+                }
+            };
+            optimizer.add_observation(measurement);
+
+            let current_best = optimizer.observations.iter()
+                .min_by(|a, b| {
+                    let score_a = a.statistics.performance_score(&weights);
+                    let score_b = b.statistics.performance_score(&weights);
+                    score_a.total_cmp(&score_b)
+                })
+                .unwrap();
+            println!("       {}", localization.get_current_best(current_best.resolution_ms, current_best.statistics.performance_score(&weights)));
+
+            backend.release()?;
+            settle(backend, 300).await;
+            opt_pb.inc(1);
+            iter += 1;
+        }
     }
     opt_pb.finish_with_message(localization.get(LocalizationKey::OptCompleted));
+    if optimizer.observations.is_empty() {
+        return Err(cancelled_before_start());
+    }
+    let surrogate = optimizer.surrogate_curve(bounds, SURROGATE_CURVE_POINTS);
+    if let Some(curve) = &surrogate {
+        println!("{}", localization.get_gp_surrogate(
+            curve.kernel.name(),
+            curve.hyperparameters.length_scale,
+            curve.hyperparameters.noise_variance / curve.hyperparameters.signal_variance,
+            curve.log_marginal_likelihood));
+    }
 
     println!("\n{}", localization.get(LocalizationKey::Phase3));
     println!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
-    let aggregated_measurements = aggregate_measurements(&optimizer.observations);
+    let aggregated_measurements = final_measurements(&optimizer.observations, params);
     println!("{}", localization.get_unique_points(aggregated_measurements.len(), optimizer.observations.len()));
-    let topsis_results = topsis_ranking(&aggregated_measurements);
-
-    println!("\n{}", localization.get(LocalizationKey::TopsisRanking));
-    println!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━\n");
-    for (i, result) in topsis_results.iter().take(5).enumerate() {
-        let marker = if i == 0 { "🥇" } else if i == 1 { "🥈" } else if i == 2 { "🥉" } else { "  " };
-        println!("{}  {}: {:.4} ms", marker, localization.get_rank(result.rank), result.resolution_ms);
-        println!("{}", localization.get_topsis_score(result.closeness_coefficient));
-        println!("{}", localization.get_p95_delta(result.criteria_scores.p95_delta));
-        println!("{}", localization.get_mad(result.criteria_scores.mad));
-        println!("{}", localization.get_p99_delta(result.criteria_scores.p99_delta));
-        println!("{}", localization.get_ci_width(result.criteria_scores.confidence_width));
-        println!();
-    }
+    let topsis_results = topsis_ranking(&aggregated_measurements, &criteria);
+    let criteria = resolve_weights(&aggregated_measurements, &criteria);
+
+    print_top_ranking(&topsis_results, Some(&criteria), localization);
+
+    let significance = winner_significance(&topsis_results, &aggregated_measurements, RUNNERS_UP_TESTED);
+    print_winner_significance(significance.as_ref(), localization);
+    let rankers = compare_rankers(&aggregated_measurements, &criteria);
+    print_ranker_comparison(rankers.as_ref(), localization);
+    let sensitivity = weight_sensitivity(&aggregated_measurements, &criteria, params.sensitivity_samples);
+    print_weight_sensitivity(sensitivity.as_ref(), localization);
 
     let best = &topsis_results[0];
     println!("{}", localization.get_optimal_value(best.resolution_ms));
@@ -726,158 +1311,90 @@ async fn optimize_timer_resolution(
         topsis_score: best.closeness_coefficient,
         aggregated_measurements,
         topsis_rankings: topsis_results,
+        partial: cancelled,
+        significance,
+        rankers,
+        sensitivity,
+        surrogate,
+        criteria,
+        failed_points,
     })
 }
 
-fn save_detailed_results(result: &OptimizationResult, filename: &str) -> io::Result<()> {
-    use std::fs::File;
-    use std::io::BufWriter;
-    let file = File::create(filename)?;
-    let mut writer = BufWriter::new(file);
-    writeln!(writer, "# Timer Resolution Optimization Results")?;
-    writeln!(writer, "# Generated: {:?}", std::time::SystemTime::now())?;
-    writeln!(writer, "")?;
-    writeln!(writer, "Resolution_ms,P50_Delta,P95_Delta,P99_Delta,Mean_Delta,StdDev,MAD,Outliers_Removed,CI_Lower,CI_Upper,TOPSIS_Score,Rank")?;
-    for topsis in &result.topsis_rankings {
-        // ИСПОЛЬЗУЕМ aggregated_measurements! и сравнение с tolerance для float
-        let m = result.aggregated_measurements.iter()
-            .find(|m| (m.resolution_ms - topsis.resolution_ms).abs() < 0.0001)
-            .ok_or_else(|| {
-                Error::new(
-                    ErrorKind::NotFound, 
-                    format!("Measurement not found for resolution {:.4} ms", topsis.resolution_ms)
-                )
-            })?;
-        writeln!(
-            writer,
-            "{:.4},{:.4},{:.4},{:.4},{:.4},{:.4},{:.4},{},{:.4},{:.4},{:.4},{}",
-            m.resolution_ms,
-            m.statistics.median,
-            m.statistics.p95,
-            m.statistics.p99,
-            m.statistics.mean,
-            m.statistics.stdev,
-            m.statistics.mad,
-            m.statistics.outliers_removed,
-            m.statistics.confidence_interval_95.0,
-            m.statistics.confidence_interval_95.1,
-            topsis.closeness_coefficient,
-            topsis.rank,
-        )?;
-    }
-    writeln!(writer, "")?;
-    writeln!(writer, "# Optimal Resolution: {:.4} ms", result.optimal_resolution)?;
-    writeln!(writer, "# TOPSIS Score: {:.4}", result.topsis_score)?;
-    Ok(())
-}
-
 // ============================================================================ 
 // ROBUST TIMER RESOLUTION MEASUREMENT
 // ============================================================================
 
+/// Measure a point, or replay it when the checkpoint already holds it
+async fn measure_point(
+    resolution_ms: f64,
+    params: &BenchmarkingParameters,
+    num_runs: usize,
+    backend: &dyn TimerBackend,
+    probe: &dyn SleepProbe,
+    localization: &Localization,
+    checkpoint: &Checkpoint,
+) -> io::Result<TimerMeasurement> {
+    if let Some(recorded) = checkpoint.take_recorded(resolution_ms) {
+        println!("{}", localization.get_point_restored(resolution_ms));
+        return match recorded {
+            RecordedPoint::Measured(measurement) => Ok(*measurement),
+            RecordedPoint::Failed(failed_point) => Err(Error::new(ErrorKind::InvalidData, failed_point)),
+        };
+    }
+    let measurement = match measure_resolution_robust(
+        resolution_ms,
+        params,
+        num_runs,
+        backend,
+        probe,
+        localization,
+    ).await {
+        Ok(measurement) => measurement,
+        Err(e) => {
+            if let Some(reason) = failure_reason(&e) {
+                checkpoint.record_failure(&FailedPoint { resolution_ms, reason })?;
+            }
+            return Err(e);
+        },
+    };
+    checkpoint.record(&measurement)?;
+    Ok(measurement)
+}
+
 async fn measure_resolution_robust(
     resolution_ms: f64,
-    samples_per_run: i32,
+    params: &BenchmarkingParameters,
     num_runs: usize,
-    set_timer_path: &PathBuf,
-    measure_sleep_path: &PathBuf,
+    backend: &dyn TimerBackend,
+    probe: &dyn SleepProbe,
     localization: &Localization,
 ) -> io::Result<TimerMeasurement> {
-    kill_all_timer_processes()?;
-    sleep(Duration::from_millis(300)).await;
+    let samples_per_run = params.sample_value;
+    backend.release()?;
+    settle(backend, 300).await;
     
+    // Wait between applying the resolution and sampling (see `Stationarity::settle_ms`)
+    const APPLY_SETTLE_MS: u64 = 350;
+
     let mut all_deltas = Vec::new();
+    let mut run_lengths = Vec::with_capacity(num_runs);
+    let mut stationarity = Stationarity::new(if backend.needs_settling() { APPLY_SETTLE_MS } else { 0 });
     println!("{}", localization.get_measurement_with_runs(resolution_ms, num_runs, samples_per_run));
     
     for run in 1..=num_runs {
-        let resolution = (resolution_ms * 10_000.0) as i32;
-        
-        kill_all_timer_processes()?;
-        sleep(Duration::from_millis(200)).await;
+        backend.apply_resolution(resolution_ms)?;
+        settle(backend, APPLY_SETTLE_MS).await;
         
-        let mut timer_child = Command::new(set_timer_path)
-            .args(&["--resolution", &resolution.to_string(), "--no-console"])
-            .stderr(Stdio::piped())
-            .stdout(Stdio::piped())
-            .spawn()
-            .map_err(|e| Error::new(ErrorKind::Other, format!("Failed to spawn SetTimerResolution: {}", e)))?;
-
-        sleep(Duration::from_millis(50)).await;
-        
-        match timer_child.try_wait() {
-            Ok(Some(_exit_status)) => {
-                let mut stderr_output = String::new();
-                let mut stdout_output = String::new();
-                if let Some(mut stderr) = timer_child.stderr.take() {
-                    let _ = stderr.read_to_string(&mut stderr_output);
-                }
-                if let Some(mut stdout) = timer_child.stdout.take() {
-                    let _ = stdout.read_to_string(&mut stdout_output);
-                }
-                let error_msg = format!("{}{}", stderr_output, stdout_output);
-                if error_msg.contains("already running") || error_msg.contains("Another instance") {
-                    eprintln!("{}", localization.get(LocalizationKey::ErrorMutexConflict));
-                    eprintln!("{}", localization.get_mutex_error_message(&error_msg.trim()));
-                    eprintln!("{}", localization.get(LocalizationKey::MutexErrorHint));
-                    eprintln!("{}", localization.get(LocalizationKey::MutexErrorRestart));
-                    kill_all_timer_processes()?;
-                    return Err(Error::new(ErrorKind::AlreadyExists, localization.get(LocalizationKey::ErrorMutexRunning)));
-                }
-                return Err(Error::new(ErrorKind::Other, localization.get_error_process_exited(&error_msg)));
-            },
-            Ok(None) => {},
+        let report = match probe.sample(samples_per_run as usize) {
+            Ok(report) => report,
             Err(e) => {
-                eprintln!("{}", localization.get_warning_cannot_check_process(&e.to_string()));
-            }
-        }
-
-        sleep(Duration::from_millis(350)).await;
-        
-        let measure_path = measure_sleep_path.clone();
-        let samples = samples_per_run;
-        let output_result = timeout(
-            Duration::from_secs(30),
-            tokio::task::spawn_blocking(move || {
-                Command::new(&measure_path)
-                    .arg("--samples")
-                    .arg(samples.to_string())
-                    .output()
-            })
-        ).await;
-        
-        let output = match output_result {
-            Ok(Ok(Ok(output))) => output,
-            Ok(Ok(Err(e))) => {
-                let _ = timer_child.kill();
-                kill_all_timer_processes()?;
-                eprintln!("{}", localization.get_measure_sleep_error(&e.to_string()));
+                backend.release()?;
                 return Err(e);
-            },
-            Ok(Err(e)) => {
-                let _ = timer_child.kill();
-                kill_all_timer_processes()?;
-                eprintln!("{}", localization.get_join_error(&e.to_string()));
-                return Err(Error::new(ErrorKind::Other, e));
-            },
-            Err(_) => {
-                let _ = timer_child.kill();
-                kill_all_timer_processes()?;
-                eprintln!("{}", localization.get(LocalizationKey::TimeoutError));
-                return Err(Error::new(ErrorKind::TimedOut, "MeasureSleep timeout"));
             }
         };
         
-        if !output.status.success() {
-            let _ = timer_child.kill();
-            kill_all_timer_processes()?;
-            eprintln!("{}", localization.get(LocalizationKey::ErrorMeasureSleepFailed));
-            eprintln!("{}", String::from_utf8_lossy(&output.stderr));
-            return Err(Error::new(ErrorKind::Other, "MeasureSleep execution failed"));
-        }
-        
-        let (delta, _stdev, measure_reported_res) = parse_measurement_output_with_resolution(&output.stdout)?;
-        
-        if let Some(reported) = measure_reported_res {
+        if let Some(reported) = report.reported_resolution_ms {
             let tolerance = 0.05;
             let diff = (reported - resolution_ms).abs();
             if diff > tolerance {
@@ -887,40 +1404,39 @@ async fn measure_resolution_robust(
                 eprintln!("{}", localization.get_diff(diff));
                 
                 if diff > 0.1 {
-                    let _ = timer_child.kill();
-                    kill_all_timer_processes()?;
-                    return Err(Error::new(ErrorKind::Other,
+                    backend.release()?;
+                    return Err(Error::other(
                         localization.get_critical_mismatch(resolution_ms, reported)));
                 }
             } else {
                 println!("{}", localization.get_verified(reported));
             }
-        } else {
-            eprintln!("{}", localization.get(LocalizationKey::WarningParseResolution));
-            eprintln!("{}",
-                localization.get_output_preview(
-                    &String::from_utf8_lossy(&output.stdout).lines().next().unwrap_or(localization.get(LocalizationKey::Empty))
-                )
-            );
         }
         
-        all_deltas.push(delta);
+        // Samples stay in the order taken; a warm-up prefix is dropped
+        let analysis = analyze_run(&report.deltas_ms, &report.offsets_ms);
+        stationarity.add_run(&analysis);
+        let kept = &report.deltas_ms[analysis.warmup_samples..];
+        run_lengths.push(kept.len());
+        all_deltas.extend_from_slice(kept);
         print!(".");
         io::stdout().flush()?;
         
-        if let Err(e) = timer_child.kill() {
-            eprintln!("{}", localization.get_warning_kill_child(&e.to_string()));
-        }
-        
-        kill_all_timer_processes()?;
+        backend.release()?;
         
         if run < num_runs {
-            sleep(Duration::from_millis(600)).await;
+            settle(backend, 600).await;
         }
     }
     println!(" ✓");
+    if stationarity.warmup_samples > 0 {
+        println!("{}", localization.get_warmup_dropped(stationarity.warmup_samples, stationarity.warmup_ms));
+    }
+    if stationarity.drifting_runs > 0 {
+        println!("{}", localization.get_drift_runs(stationarity.drifting_runs, stationarity.runs));
+    }
     
-    let statistics = RobustStatistics::from_samples(all_deltas.clone());
+    let statistics = RobustStatistics::try_from_samples(all_deltas.clone(), params.quantile_estimator)?;
     
     println!("{}", 
         localization.get_measurement_stats(
@@ -934,42 +1450,344 @@ async fn measure_resolution_robust(
         resolution_ms,
         statistics,
         raw_samples: all_deltas,
+        run_lengths,
+        histogram: None,
+        stationarity,
     })
 }
 
 
+/// Record a point whose samples were unusable (empty, NaN, too few) so the
+/// search can move on; any other error (backend, probe, I/O) is returned
+///
+/// After Ctrl+C other errors are dropped too: the probe was most likely
+/// interrupted, and the search stops at its next cancellation check and ranks
+/// the points measured so far.
+fn skip_failed_point(
+    resolution_ms: f64,
+    error: Error,
+    cancel: &CancellationToken,
+    failed_points: &mut Vec<FailedPoint>,
+) -> io::Result<()> {
+    let Some(reason) = failure_reason(&error) else {
+        if cancel.is_cancelled() {
+            eprintln!("⏹  {:.4} ms interrupted ({}), not recorded", resolution_ms, error);
+            return Ok(());
+        }
+        return Err(error);
+    };
+    eprintln!("⚠️  {:.4} ms skipped: {}", resolution_ms, reason);
+    failed_points.push(FailedPoint { resolution_ms, reason: reason.to_string() });
+    Ok(())
+}
+
+/// Why a point's samples were unusable, for errors that skip the point rather than stop the search
+fn failure_reason(error: &Error) -> Option<String> {
+    let inner = error.get_ref()?;
+    inner.downcast_ref::<StatisticsError>().map(ToString::to_string)
+        .or_else(|| inner.downcast_ref::<FailedPoint>().map(|failed_point| failed_point.reason.clone()))
+}
+
+/// Error for a search cancelled before its first point was measured
+fn cancelled_before_start() -> Error {
+    Error::new(ErrorKind::Interrupted, "Cancelled before any point was measured")
+}
+
+/// Print whether the winner is distinguishable from the runners-up
+fn print_winner_significance(significance: Option<&WinnerSignificance>, localization: &Localization) {
+    let Some(significance) = significance else { return };
+    if significance.comparisons.is_empty() {
+        return;
+    }
+
+    println!("{}", localization.get(LocalizationKey::SignificanceTitle));
+    println!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
+    for comparison in &significance.comparisons {
+        let verdict = match comparison.verdict {
+            Verdict::Equivalent => localization.get(LocalizationKey::VerdictEquivalent).to_string(),
+            Verdict::WinnerBetter => localization.get(LocalizationKey::VerdictWinnerBetter).to_string(),
+            Verdict::RunnerUpBetter => localization.get_verdict_runner_up_better(&comparison.runner_up_better_on()),
+        };
+        println!("{}", localization.get_significance_comparison(
+            comparison.rank,
+            comparison.resolution_ms,
+            comparison.median_difference_ms,
+            comparison.p95_difference_ms,
+            &verdict));
+    }
+    if significance.is_tie() {
+        println!("{}", localization.get_significance_tie(&format_resolutions(&significance.equivalent_set_ms)));
+        println!("{}", localization.get(LocalizationKey::SignificanceTieBreak));
+    }
+    for comparison in significance.better_runners_up() {
+        println!("{}", localization.get_significance_not_clear_win(comparison.resolution_ms, &comparison.runner_up_better_on()));
+    }
+    if significance.is_clear_win() {
+        println!("{}", localization.get_significance_clear_win(significance.comparisons.len()));
+    }
+    println!();
+}
+
+/// Print the winners of the alternative rankers and the Pareto front
+fn print_ranker_comparison(comparison: Option<&RankerComparison>, localization: &Localization) {
+    let Some(comparison) = comparison else { return };
+
+    println!("{}", localization.get(LocalizationKey::RankerTitle));
+    println!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
+    for winner in &comparison.winners {
+        println!("{}", localization.get_ranker_winner(&winner.ranker, winner.resolution_ms, winner.pareto_optimal));
+    }
+    println!("{}", localization.get_ranker_pareto_front(&format_resolutions(&comparison.pareto_front_ms)));
+    if comparison.agree() {
+        println!("{}\n", localization.get(LocalizationKey::RankersAgree));
+    } else {
+        println!("{}\n", localization.get(LocalizationKey::RankersDisagree));
+    }
+}
+
+/// Print how often each resolution wins under random weights and how far
+/// each weight can move before the winner changes
+fn print_weight_sensitivity(sensitivity: Option<&WeightSensitivity>, localization: &Localization) {
+    let Some(sensitivity) = sensitivity else { return };
+
+    println!("{}", localization.get_sensitivity_title(sensitivity.samples));
+    println!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
+    for rate in sensitivity.win_rates.iter().take(5) {
+        println!("{}", localization.get_sensitivity_win_rate(rate.resolution_ms, rate.fraction));
+    }
+    for range in &sensitivity.weight_ranges {
+        println!("{}", localization.get_sensitivity_weight_range(&range.criterion, range.weight, range.lower, range.upper));
+    }
+    println!("{}\n", localization.get_sensitivity_verdict(
+        sensitivity.is_robust(), sensitivity.winner_ms, sensitivity.winner_rate()));
+}
+
+/// Print the histogram and shape of the recommended resolution and list the
+/// resolutions whose samples look bimodal
+fn print_distribution(result: &OptimizationResult, localization: &Localization) {
+    let Some(optimum) = result.aggregated_measurements.iter()
+        .find(|m| (m.resolution_ms - result.optimal_resolution).abs() < 0.0001)
+    else {
+        return;
+    };
+    let shape = &optimum.statistics.shape;
+
+    println!("{}", localization.get_distribution_title(optimum.resolution_ms, optimum.raw_samples.len()));
+    println!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
+    if let Some(histogram) = &optimum.histogram {
+        for line in histogram.render(30) {
+            println!("{}", line);
+        }
+    }
+    println!("{}", localization.get_distribution_shape(shape));
+    let mut bimodal: Vec<f64> = result.aggregated_measurements.iter()
+        .filter(|m| m.statistics.shape.is_bimodal())
+        .map(|m| m.resolution_ms)
+        .collect();
+    bimodal.sort_by(f64::total_cmp);
+    if shape.is_bimodal() {
+        println!("{}\n", localization.get_distribution_bimodal(BIMODALITY_THRESHOLD));
+    } else if !bimodal.is_empty() {
+        println!("{}\n", localization.get_distribution_bimodal_elsewhere(BIMODALITY_THRESHOLD, &format_resolutions(&bimodal)));
+    } else {
+        println!();
+    }
+}
+
+/// Print how many warm-up samples were dropped, where runs drifted and the
+/// settle time that would have covered the warm-ups
+fn print_stationarity(result: &OptimizationResult, localization: &Localization) {
+    let points: Vec<&Stationarity> = result.aggregated_measurements.iter().map(|m| &m.stationarity).collect();
+    let dropped: usize = points.iter().map(|s| s.warmup_samples).sum();
+    let mut drifting: Vec<f64> = result.aggregated_measurements.iter()
+        .filter(|m| m.stationarity.drifting_runs > 0)
+        .map(|m| m.resolution_ms)
+        .collect();
+    if dropped == 0 && drifting.is_empty() {
+        return;
+    }
+    drifting.sort_by(f64::total_cmp);
+
+    println!("{}", localization.get(LocalizationKey::StationarityTitle));
+    println!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
+    if dropped > 0 {
+        println!("{}", localization.get_stationarity_dropped(
+            dropped, points.iter().filter(|s| s.warmup_samples > 0).count(), points.len()));
+    }
+    if let Some(suggested) = suggested_settle_ms(points.iter().copied()) {
+        let current = points.iter().map(|s| s.settle_ms).max().unwrap_or(0);
+        println!("{}", localization.get_stationarity_settle(suggested, current));
+    }
+    if !drifting.is_empty() {
+        println!("{}", localization.get_stationarity_drifting(&format_resolutions(&drifting)));
+    }
+    println!();
+}
+
+/// Give the system time to settle after a resolution change (skipped for in-memory backends)
+async fn settle(backend: &dyn TimerBackend, millis: u64) {
+    if backend.needs_settling() {
+        sleep(Duration::from_millis(millis)).await;
+    }
+}
+
+/// Aggregated measurements for the final ranking, with bootstrap intervals of
+/// the quantiles and histograms (too costly for the intermediate rankings of a search)
+fn final_measurements(measurements: &[TimerMeasurement], params: &BenchmarkingParameters) -> Vec<TimerMeasurement> {
+    aggregate_measurements(measurements, params.quantile_estimator).into_iter()
+        .map(|mut m| {
+            m.statistics = m.statistics.with_quantile_intervals(&m.raw_samples, params.bootstrap_resamples);
+            m.histogram = Histogram::of_samples(&m.raw_samples, params.histogram_bins, params.quantile_estimator);
+            m
+        })
+        .collect()
+}
+
+/// Key of a resolution on the 0.0001 ms grid measurements are aggregated on
+pub(crate) fn grid_key(resolution_ms: f64) -> i64 {
+    (resolution_ms * 10000.0).round() as i64
+}
 
-fn aggregate_measurements(measurements: &[TimerMeasurement]) -> Vec<TimerMeasurement> {
-    use std::collections::HashMap;
-    let mut groups: HashMap<i64, Vec<&TimerMeasurement>> = HashMap::new();
+/// One measurement per resolution, in ascending order of resolution
+fn aggregate_measurements(measurements: &[TimerMeasurement], estimator: QuantileEstimator) -> Vec<TimerMeasurement> {
+    let mut groups: BTreeMap<i64, Vec<&TimerMeasurement>> = BTreeMap::new();
     for m in measurements {
-        let key = (m.resolution_ms * 10000.0).round() as i64;
-        groups.entry(key).or_insert_with(Vec::new).push(m);
-    }
-    groups.into_iter().map(|(key, group)| {
-        let resolution_ms = key as f64 / 10000.0;
-        let mut all_samples = Vec::new();
-        for m in &group {
-            all_samples.extend(m.raw_samples.iter().copied());
+        groups.entry(grid_key(m.resolution_ms)).or_default().push(m);
+    }
+    groups.into_iter().filter_map(|(key, group)| combine_measurements(key, &group, estimator)).collect()
+}
+
+/// Merge `measurement` into aggregated measurements, re-deriving the
+/// statistics of its resolution only
+fn merge_measurement(aggregated: &mut Vec<TimerMeasurement>, measurement: TimerMeasurement, estimator: QuantileEstimator) {
+    let key = grid_key(measurement.resolution_ms);
+    match aggregated.iter().position(|m| grid_key(m.resolution_ms) == key) {
+        Some(i) => {
+            if let Some(merged) = combine_measurements(key, &[&aggregated[i], &measurement], estimator) {
+                aggregated[i] = merged;
+            }
+        },
+        None => aggregated.push(measurement),
+    }
+}
+
+/// Samples, runs and statistics of the measurements of one resolution
+fn combine_measurements(key: i64, group: &[&TimerMeasurement], estimator: QuantileEstimator) -> Option<TimerMeasurement> {
+    let mut all_samples = Vec::new();
+    let mut run_lengths = Vec::new();
+    let mut stationarity = Stationarity::default();
+    for m in group {
+        all_samples.extend(m.raw_samples.iter().copied());
+        run_lengths.extend(m.runs().iter().map(|run| run.len()));
+        stationarity.merge(&m.stationarity);
+    }
+    // Every group is made of measurements whose samples were already validated
+    let combined_stats = RobustStatistics::try_from_samples(all_samples.clone(), estimator).ok()?;
+    Some(TimerMeasurement {
+        resolution_ms: key as f64 / 10000.0,
+        statistics: combined_stats,
+        raw_samples: all_samples,
+        run_lengths,
+        histogram: None,
+        stationarity,
+    })
+}
+
+/// Points added to a running ranking before its TOPSIS model is refitted, as a
+/// fraction of the points it was fitted to
+const REFIT_FRACTION: usize = 8;
+
+/// Per-resolution accumulators and the running TOPSIS leader of a sweep
+///
+/// Adding a point merges its samples into the accumulator of its resolution
+/// and re-derives only that resolution's statistics (sketch quantiles). The
+/// point is then scored with the TOPSIS model last fitted to all summaries, so
+/// finding the leader costs the same however many points came before; the
+/// model is refitted once the sweep has grown by 1/`REFIT_FRACTION`, which
+/// keeps the total cost of refitting linear in the number of points. Summaries
+/// carry no raw samples; the final ranking uses `final_measurements`.
+struct RunningAggregate {
+    criteria: RankingCriteria,
+    points: BTreeMap<i64, (SampleAccumulator, Option<TimerMeasurement>)>,
+    model: Option<TopsisModel>,
+    summarised: usize,            // Points whose summary is `Some`
+    refit_at: usize,              // Summaries at which the model is refitted
+    leader: Option<(i64, f64)>,   // Grid key and closeness of the best summary
+}
+
+impl RunningAggregate {
+    fn new(criteria: &RankingCriteria) -> Self {
+        Self { criteria: criteria.clone(), points: BTreeMap::new(), model: None, summarised: 0, refit_at: 0, leader: None }
+    }
+
+    fn add(&mut self, measurement: &TimerMeasurement) {
+        let key = grid_key(measurement.resolution_ms);
+        let (accumulator, summary) = self.points.entry(key).or_insert_with(|| (SampleAccumulator::default(), None));
+        accumulator.merge(&SampleAccumulator::from_samples(&measurement.raw_samples));
+        let had_summary = summary.is_some();
+        *summary = RobustStatistics::try_from_accumulator(accumulator).ok()
+            .map(|statistics| TimerMeasurement {
+                resolution_ms: key as f64 / 10000.0,
+                statistics,
+                raw_samples: Vec::new(),
+                run_lengths: Vec::new(),
+                histogram: None,
+                stationarity: Stationarity::default(),
+            });
+
+        match (had_summary, summary.is_some()) {
+            (false, true) => self.summarised += 1,
+            (true, false) => self.summarised -= 1,
+            _ => {},
         }
-        let combined_stats = RobustStatistics::from_samples(all_samples.clone());
-        TimerMeasurement {
-            resolution_ms,
-            statistics: combined_stats,
-            raw_samples: all_samples,
+
+        // New samples can lower the leader's closeness, so updating it forces a refit
+        let updates_leader = self.leader.is_some_and(|(leader, _)| leader == key);
+        let summary = self.points.get(&key).and_then(|(_, summary)| summary.as_ref());
+        match (&self.model, summary) {
+            (Some(model), Some(summary)) if self.summarised < self.refit_at && !updates_leader => {
+                let closeness = model.closeness(summary);
+                if self.leader.is_none_or(|(_, best)| closeness > best) {
+                    self.leader = Some((key, closeness));
+                }
+            },
+            _ => self.refit(),
         }
-    }).collect()
+    }
+
+    /// Fit the model to every summary and rank them all
+    fn refit(&mut self) {
+        let summaries: Vec<&TimerMeasurement> = self.summaries().collect();
+        let model = TopsisModel::fit(&summaries, &self.criteria);
+        self.leader = model.as_ref().and_then(|model| summaries.iter()
+            .map(|m| (grid_key(m.resolution_ms), model.closeness(m)))
+            .max_by(|a, b| a.1.total_cmp(&b.1)));
+        self.model = model;
+        self.refit_at = self.summarised + (self.summarised / REFIT_FRACTION).max(1);
+    }
+
+    /// Approximate aggregated measurements, one per resolution
+    fn summaries(&self) -> impl Iterator<Item = &TimerMeasurement> {
+        self.points.values().filter_map(|(_, summary)| summary.as_ref())
+    }
+
+    /// Resolution and TOPSIS closeness of the current leader
+    fn leader(&self) -> Option<(f64, f64)> {
+        self.leader.map(|(key, closeness)| (key as f64 / 10000.0, closeness))
+    }
 }
 
 // ============================================================================ 
 // LINEAR EXHAUSTIVE SEARCH
 // ============================================================================
 
-async fn linear_exhaustive_search(
+pub async fn linear_exhaustive_search(
     params: &BenchmarkingParameters,
-    set_timer_path: &PathBuf,
-    measure_sleep_path: &PathBuf,
+    backend: &dyn TimerBackend,
+    probe: &dyn SleepProbe,
     localization: &Localization,
+    checkpoint: &Checkpoint,
+    cancel: &CancellationToken,
 ) -> io::Result<OptimizationResult> {
     println!("\n{}", localization.get(LocalizationKey::LinearMethodTitle));
     println!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
@@ -986,10 +1804,10 @@ async fn linear_exhaustive_search(
 
     if total_points > 100_000 {
         eprintln!("⚠️  WARNING: {} points will be tested!", total_points);
-        eprintln!("   This will take approximately {:.1} hours", (total_points as f64 * 6.5) / 3600.0);
-        eprintln!("   Press Ctrl+C to abort, or Enter to continue...");
-        let mut input = String::new();
-        io::stdin().read_line(&mut input)?;
+        eprintln!("   This will take approximately {:.1} hours", (total_points as f64 * 35.0) / 3600.0);
+        if !confirm("Continue? (y/N):", false)? {
+            return Err(Error::new(ErrorKind::Interrupted, "Too many points, aborted"));
+        }
     }
 
     println!("{}", localization.get(LocalizationKey::LinearMethodParameters));
@@ -1059,23 +1877,11 @@ async fn linear_exhaustive_search(
         eprintln!("   Minimum Windows timer resolution unit: 0.0001 ms (100 ns)");
         eprintln!("   Values smaller than 0.0001 ms will be indistinguishable.");
         eprintln!();
-        print!("Continue anyway? (y/N): ");
-        io::stdout().flush()?;
-        let mut input = String::new();
-        io::stdin().read_line(&mut input)?;
-        if !input.trim().eq_ignore_ascii_case("y") {
+        if !confirm("Continue anyway? (y/N):", false)? {
             return Err(Error::new(ErrorKind::InvalidInput, "Increment too small"));
         }
     }
 
-    if total_points > 100_000 {
-        eprintln!("⚠️  WARNING: {} points will be tested!", total_points);
-        eprintln!("   This will take approximately {:.1} hours", (total_points as f64 * 35.0) / 3600.0);
-        eprintln!("   Press Ctrl+C to abort, or Enter to continue...");
-        let mut input = String::new();
-        io::stdin().read_line(&mut input)?;
-    }
-
     let mut measurements = Vec::new();
     let start_time = std::time::Instant::now();
     
@@ -1084,11 +1890,18 @@ async fn linear_exhaustive_search(
     const EMA_ALPHA: f64 = 0.15;  // Smoothing factor (0.1-0.2 optimal)
     const MIN_SAMPLES_FOR_ETA: usize = 5;  // Минимум измерений перед показом ETA
     
-    // Initialize the weights for performance scoring (used in TOPSIS ranking)
-    let _weights = PerformanceWeights::default();
+    // Criteria and weights used in TOPSIS ranking
+    let criteria = params.ranking()?;
+    let mut running = RunningAggregate::new(&criteria);
 
     // ✅ КРИТИЧНО: Целочисленный цикл вместо float инкремента!
+    let mut cancelled = false;
+    let mut failed_points = Vec::new();
     for i in 0..total_points {
+        if cancel.is_cancelled() {
+            cancelled = true;
+            break;
+        }
         // Вычисляем current через целочисленный индекс - нет накопления ошибок!
         let current = params.start_value + (i as f64) * params.increment_value;
         
@@ -1098,26 +1911,30 @@ async fn linear_exhaustive_search(
         }
         
         pb.set_message(format!("{:.4} ms", current));
-        let measurement = measure_resolution_robust(
+        let measurement = match measure_point(
             current,
-            params.sample_value,
+            params,
             3,  // 3 runs
-            set_timer_path,
-            measure_sleep_path,
+            backend,
+            probe,
             localization,
-        ).await?;
+            checkpoint,
+        ).await {
+            Ok(measurement) => measurement,
+            Err(e) => {
+                skip_failed_point(current, e, cancel, &mut failed_points)?;
+                pb.inc(1);
+                continue;
+            }
+        };
+        running.add(&measurement);
         measurements.push(measurement);
         pb.inc(1);
 
         // ✅ НОВОЕ - показывает TOPSIS Score (лучший по всем критериям!)
         // Вычисляем TOPSIS для текущих измерений
-        let temp_aggregated = aggregate_measurements(&measurements);
-        let temp_topsis = topsis_ranking(&temp_aggregated);
-        if !temp_topsis.is_empty() {
-            let best = &temp_topsis[0];
-            pb.println(format!("       Current best: {:.4} ms (TOPSIS: {:.4})", 
-                best.resolution_ms, 
-                best.closeness_coefficient));
+        if let Some((best_ms, closeness)) = running.leader() {
+            pb.println(format!("       Current best: {:.4} ms (TOPSIS: {:.4})", best_ms, closeness));
         }
 
         // ✅ ADAPTIVE ETA с EMA сглаживанием
@@ -1164,30 +1981,28 @@ async fn linear_exhaustive_search(
             pb.set_message(format!("{:.4} ms | ETA: calculating...", current));
         }
     }
-    pb.finish_with_message("linear search completed");
+    pb.finish_with_message(if cancelled { "linear search cancelled" } else { "linear search completed" });
+    if measurements.is_empty() {
+        return Err(cancelled_before_start());
+    }
 
-    let aggregated = aggregate_measurements(&measurements);
-    let topsis_results = topsis_ranking(&aggregated);
+    let aggregated = final_measurements(&measurements, params);
+    let topsis_results = topsis_ranking(&aggregated, &criteria);
+    let criteria = resolve_weights(&aggregated, &criteria);
 
     println!("\n✅ Linear search completed:");
     println!("   Points checked: {}", measurements.len());
     println!("   Unique: {}", aggregated.len());
     println!("   Total time: {:.1} minutes", start_time.elapsed().as_secs_f64() / 60.0);
 
-    // ✅ ДОБАВИТЬ: TOP-5 результаты (как в optimize_timer_resolution!)
-    println!("\n{}", localization.get(LocalizationKey::TopsisRanking));
-    println!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━\n");
-    
-    for (i, result) in topsis_results.iter().take(5).enumerate() {
-        let marker = if i == 0 { "🥇" } else if i == 1 { "🥈" } else if i == 2 { "🥉" } else { "  " };
-        println!("{}  {}: {:.4} ms", marker, localization.get_rank(result.rank), result.resolution_ms);
-        println!("{}", localization.get_topsis_score(result.closeness_coefficient));
-        println!("{}", localization.get_p95_delta(result.criteria_scores.p95_delta));
-        println!("{}", localization.get_mad(result.criteria_scores.mad));
-        println!("{}", localization.get_p99_delta(result.criteria_scores.p99_delta));
-        println!("{}", localization.get_ci_width(result.criteria_scores.confidence_width));
-        println!();
-    }
+    print_top_ranking(&topsis_results, Some(&criteria), localization);
+
+    let significance = winner_significance(&topsis_results, &aggregated, RUNNERS_UP_TESTED);
+    print_winner_significance(significance.as_ref(), localization);
+    let rankers = compare_rankers(&aggregated, &criteria);
+    print_ranker_comparison(rankers.as_ref(), localization);
+    let sensitivity = weight_sensitivity(&aggregated, &criteria, params.sensitivity_samples);
+    print_weight_sensitivity(sensitivity.as_ref(), localization);
 
     let best = &topsis_results[0];
     println!("{}", localization.get_optimal_value(best.resolution_ms));
@@ -1198,6 +2013,13 @@ async fn linear_exhaustive_search(
         topsis_score: topsis_results[0].closeness_coefficient,
         aggregated_measurements: aggregated,
         topsis_rankings: topsis_results,
+        partial: cancelled,
+        significance,
+        rankers,
+        sensitivity,
+        surrogate: None,
+        criteria,
+        failed_points,
     })
 }
 
@@ -1205,11 +2027,13 @@ async fn linear_exhaustive_search(
 // FAST LINEAR SEARCH (METHOD 3) - с early stopping и 1 run
 // ============================================================================
 
-async fn fast_linear_search(
+pub async fn fast_linear_search(
     params: &BenchmarkingParameters,
-    set_timer_path: &PathBuf,
-    measure_sleep_path: &PathBuf,
+    backend: &dyn TimerBackend,
+    probe: &dyn SleepProbe,
     localization: &Localization,
+    checkpoint: &Checkpoint,
+    cancel: &CancellationToken,
 ) -> io::Result<OptimizationResult> {
     // ✅ ВАЛИДАЦИЯ: минимум 2 samples
     if params.sample_value < 2 {
@@ -1220,8 +2044,8 @@ async fn fast_linear_search(
     println!("\n{}", localization.get(LocalizationKey::FastLinearMethodTitle));
     println!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
     
-    // Weights may be used for other purposes in TOPSIS calculations
-    let _weights = PerformanceWeights::default();
+    // Criteria and weights used in TOPSIS ranking
+    let criteria = params.ranking()?;
     let total_points = ((params.end_value - params.start_value) / params.increment_value).ceil() as usize;
     
     println!("📊 Parameters:");
@@ -1249,6 +2073,7 @@ async fn fast_linear_search(
     );
     
     let mut measurements = Vec::new();
+    let mut running = RunningAggregate::new(&criteria);
     let mut no_improvement_counter = 0;
     let early_stop_threshold = params.early_stop_threshold;  // ✅ Из параметров!
     let start_time = std::time::Instant::now();
@@ -1260,18 +2085,35 @@ async fn fast_linear_search(
     let mut point_index = 0;
     let mut current = params.start_value;
     
+    let mut cancelled = false;
+    let mut failed_points = Vec::new();
     while current <= params.end_value && point_index < total_points {
+        if cancel.is_cancelled() {
+            cancelled = true;
+            break;
+        }
         pb.set_message(format!("{:.4} ms", current));
         
-        let measurement = measure_resolution_robust(
+        let measurement = match measure_point(
             current,
-            params.sample_value,
+            params,
             1,  // ✅ 1 run для БЫСТРОГО режима!
-            set_timer_path,
-            measure_sleep_path,
+            backend,
+            probe,
             localization,
-        ).await?;
+            checkpoint,
+        ).await {
+            Ok(measurement) => measurement,
+            Err(e) => {
+                skip_failed_point(current, e, cancel, &mut failed_points)?;
+                pb.inc(1);
+                current += params.increment_value;
+                point_index += 1;
+                continue;
+            }
+        };
         
+        running.add(&measurement);
         measurements.push(measurement);
         pb.inc(1);
 
@@ -1280,31 +2122,24 @@ async fn fast_linear_search(
             if measurements.len() < 10 {
                 // ✅ ДЛЯ ПЕРВЫХ 9 ИТЕРАЦИЙ: показываем P95
                 let current_best = measurements.iter()
-                    .min_by(|a, b| {
-                        a.statistics.p95.partial_cmp(&b.statistics.p95).unwrap()
-                    })
+                    .min_by(|a, b| a.statistics.p95.total_cmp(&b.statistics.p95))
                     .unwrap();
                 pb.println(format!("       Current best: {:.4} ms (P95: {:.4} ms)",
                     current_best.resolution_ms,
                     current_best.statistics.p95));
             } else {
                 // ✅ ДЛЯ 10+ ИТЕРАЦИЙ: показываем TOPSIS
-                let temp_aggregated = aggregate_measurements(&measurements);
-                let temp_topsis = topsis_ranking(&temp_aggregated);
-                if !temp_topsis.is_empty() {
-                    let best = &temp_topsis[0];
-                    pb.println(format!("       Current best: {:.4} ms (TOPSIS: {:.4})",
-                        best.resolution_ms,
-                        best.closeness_coefficient));
+                if let Some((best_ms, closeness)) = running.leader() {
+                    pb.println(format!("       Current best: {:.4} ms (TOPSIS: {:.4})", best_ms, closeness));
                     
                     // ✅ EARLY STOPPING LOGIC (ИСПРАВЛЕНО!)
                     // Сравниваем текущую точку с лучшей по resolution_ms
-                    if (current - best.resolution_ms).abs() > 0.003 {  // 30 шагов по 0.0001 ms
+                    if (current - best_ms).abs() > 0.003 {  // 30 шагов по 0.0001 ms
                         no_improvement_counter += 1;
                         if no_improvement_counter >= early_stop_threshold {
                             println!("\n✅ Early stopping triggered: {} points without improvement", 
                                      early_stop_threshold);
-                            println!("   Best found: {:.4} ms", best.resolution_ms);
+                            println!("   Best found: {:.4} ms", best_ms);
                             break;
                         }
                     } else {
@@ -1342,30 +2177,28 @@ async fn fast_linear_search(
     }
     
     let total_time = start_time.elapsed().as_secs_f64() / 60.0;
-    pb.finish_with_message("fast linear search completed");
+    pb.finish_with_message(if cancelled { "fast linear search cancelled" } else { "fast linear search completed" });
+    if measurements.is_empty() {
+        return Err(cancelled_before_start());
+    }
     
-    let aggregated = aggregate_measurements(&measurements);
-    let topsis_results = topsis_ranking(&aggregated);
+    let aggregated = final_measurements(&measurements, params);
+    let topsis_results = topsis_ranking(&aggregated, &criteria);
+    let criteria = resolve_weights(&aggregated, &criteria);
     
     println!("\n✅ Fast linear search completed:");
     println!("   Points checked: {}/{}", measurements.len(), total_points);
     println!("   Unique: {}", aggregated.len());
     println!("   Total time: {:.1} minutes\n", total_time);
 
-    // ✅ TOP-5 RESULTS (как в других методах)
-    println!("\n{}", localization.get(LocalizationKey::TopsisRanking));
-    println!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━\n");
-    
-    for (i, result) in topsis_results.iter().take(5).enumerate() {
-        let marker = if i == 0 { "🥇" } else if i == 1 { "🥈" } else if i == 2 { "🥉" } else { "    " };
-        println!("{}  Rank {}: {:.4} ms", marker, result.rank, result.resolution_ms);
-        println!("   TOPSIS Score: {:.4}", result.closeness_coefficient);
-        println!("   P95(Δ): {:.4} ms", result.criteria_scores.p95_delta);
-        println!("   MAD(Δ): {:.4} ms", result.criteria_scores.mad);
-        println!("   P95(Δ): {:.4} ms", result.criteria_scores.p99_delta);
-        println!("   95% CI width: {:.4} ms", result.criteria_scores.confidence_width);
-        println!();
-    }
+    print_top_ranking(&topsis_results, Some(&criteria), localization);
+
+    let significance = winner_significance(&topsis_results, &aggregated, RUNNERS_UP_TESTED);
+    print_winner_significance(significance.as_ref(), localization);
+    let rankers = compare_rankers(&aggregated, &criteria);
+    print_ranker_comparison(rankers.as_ref(), localization);
+    let sensitivity = weight_sensitivity(&aggregated, &criteria, params.sensitivity_samples);
+    print_weight_sensitivity(sensitivity.as_ref(), localization);
 
     let best = &topsis_results[0];
     println!("✅ RECOMMENDED VALUE: {:.4} ms", best.resolution_ms);
@@ -1378,103 +2211,90 @@ async fn fast_linear_search(
         topsis_score: topsis_results[0].closeness_coefficient,
         aggregated_measurements: aggregated,
         topsis_rankings: topsis_results,
+        partial: cancelled,
+        significance,
+        rankers,
+        sensitivity,
+        surrogate: None,
+        criteria,
+        failed_points,
     })
 }
-/// Force kill all SetTimerResolution.exe instances using multiple methods (quiet version for internal use)
-fn kill_all_timer_processes() -> io::Result<()> {
-    // Silent version without output
-    let _ = Command::new("powershell")
-        .args(&["-NoProfile", "-ExecutionPolicy", "Bypass", "-Command",
-            "Get-Process -Name SetTimerResolution -ErrorAction SilentlyContinue | Stop-Process -Force"])
-        .output();
-    std::thread::sleep(std::time::Duration::from_millis(200));
-    Ok(())
-}
 
-/// Force kill all SetTimerResolution.exe instances using multiple methods
-fn force_kill_all_timer_processes() -> io::Result<()> {
-    println!("   Attempting to kill SetTimerResolution.exe processes...");
+#[cfg(test)]
+mod tests {
+    use super::*;
 
-    // Method 1: PowerShell (more reliable if taskkill is disabled)
-    let ps_result = Command::new("powershell")
-        .args(&[
-            "-NoProfile",
-            "-ExecutionPolicy", "Bypass",
-            "-Command",
-            "Get-Process -Name SetTimerResolution -ErrorAction SilentlyContinue | ForEach-Object { Stop-Process -Id $_.Id -Force }"
-        ])
-        .output();
-    match ps_result {
-        Ok(output) if output.status.success() => {
-            println!("   ✓ PowerShell kill method succeeded");
-        },
-        Ok(output) => {
-            let stderr = String::from_utf8_lossy(&output.stderr);
-            if !stderr.is_empty() && !stderr.contains("Cannot find") {
-                eprintln!("   ⚠️ PowerShell warning: {}", stderr);
-            }
-        },
-        Err(e) => {
-            eprintln!("   ⚠️ PowerShell method failed: {}", e);
-        }
+    /// Deltas centred on `centre` with a deterministic spread of `spread`
+    fn measurement(resolution_ms: f64, centre: f64, spread: f64) -> TimerMeasurement {
+        let samples = (0..64)
+            .map(|i| centre + spread * ((i * 37 % 64) as f64 / 63.0 - 0.5))
+            .collect();
+        TimerMeasurement::from_samples(resolution_ms, samples)
     }
-    std::thread::sleep(std::time::Duration::from_millis(300));
 
-    // Method 2: taskkill (if service is running)
-    let taskkill_result = Command::new("taskkill")
-        .args(&["/F", "/IM", "SetTimerResolution.exe", "/T"])
-        .output();
-    match taskkill_result {
-        Ok(output) if output.status.success() => {
-            println!("   ✓ taskkill method succeeded");
-        },
-        Ok(output) => {
-            let stderr = String::from_utf8_lossy(&output.stderr);
-            if stderr.contains("disabled") || stderr.contains("отключена") {
-                println!("   ℹ️ taskkill service is disabled (using PowerShell only)");
-            } else if !stderr.contains("not found") && !stderr.contains("не найден") {
-                eprintln!("   ⚠️ taskkill warning: {}", stderr);
-            }
-        },
-        Err(_) => {
-            println!("   ℹ️ taskkill not available");
-        }
+    fn full_ranking_leader(aggregate: &RunningAggregate) -> f64 {
+        let summaries: Vec<&TimerMeasurement> = aggregate.summaries().collect();
+        topsis_ranking(&summaries, &aggregate.criteria)[0].resolution_ms
     }
-    std::thread::sleep(std::time::Duration::from_millis(500));
 
-    // Method 3: wmic (last resort)
-    let wmic_result = Command::new("wmic")
-        .args(&["process", "where", "name='SetTimerResolution.exe'", "delete"])
-        .output();
-    if let Ok(output) = wmic_result {
-        if output.status.success() {
-            println!("   ✓ wmic method succeeded");
+    #[test]
+    fn running_leader_matches_full_ranking() {
+        let criteria = RankingCriteria::default();
+        let mut aggregate = RunningAggregate::new(&criteria);
+        for i in 0..40 {
+            let resolution_ms = 0.5 + i as f64 * 0.0005;
+            // Tilted so that no two resolutions tie
+            let distance = (i as f64 - 25.0).abs() + i as f64 * 0.01;
+            aggregate.add(&measurement(resolution_ms, 0.02 + distance * 0.002, 0.01 + distance * 0.001));
         }
+        assert_eq!(aggregate.summarised, 40);
+        let (leader, _) = aggregate.leader().unwrap();
+        assert!((leader - 0.5125).abs() < 1e-9);
+        assert_eq!(leader, full_ranking_leader(&aggregate));
+
+        // Repeated adds to the leader that make it the worst point hand the lead on
+        for _ in 0..3 {
+            aggregate.add(&measurement(0.5125, 0.5, 0.4));
+            assert_eq!(aggregate.summarised, 40);
+            let (leader, _) = aggregate.leader().unwrap();
+            assert_ne!(leader, 0.5125);
+            assert_eq!(leader, full_ranking_leader(&aggregate));
+        }
+
+        // Repeated adds to the new leader that only sharpen it keep the lead
+        let (leader, _) = aggregate.leader().unwrap();
+        aggregate.add(&measurement(leader, 0.022, 0.011));
+        aggregate.add(&measurement(leader, 0.022, 0.011));
+        assert_eq!(aggregate.leader().unwrap().0, leader);
+        assert_eq!(leader, full_ranking_leader(&aggregate));
     }
-    std::thread::sleep(std::time::Duration::from_millis(300));
 
-    // Final check
-    let remaining = count_timer_processes();
-    if remaining > 0 {
-        println!("   ⚠️ {} instance(s) still remain after cleanup", remaining);
-        Err(Error::new(ErrorKind::Other,
-            format!("{} SetTimerResolution.exe instance(s) could not be killed", remaining)))
-    } else {
-        println!("   ✓ All instances successfully killed");
-        Ok(())
+    #[test]
+    fn unusable_samples_skip_the_point() {
+        let cancel = CancellationToken::new();
+        let mut failed_points = Vec::new();
+        let error = Error::new(ErrorKind::InvalidData, StatisticsError::Empty);
+        skip_failed_point(0.5, error, &cancel, &mut failed_points).unwrap();
+
+        // A failure replayed from a checkpoint is recorded again with its reason
+        let replayed = FailedPoint { resolution_ms: 0.501, reason: "1 of 3 samples are not finite".to_string() };
+        skip_failed_point(0.501, Error::new(ErrorKind::InvalidData, replayed), &cancel, &mut failed_points).unwrap();
+
+        let recorded: Vec<(f64, &str)> = failed_points.iter().map(|f| (f.resolution_ms, f.reason.as_str())).collect();
+        assert_eq!(recorded, vec![(0.5, "no samples"), (0.501, "1 of 3 samples are not finite")]);
     }
-}
 
-/// Count running SetTimerResolution.exe processes for diagnostics
-fn count_timer_processes() -> usize {
-    let output = Command::new("tasklist")
-        .arg("/FI")
-        .arg("IMAGENAME eq SetTimerResolution.exe")
-        .output();
-    if let Ok(output) = output {
-        let stdout = String::from_utf8_lossy(&output.stdout);
-        stdout.matches("SetTimerResolution.exe").count()
-    } else {
-        0
+    #[test]
+    fn other_errors_stop_the_search_unless_cancelled() {
+        let cancel = CancellationToken::new();
+        let mut failed_points = Vec::new();
+        let error = skip_failed_point(0.5, Error::other("probe crashed"), &cancel, &mut failed_points).unwrap_err();
+        assert_eq!(error.to_string(), "probe crashed");
+
+        // Once cancelled, the interrupted point is dropped without a record
+        cancel.cancel();
+        skip_failed_point(0.5, Error::other("probe killed"), &cancel, &mut failed_points).unwrap();
+        assert!(failed_points.is_empty());
     }
 }
@@ -0,0 +1,161 @@
+//! Adaptive racing of the top TOPSIS candidates
+//!
+//! The sweep gives every point the same number of samples, which is plenty for
+//! clearly bad points and often too few to separate the contenders. After the
+//! sweep the top candidates get one more measurement run per round; after each
+//! round every candidate significantly worse than the current leader drops out;
+//! a candidate significantly better on median or P95 stays in the race.
+//! The race stops when one candidate is left, after `MAX_ROUNDS`, when the time
+//! budget is spent, or on Ctrl+C. The final ranking uses all samples taken.
+
+use std::io;
+use std::time::{Duration, Instant};
+
+use crate::backend::{SleepProbe, TimerBackend};
+use crate::optimization::criteria::WeightingMethod;
+use crate::optimization::equivalence::{format_resolutions, winner_significance, RunnerUpComparison, Verdict, RUNNERS_UP_TESTED};
+use crate::optimization::rankers::compare_rankers;
+use crate::optimization::sensitivity::weight_sensitivity;
+use crate::optimization::topsis::{resolve_weights, topsis_ranking, TopsisScore};
+use crate::ui::localization::{Localization, LocalizationKey};
+
+use super::{
+    final_measurements, measure_point, merge_measurement, print_ranker_comparison, print_weight_sensitivity,
+    print_winner_significance, settle, skip_failed_point, BenchmarkingParameters, CancellationToken, Checkpoint, OptimizationResult,
+};
+
+/// Upper bound on racing rounds, so candidates that are truly tied stop early
+const MAX_ROUNDS: usize = 8;
+
+/// Same 0.0001 ms grid as measurement aggregation
+fn same_point(a: f64, b: f64) -> bool {
+    (a - b).abs() < 0.0001
+}
+
+/// Re-measure the top candidates of `result` until they separate or the budget runs out
+///
+/// Returns `result` unchanged when racing is disabled or the search was cancelled.
+pub(crate) async fn race_top_candidates(
+    result: OptimizationResult,
+    params: &BenchmarkingParameters,
+    backend: &dyn TimerBackend,
+    probe: &dyn SleepProbe,
+    localization: &Localization,
+    checkpoint: &Checkpoint,
+    cancel: &CancellationToken,
+) -> io::Result<OptimizationResult> {
+    if params.racing_candidates < 2 || result.partial || cancel.is_cancelled() {
+        return Ok(result);
+    }
+
+    let mut candidates: Vec<f64> = result.topsis_rankings.iter()
+        .take(params.racing_candidates)
+        .map(|r| r.resolution_ms)
+        .collect();
+    if candidates.len() < 2 {
+        return Ok(result);
+    }
+
+    let budget = Duration::from_secs(params.racing_budget_secs);
+    let start_time = Instant::now();
+    println!("{}", localization.get_racing_title(candidates.len(), budget.as_secs()));
+    println!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
+    println!("{}", localization.get_racing_candidates(&format_resolutions(&candidates)));
+
+    let mut measurements = result.aggregated_measurements.clone();
+    let mut failed_points = result.failed_points.clone();
+    let mut extra_runs = 0;
+    let mut rounds = 0;
+    let stop_reason = 'race: loop {
+        if candidates.len() < 2 {
+            break LocalizationKey::RacingStopSeparated;
+        }
+        if rounds == MAX_ROUNDS {
+            break LocalizationKey::RacingStopRoundLimit;
+        }
+        rounds += 1;
+
+        for &resolution_ms in &candidates {
+            if cancel.is_cancelled() {
+                break 'race LocalizationKey::RacingStopCancelled;
+            }
+            if start_time.elapsed() >= budget {
+                break 'race LocalizationKey::RacingStopBudget;
+            }
+            match measure_point(
+                resolution_ms,
+                params,
+                1,
+                backend,
+                probe,
+                localization,
+                checkpoint,
+            ).await {
+                Ok(measurement) => merge_measurement(&mut measurements, measurement, params.quantile_estimator),
+                Err(e) => skip_failed_point(resolution_ms, e, cancel, &mut failed_points)?,
+            }
+            extra_runs += 1;
+            backend.release()?;
+            settle(backend, 300).await;
+        }
+
+        let ranking: Vec<TopsisScore> = topsis_ranking(&measurements, &result.criteria).into_iter()
+            .filter(|r| candidates.iter().any(|&c| same_point(c, r.resolution_ms)))
+            .collect();
+        let Some(significance) = winner_significance(&ranking, &measurements, candidates.len()) else {
+            break LocalizationKey::RacingStopNoMeasurements;
+        };
+        // One-sided: only a candidate significantly higher than the leader (and
+        // lower on neither median nor P95) is dropped, never one that beats it
+        let (eliminated, kept): (Vec<&RunnerUpComparison>, Vec<&RunnerUpComparison>) = significance.comparisons.iter()
+            .partition(|c| c.verdict == Verdict::WinnerBetter);
+        let eliminated: Vec<f64> = eliminated.iter().map(|c| c.resolution_ms).collect();
+        candidates = std::iter::once(significance.winner_ms)
+            .chain(kept.iter().map(|c| c.resolution_ms))
+            .collect();
+        println!("{}{}",
+            localization.get_racing_round(rounds, significance.winner_ms, candidates.len()),
+            if eliminated.is_empty() {
+                String::new()
+            } else {
+                localization.get_racing_dropped(&format_resolutions(&eliminated))
+            });
+    };
+
+    let aggregated_measurements = final_measurements(&measurements, params);
+    let topsis_rankings = topsis_ranking(&aggregated_measurements, &result.criteria);
+    let criteria = resolve_weights(&aggregated_measurements, &result.criteria);
+    let best = &topsis_rankings[0];
+    println!("{}", localization.get_racing_finished(stop_reason, extra_runs, start_time.elapsed().as_secs_f64()));
+    if same_point(best.resolution_ms, result.optimal_resolution) {
+        println!("{}", localization.get_racing_confirmed(best.resolution_ms));
+    } else {
+        println!("{}", localization.get_racing_changed(result.optimal_resolution, best.resolution_ms));
+    }
+
+    if criteria.weighting != WeightingMethod::Manual {
+        println!("⚖️  {}\n", criteria);
+    }
+    let significance = winner_significance(&topsis_rankings, &aggregated_measurements, RUNNERS_UP_TESTED);
+    print_winner_significance(significance.as_ref(), localization);
+    let rankers = compare_rankers(&aggregated_measurements, &criteria);
+    print_ranker_comparison(rankers.as_ref(), localization);
+    let sensitivity = weight_sensitivity(&aggregated_measurements, &criteria, params.sensitivity_samples);
+    print_weight_sensitivity(sensitivity.as_ref(), localization);
+    println!("{}", localization.get_optimal_value(best.resolution_ms));
+    println!("   {}\n", localization.get_optimal_recommendation((best.resolution_ms * 10_000.0) as i32));
+
+    Ok(OptimizationResult {
+        optimal_resolution: best.resolution_ms,
+        topsis_score: best.closeness_coefficient,
+        aggregated_measurements,
+        topsis_rankings,
+        partial: result.partial,
+        significance,
+        rankers,
+        sensitivity,
+        surrogate: result.surrogate,
+        criteria,
+        failed_points,
+    })
+}
@@ -0,0 +1,222 @@
+//! Coarse-to-fine search
+//!
+//! The fast search walks the range in `IncrementValue` steps and stops after
+//! `EarlyStopThreshold` points without improvement, so a better region further
+//! up the range can go unseen. This search first measures a coarse grid over
+//! the whole range, then narrows in on the best bracket by golden-section
+//! search on the 0.0001 ms grid. Single runs are noisy, so points are compared
+//! by a smoothed objective: the posterior mean of a Gaussian process fitted to
+//! the performance scores of every point measured so far. A grid point is
+//! measured at most once; the final ranking is TOPSIS over all of them.
+
+use std::collections::BTreeSet;
+use std::io::{self, Error, ErrorKind};
+use std::time::Instant;
+
+use indicatif::{ProgressBar, ProgressStyle};
+
+use crate::backend::{SleepProbe, TimerBackend};
+use crate::optimization::bayesian_optimizer::BayesianOptimizer;
+use crate::optimization::equivalence::{winner_significance, RUNNERS_UP_TESTED};
+use crate::optimization::golden_section::{snap_to_grid, GoldenSection};
+use crate::optimization::rankers::compare_rankers;
+use crate::optimization::sensitivity::weight_sensitivity;
+use crate::optimization::topsis::{resolve_weights, topsis_ranking};
+use crate::report::print_top_ranking;
+use crate::ui::localization::{Localization, LocalizationKey};
+
+use super::{
+    cancelled_before_start, final_measurements, measure_point, print_ranker_comparison, print_weight_sensitivity,
+    print_winner_significance, skip_failed_point, BenchmarkingParameters, CancellationToken, Checkpoint, FailedPoint,
+    OptimizationResult, SURROGATE_CURVE_POINTS,
+};
+
+/// Fewest coarse grid points that still leave a bracket around an interior best
+const MIN_COARSE_POINTS: usize = 3;
+
+/// Upper bound on golden-section steps; a bracket of 1000 grid points needs about 15
+const MAX_REFINE_STEPS: usize = 30;
+
+/// Measured points and the surrogate smoothing their scores
+struct Evaluations<'a> {
+    params: &'a BenchmarkingParameters,
+    backend: &'a dyn TimerBackend,
+    probe: &'a dyn SleepProbe,
+    localization: &'a Localization,
+    checkpoint: &'a Checkpoint,
+    cancel: &'a CancellationToken,
+    optimizer: BayesianOptimizer,
+    measured: BTreeSet<i64>,  // Grid keys of the points measured or skipped
+    failed_points: Vec<FailedPoint>,
+}
+
+impl Evaluations<'_> {
+    /// Measure a grid point unless it was measured before; true when a measurement was taken
+    async fn measure(&mut self, resolution_ms: f64) -> io::Result<bool> {
+        if !self.measured.insert((resolution_ms * 10000.0).round() as i64) {
+            return Ok(false);
+        }
+        match measure_point(
+            resolution_ms,
+            self.params,
+            1,
+            self.backend,
+            self.probe,
+            self.localization,
+            self.checkpoint,
+        ).await {
+            Ok(measurement) => self.optimizer.add_observation(measurement),
+            Err(e) => skip_failed_point(resolution_ms, e, self.cancel, &mut self.failed_points)?,
+        }
+        Ok(true)
+    }
+
+    /// Posterior mean of the performance score, lower is better
+    fn objective(&self, resolution_ms: f64) -> f64 {
+        self.optimizer.surrogate().map_or(f64::INFINITY, |gp| gp.predict(resolution_ms).0)
+    }
+}
+
+pub async fn coarse_to_fine_search(
+    params: &BenchmarkingParameters,
+    backend: &dyn TimerBackend,
+    probe: &dyn SleepProbe,
+    localization: &Localization,
+    checkpoint: &Checkpoint,
+    cancel: &CancellationToken,
+) -> io::Result<OptimizationResult> {
+    if params.end_value <= params.start_value {
+        return Err(Error::new(ErrorKind::InvalidInput, "End value must be above the start value"));
+    }
+
+    println!("\n{}", localization.get(LocalizationKey::RefineMethodTitle));
+    println!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
+
+    let criteria = params.ranking()?;
+    let bounds = (params.start_value, params.end_value);
+    let coarse_points = params.coarse_points.max(MIN_COARSE_POINTS);
+    let coarse_step = (bounds.1 - bounds.0) / (coarse_points - 1) as f64;
+    let mut grid: Vec<f64> = (0..coarse_points)
+        .map(|i| snap_to_grid(bounds.0 + i as f64 * coarse_step))
+        .collect();
+    grid.dedup_by_key(|x| (*x * 10000.0).round() as i64);
+
+    println!("{}", localization.get(LocalizationKey::LinearMethodParameters));
+    println!("   {}", localization.get_range(bounds.0, bounds.1));
+    println!("{}", localization.get_refine_coarse_grid(grid.len(), coarse_step));
+    println!("{}", localization.get(LocalizationKey::RefineRefinement));
+    println!("   {}", localization.get_runs_per_point(1));
+    println!("   {}", localization.get_samples_per_run(params.sample_value));
+    println!();
+
+    let mut evaluations = Evaluations {
+        params,
+        backend,
+        probe,
+        localization,
+        checkpoint,
+        cancel,
+        optimizer: BayesianOptimizer::new(coarse_step, criteria.performance_weights()),
+        measured: BTreeSet::new(),
+        failed_points: Vec::new(),
+    };
+    let start_time = Instant::now();
+    let mut cancelled = false;
+
+    let pb = ProgressBar::new(grid.len() as u64);
+    pb.set_style(
+        ProgressStyle::default_bar()
+            .template("{spinner:.green} [{elapsed_precise}] [{bar:40.cyan/blue}] {pos}/{len} {msg}")
+            .unwrap()
+            .progress_chars("##-")
+    );
+    for &x in &grid {
+        if cancel.is_cancelled() {
+            cancelled = true;
+            break;
+        }
+        pb.set_message(format!("{:.4} ms", x));
+        evaluations.measure(x).await?;
+        pb.inc(1);
+    }
+    pb.finish_with_message(localization.get(if cancelled {
+        LocalizationKey::RefineCoarseCancelled
+    } else {
+        LocalizationKey::RefineCoarseCompleted
+    }));
+    let coarse_measured = evaluations.optimizer.observations.len();
+
+    let mut refine_measured = 0;
+    if !cancelled && coarse_measured > 0 {
+        // Bracket the smoothed best of the grid by its neighbours
+        let best = (0..grid.len())
+            .min_by(|&a, &b| evaluations.objective(grid[a]).total_cmp(&evaluations.objective(grid[b])))
+            .unwrap_or(0);
+        let mut bracket = GoldenSection::new(grid[best.saturating_sub(1)], grid[(best + 1).min(grid.len() - 1)]);
+        println!("{}", localization.get_refine_coarse_best(grid[best], bracket.bounds().0, bracket.bounds().1));
+        println!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
+
+        let mut step = 0;
+        while !bracket.converged() && step < MAX_REFINE_STEPS {
+            if cancel.is_cancelled() {
+                cancelled = true;
+                break;
+            }
+            step += 1;
+            let (lower, upper) = bracket.probes();
+            for x in [lower, upper] {
+                if evaluations.measure(x).await? {
+                    refine_measured += 1;
+                }
+            }
+            // Both probes are re-read from the refitted surrogate rather than from earlier steps
+            let lower_is_better = evaluations.objective(lower) <= evaluations.objective(upper);
+            bracket.narrow(lower_is_better);
+            println!("{}", localization.get_refine_step(step, lower, upper, bracket.bounds().0, bracket.bounds().1));
+        }
+        let (lower, upper) = bracket.probes();
+        let minimum = if evaluations.objective(lower) <= evaluations.objective(upper) { lower } else { upper };
+        println!("{}", localization.get_refine_minimum(minimum, step));
+    }
+
+    let Evaluations { optimizer, failed_points, .. } = evaluations;
+    if optimizer.observations.is_empty() {
+        return Err(cancelled_before_start());
+    }
+    let surrogate = optimizer.surrogate_curve(bounds, SURROGATE_CURVE_POINTS);
+
+    let aggregated = final_measurements(&optimizer.observations, params);
+    let topsis_results = topsis_ranking(&aggregated, &criteria);
+    let criteria = resolve_weights(&aggregated, &criteria);
+
+    println!("{}", localization.get(LocalizationKey::RefineCompleted));
+    println!("{}", localization.get_refine_points_measured(aggregated.len(), coarse_measured, refine_measured));
+    println!("{}", localization.get_refine_total_time(start_time.elapsed().as_secs_f64() / 60.0));
+
+    print_top_ranking(&topsis_results, Some(&criteria), localization);
+
+    let significance = winner_significance(&topsis_results, &aggregated, RUNNERS_UP_TESTED);
+    print_winner_significance(significance.as_ref(), localization);
+    let rankers = compare_rankers(&aggregated, &criteria);
+    print_ranker_comparison(rankers.as_ref(), localization);
+    let sensitivity = weight_sensitivity(&aggregated, &criteria, params.sensitivity_samples);
+    print_weight_sensitivity(sensitivity.as_ref(), localization);
+
+    let best = &topsis_results[0];
+    println!("{}", localization.get_optimal_value(best.resolution_ms));
+    println!("   {}\n", localization.get_optimal_recommendation((best.resolution_ms * 10_000.0).round() as i32));
+
+    Ok(OptimizationResult {
+        optimal_resolution: best.resolution_ms,
+        topsis_score: best.closeness_coefficient,
+        aggregated_measurements: aggregated,
+        topsis_rankings: topsis_results,
+        partial: cancelled,
+        significance,
+        rankers,
+        sensitivity,
+        surrogate,
+        criteria,
+        failed_points,
+    })
+}
@@ -64,7 +64,7 @@ impl LocalizationKey {
             LocalizationKey::HybridMethodDesc1 => "贝叶斯 → 局部网格 → 精确细化",
             LocalizationKey::HybridMethodDesc2 => "精度: 高达0.0001毫秒",
             LocalizationKey::HybridMethodDesc3 => "时间: ~5-7分钟",
-            LocalizationKey::MethodChoice => "您的选择 (1/2/3) [默认: 3]: ",
+            LocalizationKey::MethodChoice => "您的选择 (1/2/3/4) [默认: 3]: ",
             LocalizationKey::IterationsLinear => "迭代次数: {} (线性搜索)",
             LocalizationKey::IterationsHybrid => "迭代次数: ~50 (3阶段优化)",
             LocalizationKey::IncrementNotUsed => " (在3阶段方法中不使用)",
@@ -137,6 +137,11 @@ impl LocalizationKey {
             LocalizationKey::Mad => "   MAD(Δ): {:.4} ms",
             LocalizationKey::P99Delta => "   P99(Δ): {:.4} ms",
             LocalizationKey::CiWidth => "   95% CI 宽度: {:.4} ms",
+            LocalizationKey::P50Delta => "   P50(Δ): {:.4} ms",
+            LocalizationKey::MeanDelta => "   平均值(Δ): {:.4} ms",
+            LocalizationKey::StdevDelta => "   标准差(Δ): {:.4} ms",
+            LocalizationKey::OutliersRemoved => "   离群值已移除: {}",
+            LocalizationKey::BimodalityCoefficient => "   双峰系数: {:.3}",
             LocalizationKey::ErrorMutexConflict => "检测到互斥锁冲突",
             LocalizationKey::MutexErrorMessage => "错误消息: {}",
             LocalizationKey::MutexErrorHint => "提示: 另一个计时器分辨率工具可能正在运行",
@@ -185,6 +190,95 @@ impl LocalizationKey {
             LocalizationKey::FastLinearMethodDesc3 => "可配置的早停阈值（快速完成）",
             LocalizationKey::FastLinearMethodDesc4 => "最适合大多数用户（约2-5分钟）",
             LocalizationKey::EarlyStopThreshold => "早停阈值",
+            LocalizationKey::RefineMethod => "粗到精搜索",
+            LocalizationKey::RefineMethodTitle => "🎯 粗到精搜索",
+            LocalizationKey::RefineMethodDesc1 => "在整个范围内进行粗网格扫描（无提前停止）",
+            LocalizationKey::RefineMethodDesc2 => "在最佳区间内进行黄金分割细化",
+            LocalizationKey::RefineMethodDesc3 => "约35个点，对齐到0.0001毫秒网格（约1-2分钟）",
+            LocalizationKey::SignificanceTitle => "📐 显著性（优胜者对比其后候选，基于原始样本的自助法，所有比较合计 95%）",
+            LocalizationKey::SignificanceComparison => "   #{} {:.4} ms: ΔP50 {:+.4} ms, ΔP95 {:+.4} ms → {}",
+            LocalizationKey::VerdictEquivalent => "无法区分",
+            LocalizationKey::VerdictWinnerBetter => "优胜者显著更好",
+            LocalizationKey::VerdictRunnerUpBetter => "候选在 {} 上显著更好",
+            LocalizationKey::SignificanceTie => "🤝 平局: {} ms 在统计上等效",
+            LocalizationKey::SignificanceTieBreak => "   下面的值只是 TOPSIS 的平局决胜结果；这些值的表现应当相同",
+            LocalizationKey::SignificanceNotClearWin => "⚠️  并非明显胜出: {:.4} ms 在 {} 上显著更好；TOPSIS 在其他标准上更偏好优胜者",
+            LocalizationKey::SignificanceClearWin => "✅ 优胜者显著优于全部 {} 个其后候选",
+            LocalizationKey::ListAnd => " 和 ",
+            LocalizationKey::ReportPartial => "\n⚠️  部分结果: 运行在搜索完成前被取消",
+            LocalizationKey::ReportPointsInFile => "   文件中的点数: {}",
+            LocalizationKey::RacingTitle => "\n🏁 自适应竞速: 前 {} 个候选，预算 {} 秒",
+            LocalizationKey::RacingCandidates => "   {} ms",
+            LocalizationKey::RacingRound => "   第 {} 轮: 领先 {:.4} ms，剩余 {} 个",
+            LocalizationKey::RacingDropped => "，淘汰 {} ms",
+            LocalizationKey::RacingStopSeparated => "候选已区分",
+            LocalizationKey::RacingStopRoundLimit => "达到轮数上限",
+            LocalizationKey::RacingStopCancelled => "已取消",
+            LocalizationKey::RacingStopBudget => "时间预算已用完",
+            LocalizationKey::RacingStopNoMeasurements => "没有测量结果",
+            LocalizationKey::RacingFinished => "\n   竞速结束（{}）: {:.1} 秒内额外运行 {} 次",
+            LocalizationKey::RacingConfirmed => "   推荐值已确认: {:.4} ms\n",
+            LocalizationKey::RacingChanged => "   推荐值已更改: {:.4} ms → {:.4} ms\n",
+            LocalizationKey::WarmupDropped => "   预热: 丢弃 {} 个样本",
+            LocalizationKey::WarmupDroppedMs => "（采样的前 {:.0} ms）",
+            LocalizationKey::DriftRuns => "   漂移: {} / {} 次运行内存在超调偏移或趋势",
+            LocalizationKey::StationarityTitle => "⏳ 预热与漂移",
+            LocalizationKey::StationarityDropped => "   丢弃了 {} 个预热样本（{} / {} 个点）",
+            LocalizationKey::StationaritySettle => "   建议稳定时间: 设置分辨率后 {} ms（当前 {} ms）",
+            LocalizationKey::StationarityDrifting => "⚠️  在 {} ms 处运行内超调发生漂移；其统计混合了多个状态",
+            LocalizationKey::RefineCoarseGrid => "   粗网格: {} 个点，间隔 {:.4} ms",
+            LocalizationKey::RefineRefinement => "   细化: 黄金分割，对齐到 0.0001 ms",
+            LocalizationKey::RefineCoarseCancelled => "粗网格已取消",
+            LocalizationKey::RefineCoarseCompleted => "粗网格完成",
+            LocalizationKey::RefineCoarseBest => "\n🔍 粗网格最佳（平滑后）: {:.4} ms，细化区间 [{:.4}, {:.4}] ms",
+            LocalizationKey::RefineStep => "   第 {} 步: {:.4} / {:.4} ms → [{:.4}, {:.4}] ms",
+            LocalizationKey::RefineMinimum => "   平滑最小值: {:.4} ms（{} 步后）",
+            LocalizationKey::RefineCompleted => "\n✅ 由粗到细搜索完成:",
+            LocalizationKey::RefinePointsMeasured => "   已测量点数: {}（粗网格 {}，细化 {}）",
+            LocalizationKey::RefineTotalTime => "   总时间: {:.1} 分钟\n",
+            LocalizationKey::ResumingRun => "↺ 恢复 {} 运行 (来自 {}): 已记录 {} 次测量",
+            LocalizationKey::ResumingRange => "   范围: [{:.4}, {:.4}] 毫秒, 步长 {:.4} 毫秒, {} 个样本 (来自检查点)",
+            LocalizationKey::PointRestored => "   ↺ {:.4} 毫秒已从检查点恢复",
+            LocalizationKey::RankerTitle => "🧭 排序方法一致性",
+            LocalizationKey::RankerWinner => "   {} {:.4} 毫秒{}",
+            LocalizationKey::RankerDominated => " (被支配)",
+            LocalizationKey::RankerParetoFront => "   帕累托前沿: {} 毫秒",
+            LocalizationKey::RankersAgree => "✅ 所有排序方法选择相同的分辨率",
+            LocalizationKey::RankersDisagree => "⚠️  排序方法不一致; 选择取决于决策方法, 保留 TOPSIS 结果",
+            LocalizationKey::SensitivityTitle => "🎲 权重敏感性 ({} 组随机权重)",
+            LocalizationKey::SensitivityWinRate => "   {:.4} 毫秒胜出 {}%",
+            LocalizationKey::SensitivityWeightRange => "   {} {:.2} → 在 {:.2}..{:.2} 范围内优胜者不变",
+            LocalizationKey::SensitivityRobust => "✅ 稳健: {:.4} 毫秒在 {:.0}% 的权重设置下排名第一",
+            LocalizationKey::SensitivityFragile => "⚠️  不稳定: {:.4} 毫秒仅在 {:.0}% 的权重设置下排名第一; 选择取决于权重",
+            LocalizationKey::DistributionTitle => "📊 {:.4} 毫秒处的分布 ({} 个样本)",
+            LocalizationKey::DistributionShape => "   偏度 {:.2}, 超额峰度 {:.2}, 双峰系数 {:.3}, P99.9 {:.4} 毫秒, 最大值 {:.4} 毫秒",
+            LocalizationKey::DistributionBimodal => "⚠️  双峰: 推荐值下的睡眠在两个水平之间交替 (系数高于 {:.3})",
+            LocalizationKey::DistributionBimodalElsewhere => "   其他双峰分辨率 (系数高于 {:.3}): {} 毫秒",
+            LocalizationKey::CtrlCStopping => "\n⏹  Ctrl+C: 当前点完成后停止并保存部分结果",
+            LocalizationKey::CtrlCAgain => "   再次按 Ctrl+C 立即中止",
+            LocalizationKey::CtrlCAborted => "\n⛔ 已中止: 正在释放计时器分辨率",
+            LocalizationKey::CtrlCReleaseFailed => "   释放计时器分辨率失败: {}",
+            LocalizationKey::RankingCriteriaLine => "▸ 排序标准: {}",
+            LocalizationKey::RankingCriteriaDerived => "▸ 排序标准: {} (权重由数据得出: {})",
+            LocalizationKey::AcquisitionBatch => "  {} 个点的批次: {}",
+            LocalizationKey::GpSurrogate => "   GP 代理模型: {} 核, 长度尺度 {:.4} 毫秒, 噪声/信号 {:.3}, 对数边际似然 {:.2}",
+            LocalizationKey::HistoryEmpty => "{} 中没有已保存的运行",
+            LocalizationKey::HistoryId => "Id",
+            LocalizationKey::HistoryMethod => "方法",
+            LocalizationKey::HistoryBackend => "后端",
+            LocalizationKey::HistoryPoints => "点数",
+            LocalizationKey::HistoryOptimal => "最优 (毫秒)",
+            LocalizationKey::HistoryTie => " ({} 个并列)",
+            LocalizationKey::HistoryPartial => " (部分)",
+            LocalizationKey::HistoryCount => "{} 个运行, 位于 {}",
+            LocalizationKey::CompareTitle => "\n📊 比较运行",
+            LocalizationKey::CompareRun => "   {}: {} ({}, {}, HPET: {}, 最优 {:.4} 毫秒{})",
+            LocalizationKey::CompareRunPartial => ", 部分",
+            LocalizationKey::CompareDifferentSystems => "⚠️  运行来自不同的系统 ({} 与 {})",
+            LocalizationKey::CompareResolution => "分辨率 (毫秒)",
+            LocalizationKey::CompareLegend => "   * 95% 显著 (基于原始样本的自助法); ▼ B 中更低 (更好), ▲ B 中更高 (更差)",
+            LocalizationKey::CompareSummary => "   比较了 {} 个分辨率, {} 项显著改进, {} 项显著退化",
+            LocalizationKey::CompareOptimal => "   最优: {:.4} 毫秒 → {:.4} 毫秒",
         }
     }
 }
\ No newline at end of file
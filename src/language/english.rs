@@ -64,7 +64,7 @@ impl LocalizationKey {
             LocalizationKey::HybridMethodDesc1 => "Bayesian в†’ Local Grid в†’ Precise Refinement",
             LocalizationKey::HybridMethodDesc2 => "Precision: up to 0.0001 ms",
             LocalizationKey::HybridMethodDesc3 => "Time: ~5-7 minutes",
-            LocalizationKey::MethodChoice => "Your choice (1/2/3) [default: 3]: ",
+            LocalizationKey::MethodChoice => "Your choice (1/2/3/4) [default: 3]: ",
             LocalizationKey::IterationsLinear => "Iterations: {} (linear search)",
             LocalizationKey::IterationsHybrid => "Iterations: ~50 (3-phase optimization)",
             LocalizationKey::IncrementNotUsed => " (not used in 3-phase method)",
@@ -137,6 +137,11 @@ impl LocalizationKey {
             LocalizationKey::Mad => "   MAD(Δ): {:.4} ms",
             LocalizationKey::P99Delta => "   P99(Δ): {:.4} ms",
             LocalizationKey::CiWidth => "   95% CI width: {:.4} ms",
+            LocalizationKey::P50Delta => "   P50(Δ): {:.4} ms",
+            LocalizationKey::MeanDelta => "   Mean(Δ): {:.4} ms",
+            LocalizationKey::StdevDelta => "   Stdev(Δ): {:.4} ms",
+            LocalizationKey::OutliersRemoved => "   Outliers removed: {}",
+            LocalizationKey::BimodalityCoefficient => "   Bimodality coefficient: {:.3}",
             LocalizationKey::ErrorMutexConflict => "Mutex conflict detected",
             LocalizationKey::MutexErrorMessage => "Error message: {}",
             LocalizationKey::MutexErrorHint => "Hint: Another timer resolution utility may be running",
@@ -185,6 +190,95 @@ impl LocalizationKey {
             LocalizationKey::FastLinearMethodDesc3 => "Configurable early stop threshold (fast completion)",
             LocalizationKey::FastLinearMethodDesc4 => "Optimal for most users (~2-5 minutes)",
             LocalizationKey::EarlyStopThreshold => "Early Stop Threshold",
+            LocalizationKey::RefineMethod => "Coarse-to-Fine Search",
+            LocalizationKey::RefineMethodTitle => "🎯 COARSE-TO-FINE SEARCH",
+            LocalizationKey::RefineMethodDesc1 => "Coarse grid over the whole range (no early stop)",
+            LocalizationKey::RefineMethodDesc2 => "Golden-section refinement around the best bracket",
+            LocalizationKey::RefineMethodDesc3 => "About 35 points, snapped to 0.0001 ms (~1-2 minutes)",
+            LocalizationKey::SignificanceTitle => "📐 SIGNIFICANCE (winner vs runners-up, bootstrap on raw samples, 95% family-wise)",
+            LocalizationKey::SignificanceComparison => "   #{} {:.4} ms: ΔP50 {:+.4} ms, ΔP95 {:+.4} ms → {}",
+            LocalizationKey::VerdictEquivalent => "not distinguishable",
+            LocalizationKey::VerdictWinnerBetter => "winner significantly better",
+            LocalizationKey::VerdictRunnerUpBetter => "runner-up significantly better on {}",
+            LocalizationKey::SignificanceTie => "🤝 TIE: {} ms are statistically equivalent",
+            LocalizationKey::SignificanceTieBreak => "   The value below is only the TOPSIS tie-break; any of these should perform the same",
+            LocalizationKey::SignificanceNotClearWin => "⚠️  Not a clear win: {:.4} ms is significantly better on {}; TOPSIS prefers the winner on the other criteria",
+            LocalizationKey::SignificanceClearWin => "✅ The winner is significantly better than all {} runners-up",
+            LocalizationKey::ListAnd => " and ",
+            LocalizationKey::ReportPartial => "\n⚠️  Partial results: the run was cancelled before the search finished",
+            LocalizationKey::ReportPointsInFile => "   Points in file: {}",
+            LocalizationKey::RacingTitle => "\n🏁 ADAPTIVE RACING: top {} candidates, budget {} s",
+            LocalizationKey::RacingCandidates => "   {} ms",
+            LocalizationKey::RacingRound => "   Round {}: leader {:.4} ms, {} left",
+            LocalizationKey::RacingDropped => ", dropped {} ms",
+            LocalizationKey::RacingStopSeparated => "candidates separated",
+            LocalizationKey::RacingStopRoundLimit => "round limit reached",
+            LocalizationKey::RacingStopCancelled => "cancelled",
+            LocalizationKey::RacingStopBudget => "time budget spent",
+            LocalizationKey::RacingStopNoMeasurements => "no measurements",
+            LocalizationKey::RacingFinished => "\n   Racing finished ({}): {} extra runs in {:.1} s",
+            LocalizationKey::RacingConfirmed => "   Recommendation confirmed: {:.4} ms\n",
+            LocalizationKey::RacingChanged => "   Recommendation changed: {:.4} ms → {:.4} ms\n",
+            LocalizationKey::WarmupDropped => "   Warm-up: {} samples dropped",
+            LocalizationKey::WarmupDroppedMs => " (first {:.0} ms of sampling)",
+            LocalizationKey::DriftRuns => "   Drift: overshoot shifts or trends within {} of {} runs",
+            LocalizationKey::StationarityTitle => "⏳ WARM-UP AND DRIFT",
+            LocalizationKey::StationarityDropped => "   {} warm-up samples dropped at {} of {} points",
+            LocalizationKey::StationaritySettle => "   Suggested settle time: {} ms after applying a resolution (now {} ms)",
+            LocalizationKey::StationarityDrifting => "⚠️  Overshoot drifts within runs at {} ms; their statistics mix several states",
+            LocalizationKey::RefineCoarseGrid => "   Coarse grid: {} points, every {:.4} ms",
+            LocalizationKey::RefineRefinement => "   Refinement: golden section, snapped to 0.0001 ms",
+            LocalizationKey::RefineCoarseCancelled => "coarse grid cancelled",
+            LocalizationKey::RefineCoarseCompleted => "coarse grid completed",
+            LocalizationKey::RefineCoarseBest => "\n🔍 Coarse best (smoothed): {:.4} ms, refining [{:.4}, {:.4}] ms",
+            LocalizationKey::RefineStep => "   Step {}: {:.4} / {:.4} ms → [{:.4}, {:.4}] ms",
+            LocalizationKey::RefineMinimum => "   Smoothed minimum: {:.4} ms after {} steps",
+            LocalizationKey::RefineCompleted => "\n✅ Coarse-to-fine search completed:",
+            LocalizationKey::RefinePointsMeasured => "   Points measured: {} (coarse {}, refinement {})",
+            LocalizationKey::RefineTotalTime => "   Total time: {:.1} minutes\n",
+            LocalizationKey::ResumingRun => "↺ Resuming {} run from {}: {} measurements recorded",
+            LocalizationKey::ResumingRange => "   Range: [{:.4}, {:.4}] ms, step {:.4} ms, {} samples (from checkpoint)",
+            LocalizationKey::PointRestored => "   ↺ {:.4} ms restored from checkpoint",
+            LocalizationKey::RankerTitle => "🧭 RANKER AGREEMENT",
+            LocalizationKey::RankerWinner => "   {} {:.4} ms{}",
+            LocalizationKey::RankerDominated => " (dominated)",
+            LocalizationKey::RankerParetoFront => "   Pareto front: {} ms",
+            LocalizationKey::RankersAgree => "✅ All rankers pick the same resolution",
+            LocalizationKey::RankersDisagree => "⚠️  Rankers disagree; the choice depends on the decision method, TOPSIS is kept",
+            LocalizationKey::SensitivityTitle => "🎲 WEIGHT SENSITIVITY ({} random weightings)",
+            LocalizationKey::SensitivityWinRate => "   {:.4} ms wins {}%",
+            LocalizationKey::SensitivityWeightRange => "   {} {:.2} → winner holds for {:.2}..{:.2}",
+            LocalizationKey::SensitivityRobust => "✅ Robust: {:.4} ms ranks first for {:.0}% of weight settings",
+            LocalizationKey::SensitivityFragile => "⚠️  Fragile: {:.4} ms ranks first for only {:.0}% of weight settings; the choice depends on the weights",
+            LocalizationKey::DistributionTitle => "📊 DISTRIBUTION AT {:.4} ms ({} samples)",
+            LocalizationKey::DistributionShape => "   Skewness {:.2}, excess kurtosis {:.2}, bimodality {:.3}, P99.9 {:.4} ms, max {:.4} ms",
+            LocalizationKey::DistributionBimodal => "⚠️  Bimodal: sleeps at the recommended value alternate between two levels (coefficient above {:.3})",
+            LocalizationKey::DistributionBimodalElsewhere => "   Bimodal elsewhere (coefficient above {:.3}): {} ms",
+            LocalizationKey::CtrlCStopping => "\n⏹  Ctrl+C: stopping after the current point and saving partial results",
+            LocalizationKey::CtrlCAgain => "   Press Ctrl+C again to abort immediately",
+            LocalizationKey::CtrlCAborted => "\n⛔ Aborted: releasing timer resolution",
+            LocalizationKey::CtrlCReleaseFailed => "   Failed to release timer resolution: {}",
+            LocalizationKey::RankingCriteriaLine => "▸ Ranking criteria: {}",
+            LocalizationKey::RankingCriteriaDerived => "▸ Ranking criteria: {} (weights derived from the data: {})",
+            LocalizationKey::AcquisitionBatch => "  Batch of {}: {}",
+            LocalizationKey::GpSurrogate => "   GP surrogate: {} kernel, length scale {:.4} ms, noise/signal {:.3}, log ML {:.2}",
+            LocalizationKey::HistoryEmpty => "No runs stored in {}",
+            LocalizationKey::HistoryId => "Id",
+            LocalizationKey::HistoryMethod => "Method",
+            LocalizationKey::HistoryBackend => "Backend",
+            LocalizationKey::HistoryPoints => "Points",
+            LocalizationKey::HistoryOptimal => "Optimal (ms)",
+            LocalizationKey::HistoryTie => " (tie of {})",
+            LocalizationKey::HistoryPartial => " (partial)",
+            LocalizationKey::HistoryCount => "{} runs in {}",
+            LocalizationKey::CompareTitle => "\n📊 Comparing runs",
+            LocalizationKey::CompareRun => "   {}: {} ({}, {}, HPET: {}, optimal {:.4} ms{})",
+            LocalizationKey::CompareRunPartial => ", partial",
+            LocalizationKey::CompareDifferentSystems => "⚠️  Runs come from different systems ({} vs {})",
+            LocalizationKey::CompareResolution => "Resolution (ms)",
+            LocalizationKey::CompareLegend => "   * significant at 95% (bootstrap on raw samples); ▼ lower in B (better), ▲ higher in B (worse)",
+            LocalizationKey::CompareSummary => "   {} resolutions compared, {} significant improvements, {} significant regressions",
+            LocalizationKey::CompareOptimal => "   Optimal: {:.4} ms → {:.4} ms",
         }
     }
 }
@@ -64,7 +64,7 @@ impl LocalizationKey {
             LocalizationKey::HybridMethodDesc1 => "Байесовская → Локальная сетка → Точное уточнение",
             LocalizationKey::HybridMethodDesc2 => "Точность: до 0.0001 ms",
             LocalizationKey::HybridMethodDesc3 => "Время: ~5-7 минут",
-            LocalizationKey::MethodChoice => "Ваш выбор (1/2/3) [по умолчанию: 3]: ",
+            LocalizationKey::MethodChoice => "Ваш выбор (1/2/3/4) [по умолчанию: 3]: ",
             LocalizationKey::IterationsLinear => "Итераций: {} (линейный перебор)",
             LocalizationKey::IterationsHybrid => "Итераций: ~50 (3-фазная оптимизация)",
             LocalizationKey::IncrementNotUsed => " (не используется в 3-фазном методе)",
@@ -138,6 +138,11 @@ impl LocalizationKey {
             LocalizationKey::Mad => "     MAD:          {:.4} ms",
             LocalizationKey::P99Delta => "     P99 Delta:    {:.4} ms",
             LocalizationKey::CiWidth => "     CI Width:     {:.4} ms",
+            LocalizationKey::P50Delta => "     P50 Delta:    {:.4} ms",
+            LocalizationKey::MeanDelta => "     Mean Delta:   {:.4} ms",
+            LocalizationKey::StdevDelta => "     Stdev:        {:.4} ms",
+            LocalizationKey::OutliersRemoved => "     Выбросов:     {}",
+            LocalizationKey::BimodalityCoefficient => "     Бимодальность: {:.3}",
             LocalizationKey::ErrorMutexConflict => "\n❌ КРИТИЧЕСКАЯ ОШИБКА: Конфликт мьютекса SetTimerResolution!",
             LocalizationKey::MutexErrorMessage => "   Сообщение: {}",
             LocalizationKey::MutexErrorHint => "\n   Это означает, что другой экземпляр SetTimerResolution.exe УЖЕ запущен!",
@@ -186,6 +191,95 @@ impl LocalizationKey {
             LocalizationKey::FastLinearMethodDesc3 => "Настраиваемый порог ранней остановки (быстрое завершение)",
             LocalizationKey::FastLinearMethodDesc4 => "Оптимален для большинства пользователей (~2-5 минут)",
             LocalizationKey::EarlyStopThreshold => "Порог ранней остановки",
+            LocalizationKey::RefineMethod => "Поиск от грубого к точному",
+            LocalizationKey::RefineMethodTitle => "🎯 ПОИСК ОТ ГРУБОГО К ТОЧНОМУ",
+            LocalizationKey::RefineMethodDesc1 => "Грубая сетка по всему диапазону (без ранней остановки)",
+            LocalizationKey::RefineMethodDesc2 => "Уточнение золотым сечением вокруг лучшего интервала",
+            LocalizationKey::RefineMethodDesc3 => "Около 35 точек с шагом сетки 0.0001 мс (~1-2 минуты)",
+            LocalizationKey::SignificanceTitle => "📐 ЗНАЧИМОСТЬ (победитель против ближайших, бутстреп по исходным замерам, 95% для всех сравнений вместе)",
+            LocalizationKey::SignificanceComparison => "   #{} {:.4} мс: ΔP50 {:+.4} мс, ΔP95 {:+.4} мс → {}",
+            LocalizationKey::VerdictEquivalent => "неразличимы",
+            LocalizationKey::VerdictWinnerBetter => "победитель значимо лучше",
+            LocalizationKey::VerdictRunnerUpBetter => "кандидат значимо лучше по {}",
+            LocalizationKey::SignificanceTie => "🤝 НИЧЬЯ: {} мс статистически эквивалентны",
+            LocalizationKey::SignificanceTieBreak => "   Значение ниже — лишь выбор TOPSIS среди равных; любое из них должно работать одинаково",
+            LocalizationKey::SignificanceNotClearWin => "⚠️  Победа не явная: {:.4} мс значимо лучше по {}; TOPSIS предпочитает победителя по остальным критериям",
+            LocalizationKey::SignificanceClearWin => "✅ Победитель значимо лучше всех {} ближайших кандидатов",
+            LocalizationKey::ListAnd => " и ",
+            LocalizationKey::ReportPartial => "\n⚠️  Частичные результаты: запуск был прерван до завершения поиска",
+            LocalizationKey::ReportPointsInFile => "   Точек в файле: {}",
+            LocalizationKey::RacingTitle => "\n🏁 АДАПТИВНАЯ ГОНКА: {} лучших кандидатов, бюджет {} с",
+            LocalizationKey::RacingCandidates => "   {} мс",
+            LocalizationKey::RacingRound => "   Раунд {}: лидер {:.4} мс, осталось {}",
+            LocalizationKey::RacingDropped => ", выбыли {} мс",
+            LocalizationKey::RacingStopSeparated => "кандидаты разделены",
+            LocalizationKey::RacingStopRoundLimit => "достигнут лимит раундов",
+            LocalizationKey::RacingStopCancelled => "отменено",
+            LocalizationKey::RacingStopBudget => "бюджет времени исчерпан",
+            LocalizationKey::RacingStopNoMeasurements => "нет измерений",
+            LocalizationKey::RacingFinished => "\n   Гонка завершена ({}): {} дополнительных запусков за {:.1} с",
+            LocalizationKey::RacingConfirmed => "   Рекомендация подтверждена: {:.4} мс\n",
+            LocalizationKey::RacingChanged => "   Рекомендация изменена: {:.4} мс → {:.4} мс\n",
+            LocalizationKey::WarmupDropped => "   Прогрев: отброшено замеров: {}",
+            LocalizationKey::WarmupDroppedMs => " (первые {:.0} мс замеров)",
+            LocalizationKey::DriftRuns => "   Дрейф: сдвиг или тренд задержки внутри {} из {} запусков",
+            LocalizationKey::StationarityTitle => "⏳ ПРОГРЕВ И ДРЕЙФ",
+            LocalizationKey::StationarityDropped => "   Отброшено замеров прогрева: {} в {} из {} точек",
+            LocalizationKey::StationaritySettle => "   Рекомендуемая пауза: {} мс после установки разрешения (сейчас {} мс)",
+            LocalizationKey::StationarityDrifting => "⚠️  Задержка дрейфует внутри запусков на {} мс; их статистика смешивает несколько состояний",
+            LocalizationKey::RefineCoarseGrid => "   Грубая сетка: {} точек, шаг {:.4} мс",
+            LocalizationKey::RefineRefinement => "   Уточнение: золотое сечение с шагом сетки 0.0001 мс",
+            LocalizationKey::RefineCoarseCancelled => "грубая сетка отменена",
+            LocalizationKey::RefineCoarseCompleted => "грубая сетка завершена",
+            LocalizationKey::RefineCoarseBest => "\n🔍 Лучшая точка сетки (сглаженно): {:.4} мс, уточнение [{:.4}, {:.4}] мс",
+            LocalizationKey::RefineStep => "   Шаг {}: {:.4} / {:.4} мс → [{:.4}, {:.4}] мс",
+            LocalizationKey::RefineMinimum => "   Сглаженный минимум: {:.4} мс после {} шагов",
+            LocalizationKey::RefineCompleted => "\n✅ Поиск от грубого к точному завершён:",
+            LocalizationKey::RefinePointsMeasured => "   Измерено точек: {} (сетка {}, уточнение {})",
+            LocalizationKey::RefineTotalTime => "   Общее время: {:.1} мин\n",
+            LocalizationKey::ResumingRun => "↺ Возобновление запуска {} из {}: записано измерений: {}",
+            LocalizationKey::ResumingRange => "   Диапазон: [{:.4}, {:.4}] мс, шаг {:.4} мс, выборок: {} (из контрольной точки)",
+            LocalizationKey::PointRestored => "   ↺ {:.4} мс восстановлено из контрольной точки",
+            LocalizationKey::RankerTitle => "🧭 СОГЛАСИЕ МЕТОДОВ РАНЖИРОВАНИЯ",
+            LocalizationKey::RankerWinner => "   {} {:.4} мс{}",
+            LocalizationKey::RankerDominated => " (доминируется)",
+            LocalizationKey::RankerParetoFront => "   Фронт Парето: {} мс",
+            LocalizationKey::RankersAgree => "✅ Все методы выбирают одно и то же разрешение",
+            LocalizationKey::RankersDisagree => "⚠️  Методы расходятся; выбор зависит от метода принятия решений, оставлен TOPSIS",
+            LocalizationKey::SensitivityTitle => "🎲 ЧУВСТВИТЕЛЬНОСТЬ К ВЕСАМ ({} случайных наборов весов)",
+            LocalizationKey::SensitivityWinRate => "   {:.4} мс побеждает в {}%",
+            LocalizationKey::SensitivityWeightRange => "   {} {:.2} → победитель сохраняется при {:.2}..{:.2}",
+            LocalizationKey::SensitivityRobust => "✅ Устойчиво: {:.4} мс на первом месте для {:.0}% наборов весов",
+            LocalizationKey::SensitivityFragile => "⚠️  Неустойчиво: {:.4} мс на первом месте лишь для {:.0}% наборов весов; выбор зависит от весов",
+            LocalizationKey::DistributionTitle => "📊 РАСПРЕДЕЛЕНИЕ ПРИ {:.4} мс ({} замеров)",
+            LocalizationKey::DistributionShape => "   Асимметрия {:.2}, эксцесс {:.2}, бимодальность {:.3}, P99.9 {:.4} мс, максимум {:.4} мс",
+            LocalizationKey::DistributionBimodal => "⚠️  Бимодальность: паузы при рекомендованном значении чередуются между двумя уровнями (коэффициент выше {:.3})",
+            LocalizationKey::DistributionBimodalElsewhere => "   Бимодальность в других точках (коэффициент выше {:.3}): {} мс",
+            LocalizationKey::CtrlCStopping => "\n⏹  Ctrl+C: остановка после текущей точки и сохранение частичных результатов",
+            LocalizationKey::CtrlCAgain => "   Нажмите Ctrl+C ещё раз для немедленного прерывания",
+            LocalizationKey::CtrlCAborted => "\n⛔ Прервано: освобождение разрешения таймера",
+            LocalizationKey::CtrlCReleaseFailed => "   Не удалось освободить разрешение таймера: {}",
+            LocalizationKey::RankingCriteriaLine => "▸ Критерии ранжирования: {}",
+            LocalizationKey::RankingCriteriaDerived => "▸ Критерии ранжирования: {} (веса выводятся из данных: {})",
+            LocalizationKey::AcquisitionBatch => "  Пакет из {}: {}",
+            LocalizationKey::GpSurrogate => "   GP-модель: ядро {}, масштаб длины {:.4} мс, шум/сигнал {:.3}, log ML {:.2}",
+            LocalizationKey::HistoryEmpty => "Нет сохранённых запусков в {}",
+            LocalizationKey::HistoryId => "Id",
+            LocalizationKey::HistoryMethod => "Метод",
+            LocalizationKey::HistoryBackend => "Бэкенд",
+            LocalizationKey::HistoryPoints => "Точки",
+            LocalizationKey::HistoryOptimal => "Оптимум (мс)",
+            LocalizationKey::HistoryTie => " (ничья из {})",
+            LocalizationKey::HistoryPartial => " (частично)",
+            LocalizationKey::HistoryCount => "Запусков: {} в {}",
+            LocalizationKey::CompareTitle => "\n📊 Сравнение запусков",
+            LocalizationKey::CompareRun => "   {}: {} ({}, {}, HPET: {}, оптимум {:.4} мс{})",
+            LocalizationKey::CompareRunPartial => ", частично",
+            LocalizationKey::CompareDifferentSystems => "⚠️  Запуски сделаны на разных системах ({} и {})",
+            LocalizationKey::CompareResolution => "Разрешение (мс)",
+            LocalizationKey::CompareLegend => "   * значимо на уровне 95% (бутстреп по исходным замерам); ▼ ниже в B (лучше), ▲ выше в B (хуже)",
+            LocalizationKey::CompareSummary => "   Сравнено разрешений: {}, значимых улучшений: {}, значимых ухудшений: {}",
+            LocalizationKey::CompareOptimal => "   Оптимум: {:.4} мс → {:.4} мс",
         }
     }
 }
@@ -64,7 +64,7 @@ impl LocalizationKey {
             LocalizationKey::HybridMethodDesc1 => "Байесівська → Локальна сітка → Точне уточнення",
             LocalizationKey::HybridMethodDesc2 => "Точність: до 0.0001 мс",
             LocalizationKey::HybridMethodDesc3 => "Час: ~5-7 хвилин",
-            LocalizationKey::MethodChoice => "Ваш вибір (1/2/3) [за замовчуванням: 3]: ",
+            LocalizationKey::MethodChoice => "Ваш вибір (1/2/3/4) [за замовчуванням: 3]: ",
             LocalizationKey::IterationsLinear => "Ітерацій: {} (лінійний пошук)",
             LocalizationKey::IterationsHybrid => "Ітерацій: ~50 (3-фазна оптимізація)",
             LocalizationKey::IncrementNotUsed => " (не використовується в 3-фазному методі)",
@@ -137,6 +137,11 @@ impl LocalizationKey {
             LocalizationKey::Mad => "   MAD(Δ): {:.4} мс",
             LocalizationKey::P99Delta => "   P99(Δ): {:.4} мс",
             LocalizationKey::CiWidth => "   95% CI ширина: {:.4} мс",
+            LocalizationKey::P50Delta => "   P50(Δ): {:.4} мс",
+            LocalizationKey::MeanDelta => "   Середнє(Δ): {:.4} мс",
+            LocalizationKey::StdevDelta => "   Стд. відхилення(Δ): {:.4} мс",
+            LocalizationKey::OutliersRemoved => "   Викидів видалено: {}",
+            LocalizationKey::BimodalityCoefficient => "   Коефіцієнт бімодальності: {:.3}",
             LocalizationKey::ErrorMutexConflict => "Виявлено конфлікт м'ютекса",
             LocalizationKey::MutexErrorMessage => "Повідомлення про помилку: {}",
             LocalizationKey::MutexErrorHint => "Підказка: Можливо, вже працює інший інструмент розширення таймера",
@@ -185,6 +190,95 @@ impl LocalizationKey {
             LocalizationKey::FastLinearMethodDesc3 => "Налаштовуваний поріг ранньої зупинки (швидке завершення)",
             LocalizationKey::FastLinearMethodDesc4 => "Оптимальний для більшості користувачів (~2-5 хвилин)",
             LocalizationKey::EarlyStopThreshold => "Поріг ранньої зупинки",
+            LocalizationKey::RefineMethod => "Пошук від грубого до точного",
+            LocalizationKey::RefineMethodTitle => "🎯 ПОШУК ВІД ГРУБОГО ДО ТОЧНОГО",
+            LocalizationKey::RefineMethodDesc1 => "Груба сітка по всьому діапазону (без ранньої зупинки)",
+            LocalizationKey::RefineMethodDesc2 => "Уточнення золотим перетином навколо найкращого інтервалу",
+            LocalizationKey::RefineMethodDesc3 => "Близько 35 точок з кроком сітки 0.0001 мс (~1-2 хвилини)",
+            LocalizationKey::SignificanceTitle => "📐 ЗНАЧУЩІСТЬ (переможець проти найближчих, бутстреп за сирими вимірами, 95% для всіх порівнянь разом)",
+            LocalizationKey::SignificanceComparison => "   #{} {:.4} мс: ΔP50 {:+.4} мс, ΔP95 {:+.4} мс → {}",
+            LocalizationKey::VerdictEquivalent => "нерозрізненні",
+            LocalizationKey::VerdictWinnerBetter => "переможець значуще кращий",
+            LocalizationKey::VerdictRunnerUpBetter => "кандидат значуще кращий за {}",
+            LocalizationKey::SignificanceTie => "🤝 НІЧИЯ: {} мс статистично еквівалентні",
+            LocalizationKey::SignificanceTieBreak => "   Значення нижче — лише вибір TOPSIS серед рівних; будь-яке з них має працювати однаково",
+            LocalizationKey::SignificanceNotClearWin => "⚠️  Перемога не однозначна: {:.4} мс значуще краще за {}; TOPSIS віддає перевагу переможцю за іншими критеріями",
+            LocalizationKey::SignificanceClearWin => "✅ Переможець значуще кращий за всіх {} найближчих кандидатів",
+            LocalizationKey::ListAnd => " і ",
+            LocalizationKey::ReportPartial => "\n⚠️  Часткові результати: запуск було перервано до завершення пошуку",
+            LocalizationKey::ReportPointsInFile => "   Точок у файлі: {}",
+            LocalizationKey::RacingTitle => "\n🏁 АДАПТИВНІ ПЕРЕГОНИ: {} найкращих кандидатів, бюджет {} с",
+            LocalizationKey::RacingCandidates => "   {} мс",
+            LocalizationKey::RacingRound => "   Раунд {}: лідер {:.4} мс, залишилось {}",
+            LocalizationKey::RacingDropped => ", вибули {} мс",
+            LocalizationKey::RacingStopSeparated => "кандидатів розділено",
+            LocalizationKey::RacingStopRoundLimit => "досягнуто ліміту раундів",
+            LocalizationKey::RacingStopCancelled => "скасовано",
+            LocalizationKey::RacingStopBudget => "бюджет часу вичерпано",
+            LocalizationKey::RacingStopNoMeasurements => "немає вимірювань",
+            LocalizationKey::RacingFinished => "\n   Перегони завершено ({}): {} додаткових запусків за {:.1} с",
+            LocalizationKey::RacingConfirmed => "   Рекомендацію підтверджено: {:.4} мс\n",
+            LocalizationKey::RacingChanged => "   Рекомендацію змінено: {:.4} мс → {:.4} мс\n",
+            LocalizationKey::WarmupDropped => "   Прогрів: відкинуто вимірів: {}",
+            LocalizationKey::WarmupDroppedMs => " (перші {:.0} мс вимірювань)",
+            LocalizationKey::DriftRuns => "   Дрейф: зсув або тренд затримки всередині {} з {} запусків",
+            LocalizationKey::StationarityTitle => "⏳ ПРОГРІВ І ДРЕЙФ",
+            LocalizationKey::StationarityDropped => "   Відкинуто вимірів прогріву: {} у {} з {} точок",
+            LocalizationKey::StationaritySettle => "   Рекомендована пауза: {} мс після встановлення роздільності (зараз {} мс)",
+            LocalizationKey::StationarityDrifting => "⚠️  Затримка дрейфує всередині запусків на {} мс; їхня статистика змішує кілька станів",
+            LocalizationKey::RefineCoarseGrid => "   Груба сітка: {} точок, крок {:.4} мс",
+            LocalizationKey::RefineRefinement => "   Уточнення: золотий переріз із кроком сітки 0.0001 мс",
+            LocalizationKey::RefineCoarseCancelled => "грубу сітку скасовано",
+            LocalizationKey::RefineCoarseCompleted => "грубу сітку завершено",
+            LocalizationKey::RefineCoarseBest => "\n🔍 Найкраща точка сітки (згладжено): {:.4} мс, уточнення [{:.4}, {:.4}] мс",
+            LocalizationKey::RefineStep => "   Крок {}: {:.4} / {:.4} мс → [{:.4}, {:.4}] мс",
+            LocalizationKey::RefineMinimum => "   Згладжений мінімум: {:.4} мс після {} кроків",
+            LocalizationKey::RefineCompleted => "\n✅ Пошук від грубого до точного завершено:",
+            LocalizationKey::RefinePointsMeasured => "   Виміряно точок: {} (сітка {}, уточнення {})",
+            LocalizationKey::RefineTotalTime => "   Загальний час: {:.1} хв\n",
+            LocalizationKey::ResumingRun => "↺ Відновлення запуску {} з {}: записано вимірювань: {}",
+            LocalizationKey::ResumingRange => "   Діапазон: [{:.4}, {:.4}] мс, крок {:.4} мс, вибірок: {} (з контрольної точки)",
+            LocalizationKey::PointRestored => "   ↺ {:.4} мс відновлено з контрольної точки",
+            LocalizationKey::RankerTitle => "🧭 УЗГОДЖЕНІСТЬ МЕТОДІВ РАНЖУВАННЯ",
+            LocalizationKey::RankerWinner => "   {} {:.4} мс{}",
+            LocalizationKey::RankerDominated => " (домінується)",
+            LocalizationKey::RankerParetoFront => "   Фронт Парето: {} мс",
+            LocalizationKey::RankersAgree => "✅ Усі методи обирають ту саму роздільну здатність",
+            LocalizationKey::RankersDisagree => "⚠️  Методи розходяться; вибір залежить від методу прийняття рішень, залишено TOPSIS",
+            LocalizationKey::SensitivityTitle => "🎲 ЧУТЛИВІСТЬ ДО ВАГ ({} випадкових наборів ваг)",
+            LocalizationKey::SensitivityWinRate => "   {:.4} мс перемагає в {}%",
+            LocalizationKey::SensitivityWeightRange => "   {} {:.2} → переможець зберігається при {:.2}..{:.2}",
+            LocalizationKey::SensitivityRobust => "✅ Стійко: {:.4} мс на першому місці для {:.0}% наборів ваг",
+            LocalizationKey::SensitivityFragile => "⚠️  Нестійко: {:.4} мс на першому місці лише для {:.0}% наборів ваг; вибір залежить від ваг",
+            LocalizationKey::DistributionTitle => "📊 РОЗПОДІЛ ПРИ {:.4} мс ({} вимірів)",
+            LocalizationKey::DistributionShape => "   Асиметрія {:.2}, ексцес {:.2}, бімодальність {:.3}, P99.9 {:.4} мс, максимум {:.4} мс",
+            LocalizationKey::DistributionBimodal => "⚠️  Бімодальність: паузи при рекомендованому значенні чергуються між двома рівнями (коефіцієнт вище {:.3})",
+            LocalizationKey::DistributionBimodalElsewhere => "   Бімодальність в інших точках (коефіцієнт вище {:.3}): {} мс",
+            LocalizationKey::CtrlCStopping => "\n⏹  Ctrl+C: зупинка після поточної точки та збереження часткових результатів",
+            LocalizationKey::CtrlCAgain => "   Натисніть Ctrl+C ще раз для негайного переривання",
+            LocalizationKey::CtrlCAborted => "\n⛔ Перервано: звільнення роздільної здатності таймера",
+            LocalizationKey::CtrlCReleaseFailed => "   Не вдалося звільнити роздільну здатність таймера: {}",
+            LocalizationKey::RankingCriteriaLine => "▸ Критерії ранжування: {}",
+            LocalizationKey::RankingCriteriaDerived => "▸ Критерії ранжування: {} (ваги виводяться з даних: {})",
+            LocalizationKey::AcquisitionBatch => "  Пакет із {}: {}",
+            LocalizationKey::GpSurrogate => "   GP-модель: ядро {}, масштаб довжини {:.4} мс, шум/сигнал {:.3}, log ML {:.2}",
+            LocalizationKey::HistoryEmpty => "Немає збережених запусків у {}",
+            LocalizationKey::HistoryId => "Id",
+            LocalizationKey::HistoryMethod => "Метод",
+            LocalizationKey::HistoryBackend => "Бекенд",
+            LocalizationKey::HistoryPoints => "Точки",
+            LocalizationKey::HistoryOptimal => "Оптимум (мс)",
+            LocalizationKey::HistoryTie => " (нічия з {})",
+            LocalizationKey::HistoryPartial => " (частково)",
+            LocalizationKey::HistoryCount => "Запусків: {} у {}",
+            LocalizationKey::CompareTitle => "\n📊 Порівняння запусків",
+            LocalizationKey::CompareRun => "   {}: {} ({}, {}, HPET: {}, оптимум {:.4} мс{})",
+            LocalizationKey::CompareRunPartial => ", частково",
+            LocalizationKey::CompareDifferentSystems => "⚠️  Запуски зроблено на різних системах ({} і {})",
+            LocalizationKey::CompareResolution => "Роздільна здатність (мс)",
+            LocalizationKey::CompareLegend => "   * значуще на рівні 95% (бутстреп за сирими вимірами); ▼ нижче в B (краще), ▲ вище в B (гірше)",
+            LocalizationKey::CompareSummary => "   Порівняно роздільних здатностей: {}, значущих покращень: {}, значущих погіршень: {}",
+            LocalizationKey::CompareOptimal => "   Оптимум: {:.4} мс → {:.4} мс",
         }
     }
 }
\ No newline at end of file
@@ -2,14 +2,17 @@
 //!
 //! This library provides modular components for timer resolution benchmarking.
 
+pub mod cli;
 pub mod core;
+pub mod backend;
 pub mod stats;
 pub mod optimization;
+pub mod report;
 pub mod ui;
 pub mod utils;
 pub mod language;
 
-pub use core::run_benchmark;
+pub use core::{run_benchmark, run_check, RunOptions};
 
 /// Library version
 pub const VERSION: &str = "0.3.2";
\ No newline at end of file
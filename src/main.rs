@@ -1,7 +1,10 @@
-use timer_res_benchmark::run_benchmark;
+use clap::Parser;
+use timer_res_benchmark::cli::Cli;
 
 #[tokio::main]
 async fn main() {
+    let cli = Cli::parse();
+
     // On Windows, set the console output codepage to UTF-8
     #[cfg(windows)]
     {
@@ -14,8 +17,8 @@ async fn main() {
         }
     }
 
-    if let Err(e) = run_benchmark().await {
+    if let Err(e) = cli.execute().await {
         eprintln!("Fatal error: {}", e);
         std::process::exit(1);
     }
-}
\ No newline at end of file
+}
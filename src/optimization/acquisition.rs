@@ -0,0 +1,145 @@
+//! Acquisition functions for `BayesianOptimizer`
+//!
+//! The optimizer minimises the performance score, so every function here rates
+//! how promising it is to measure a candidate resolution given the GP posterior;
+//! the candidate with the highest utility is measured next.
+
+use serde::{Deserialize, Serialize};
+
+use crate::optimization::gaussian_process::GaussianProcess;
+use crate::stats::quantile::normal_cdf;
+use crate::utils::rng::SplitMix64;
+
+/// UCB exploration weight at the start and at the end of the search
+const UCB_KAPPA_START: f64 = 2.5;
+const UCB_KAPPA_END: f64 = 0.5;
+
+/// Probability-of-improvement margin, as a fraction of the incumbent score
+const PI_MARGIN: f64 = 0.01;
+
+/// Acquisition function selectable from appsettings.json and the command line
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, clap::ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub enum AcquisitionKind {
+    /// Confidence bound with κ decaying from 2.5 to 0.5
+    #[default]
+    Ucb,
+    /// Expected improvement
+    Ei,
+    /// Probability of improvement
+    Pi,
+    /// Thompson sampling from the joint posterior
+    Thompson,
+}
+
+impl AcquisitionKind {
+    /// Acquisition function for a search that is `progress` (0..=1) through its iterations
+    pub fn function(self, progress: f64) -> Box<dyn AcquisitionFunction> {
+        match self {
+            AcquisitionKind::Ucb => Box::new(ConfidenceBound { kappa: ucb_kappa(progress) }),
+            AcquisitionKind::Ei => Box::new(ExpectedImprovement),
+            AcquisitionKind::Pi => Box::new(ProbabilityOfImprovement),
+            AcquisitionKind::Thompson => Box::new(ThompsonSampling),
+        }
+    }
+}
+
+/// UCB exploration weight for a search that is `progress` (0..=1) through its iterations
+pub fn ucb_kappa(progress: f64) -> f64 {
+    UCB_KAPPA_START - (UCB_KAPPA_START - UCB_KAPPA_END) * progress.clamp(0.0, 1.0)
+}
+
+/// Rates candidate inputs for the next measurement
+pub trait AcquisitionFunction {
+    /// Short description for progress output, e.g. "UCB κ=1.50"
+    fn describe(&self) -> String;
+
+    /// Utility of measuring each of `inputs` next (higher is better)
+    fn utilities(&self, gp: &GaussianProcess, inputs: &[f64], rng: &mut SplitMix64) -> Vec<f64>;
+}
+
+/// Lower confidence bound μ − κσ (negated, since lower scores are better)
+pub struct ConfidenceBound {
+    pub kappa: f64,
+}
+
+impl AcquisitionFunction for ConfidenceBound {
+    fn describe(&self) -> String {
+        format!("UCB κ={:.2}", self.kappa)
+    }
+
+    fn utilities(&self, gp: &GaussianProcess, inputs: &[f64], _rng: &mut SplitMix64) -> Vec<f64> {
+        inputs.iter()
+            .map(|&x| {
+                let (mean, variance) = gp.predict(x);
+                -(mean - self.kappa * variance.sqrt())
+            })
+            .collect()
+    }
+}
+
+/// Expected amount by which a candidate beats the incumbent
+pub struct ExpectedImprovement;
+
+impl AcquisitionFunction for ExpectedImprovement {
+    fn describe(&self) -> String {
+        "EI".to_string()
+    }
+
+    fn utilities(&self, gp: &GaussianProcess, inputs: &[f64], _rng: &mut SplitMix64) -> Vec<f64> {
+        let best = gp.best_mean_at_observations();
+        inputs.iter()
+            .map(|&x| {
+                let (mean, variance) = gp.predict(x);
+                let sigma = variance.sqrt();
+                let improvement = best - mean;
+                if sigma < 1e-12 {
+                    return improvement.max(0.0);
+                }
+                let z = improvement / sigma;
+                improvement * normal_cdf(z) + sigma * normal_pdf(z)
+            })
+            .collect()
+    }
+}
+
+/// Probability that a candidate beats the incumbent by at least `PI_MARGIN`
+pub struct ProbabilityOfImprovement;
+
+impl AcquisitionFunction for ProbabilityOfImprovement {
+    fn describe(&self) -> String {
+        "PI".to_string()
+    }
+
+    fn utilities(&self, gp: &GaussianProcess, inputs: &[f64], _rng: &mut SplitMix64) -> Vec<f64> {
+        let best = gp.best_mean_at_observations();
+        let target = best - PI_MARGIN * best.abs();
+        inputs.iter()
+            .map(|&x| {
+                let (mean, variance) = gp.predict(x);
+                let sigma = variance.sqrt();
+                if sigma < 1e-12 {
+                    return if mean < target { 1.0 } else { 0.0 };
+                }
+                normal_cdf((target - mean) / sigma)
+            })
+            .collect()
+    }
+}
+
+/// Minimum of one joint posterior draw
+pub struct ThompsonSampling;
+
+impl AcquisitionFunction for ThompsonSampling {
+    fn describe(&self) -> String {
+        "Thompson".to_string()
+    }
+
+    fn utilities(&self, gp: &GaussianProcess, inputs: &[f64], rng: &mut SplitMix64) -> Vec<f64> {
+        gp.sample_posterior(inputs, rng).into_iter().map(|draw| -draw).collect()
+    }
+}
+
+fn normal_pdf(z: f64) -> f64 {
+    (-0.5 * z * z).exp() / (2.0 * std::f64::consts::PI).sqrt()
+}
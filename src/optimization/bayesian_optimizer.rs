@@ -3,74 +3,152 @@
 //! This module implements Bayesian optimization with Gaussian processes
 //! for intelligent exploration of timer resolution parameter space.
 
+use crate::optimization::acquisition::{AcquisitionFunction, ConfidenceBound};
+use crate::optimization::gaussian_process::{GaussianProcess, Kernel, SurrogateCurve};
 use crate::stats::timer_measurement::TimerMeasurement;
 use crate::stats::robust_statistics::PerformanceWeights;
+use crate::utils::rng::SplitMix64;
 use std::f64;
 
+/// Fixed seed so Thompson sampling suggests the same points when a run is resumed
+const ACQUISITION_SEED: u64 = 0xB0_5EED;
+
 /// Bayesian optimizer for intelligent parameter search
 pub struct BayesianOptimizer {
     pub observations: Vec<TimerMeasurement>,
+    excluded: Vec<f64>,                  // Points never suggested again (unusable samples)
     kernel_width: f64,
     weights: PerformanceWeights,
+    kernel: Kernel,
+    surrogate: Option<GaussianProcess>,  // Refitted after every observation
+    rng: SplitMix64,                     // Randomness for Thompson sampling
 }
 
 impl BayesianOptimizer {
     /// Create a new Bayesian optimizer with specified kernel width
+    ///
+    /// The kernel width is the starting point of the length-scale search; the
+    /// fitted length scale is chosen by marginal likelihood.
     pub fn new(kernel_width: f64, weights: PerformanceWeights) -> Self {
         Self {
             observations: Vec::new(),
+            excluded: Vec::new(),
             kernel_width,
             weights,
+            kernel: Kernel::Matern52,
+            surrogate: None,
+            rng: SplitMix64::new(ACQUISITION_SEED),
         }
     }
 
-    /// Add observation to the optimizer's knowledge base
+    /// Add observation to the optimizer's knowledge base and refit the surrogate
     pub fn add_observation(&mut self, measurement: TimerMeasurement) {
         self.observations.push(measurement);
+        let xs: Vec<f64> = self.observations.iter().map(|o| o.resolution_ms).collect();
+        let ys: Vec<f64> = self.observations.iter()
+            .map(|o| o.statistics.performance_score(&self.weights))
+            .collect();
+        self.surrogate = GaussianProcess::fit(&xs, &ys, self.kernel, self.kernel_width);
     }
 
-    /// Gaussian process with robust metrics
-    fn predict(&self, x: f64) -> (f64, f64) {
-        if self.observations.is_empty() {
-            return (1.0, 1.0);
-        }
-        let mut weighted_sum = 0.0;
-        let mut weight_total = 0.0;
-        for obs in &self.observations {
-            let dist_sq = (x - obs.resolution_ms).powi(2);
-            let weight = (-dist_sq / (2.0 * self.kernel_width.powi(2))).exp();
-            let score = obs.statistics.performance_score(&self.weights);
-            weighted_sum += weight * score;
-            weight_total += weight;
-        }
-        let mu = if weight_total > 1e-10 { weighted_sum / weight_total } else { 1.0 };
-        // Uncertainty accounts for observation density
-        let sigma = 0.3 / (1.0 + weight_total * 0.1);
-        (mu, sigma)
+    /// Never suggest `resolution_ms` again, e.g. because its samples were unusable
+    ///
+    /// A failed point adds no observation, so the surrogate would otherwise
+    /// keep rating it exactly as before and suggest it again.
+    pub fn exclude(&mut self, resolution_ms: f64) {
+        self.excluded.push(resolution_ms);
     }
 
-    /// Upper Confidence Bound (UCB) instead of Expected Improvement
-    fn acquisition_ucb(&self, x: f64, kappa: f64) -> f64 {
-        let (mu, sigma) = self.predict(x);
-        mu - kappa * sigma  // Minus because we minimize score
+    /// Fitted Gaussian process, once there is at least one observation
+    pub fn surrogate(&self) -> Option<&GaussianProcess> {
+        self.surrogate.as_ref()
     }
 
-    /// Suggest next point to evaluate using acquisition function
+    /// Posterior mean and variance of the performance score over `bounds`
+    pub fn surrogate_curve(&self, bounds: (f64, f64), n_points: usize) -> Option<SurrogateCurve> {
+        self.surrogate.as_ref().map(|gp| gp.curve(bounds, n_points))
+    }
+
+    /// Suggest next point to evaluate by the lower confidence bound μ − κσ
+    ///
+    /// Kept with its original signature; `suggest_batch` takes any acquisition
+    /// function and suggests several points at once.
     pub fn suggest_next(&self, bounds: (f64, f64), n_samples: usize, kappa: f64) -> f64 {
+        // The confidence bound draws no random numbers
+        let mut rng = SplitMix64::new(ACQUISITION_SEED);
+        self.batch(bounds, n_samples, &ConfidenceBound { kappa }, 1, &mut rng)[0]
+    }
+
+    /// Suggest `batch_size` distinct points to measure before the next update
+    ///
+    /// Kriging believer: after each pick the surrogate is updated as if the point
+    /// had been measured at its posterior mean, which pushes the next pick away.
+    /// Points passed to `exclude` are never suggested.
+    pub fn suggest_batch(
+        &mut self,
+        bounds: (f64, f64),
+        n_samples: usize,
+        acquisition: &dyn AcquisitionFunction,
+        batch_size: usize,
+    ) -> Vec<f64> {
+        let mut rng = self.rng.clone();
+        let batch = self.batch(bounds, n_samples, acquisition, batch_size, &mut rng);
+        self.rng = rng;
+        batch
+    }
+
+    fn batch(
+        &self,
+        bounds: (f64, f64),
+        n_samples: usize,
+        acquisition: &dyn AcquisitionFunction,
+        batch_size: usize,
+        rng: &mut SplitMix64,
+    ) -> Vec<f64> {
         let (low, high) = bounds;
+        let batch_size = batch_size.max(1);
         let step = (high - low) / (n_samples as f64);
-        let mut best_x = low;
-        let mut best_ucb = f64::MAX; // Seek minimum UCB
-        for i in 0..n_samples {
-            let x = low + (i as f64) * step;
-            let ucb = self.acquisition_ucb(x, kappa);
-            if ucb < best_ucb {  // Smaller is better
-                best_ucb = ucb;
-                best_x = x;
+        let grid: Vec<f64> = (0..n_samples)
+            .map(|i| low + (i as f64) * step)
+            .filter(|&x| !self.is_excluded(x, step / 2.0))
+            .collect();
+        let fallback = grid.first().copied().unwrap_or(low);
+
+        let Some(mut gp) = self.surrogate.clone() else {
+            // No model yet: spread the batch over the range
+            let segment = (high - low) / batch_size as f64;
+            let spread: Vec<f64> = (0..batch_size)
+                .map(|i| low + (i as f64 + 0.5) * segment)
+                .filter(|&x| !self.is_excluded(x, step / 2.0))
+                .collect();
+            return if spread.is_empty() { vec![fallback] } else { spread };
+        };
+
+        let mut batch: Vec<f64> = Vec::with_capacity(batch_size);
+        while batch.len() < batch_size {
+            let utilities = acquisition.utilities(&gp, &grid, rng);
+            let Some(best) = utilities.iter()
+                .enumerate()
+                .filter(|(i, u)| !u.is_nan() && !batch.contains(&grid[*i]))
+                .max_by(|a, b| a.1.total_cmp(b.1))
+                .map(|(i, _)| grid[i])
+            else {
+                break;
+            };
+            batch.push(best);
+            let (mean, _) = gp.predict(best);
+            match gp.with_observation(best, mean) {
+                Some(updated) => gp = updated,
+                None => break,
             }
         }
-        best_x
+        if batch.is_empty() {
+            batch.push(fallback);
+        }
+        batch
     }
 
-
+    fn is_excluded(&self, x: f64, tolerance: f64) -> bool {
+        self.excluded.iter().any(|e| (x - e).abs() < tolerance)
+    }
 }
\ No newline at end of file
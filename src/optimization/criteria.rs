@@ -0,0 +1,338 @@
+//! Ranking criteria and weights
+//!
+//! TOPSIS ranks measurements on a weighted set of criteria. The set comes from a
+//! named preset or from an explicit list in appsettings.json / `--criteria`;
+//! weights are normalised to sum to 1.
+
+use std::fmt;
+use std::io::{self, Error, ErrorKind};
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+
+use crate::stats::robust_statistics::PerformanceWeights;
+use crate::stats::timer_measurement::TimerMeasurement;
+
+/// Measurement statistic usable as a ranking criterion
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Criterion {
+    P50,
+    P95,
+    P99,
+    Mean,
+    Stdev,
+    Mad,
+    CiWidth,   // Width of the 95% CI of the mean
+    Outliers,  // Samples removed as outliers
+    Bimodality,  // Bimodality coefficient, above 5/9 when sleeps alternate between two levels
+}
+
+impl Criterion {
+    pub const ALL: [Criterion; 9] = [
+        Criterion::P50, Criterion::P95, Criterion::P99, Criterion::Mean,
+        Criterion::Stdev, Criterion::Mad, Criterion::CiWidth, Criterion::Outliers,
+        Criterion::Bimodality,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Criterion::P50 => "p50",
+            Criterion::P95 => "p95",
+            Criterion::P99 => "p99",
+            Criterion::Mean => "mean",
+            Criterion::Stdev => "stdev",
+            Criterion::Mad => "mad",
+            Criterion::CiWidth => "ci_width",
+            Criterion::Outliers => "outliers",
+            Criterion::Bimodality => "bimodality",
+        }
+    }
+
+    /// Value of the criterion for one measurement
+    pub fn value(&self, measurement: &TimerMeasurement) -> f64 {
+        let s = &measurement.statistics;
+        match self {
+            Criterion::P50 => s.median,
+            Criterion::P95 => s.p95,
+            Criterion::P99 => s.p99,
+            Criterion::Mean => s.mean,
+            Criterion::Stdev => s.stdev,
+            Criterion::Mad => s.mad,
+            Criterion::CiWidth => s.confidence_interval_95.1 - s.confidence_interval_95.0,
+            Criterion::Outliers => s.outliers_removed as f64,
+            Criterion::Bimodality => s.shape.bimodality_coefficient,
+        }
+    }
+}
+
+/// Whether lower (cost) or higher (benefit) values are better
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Direction {
+    #[default]
+    Cost,
+    Benefit,
+}
+
+/// One criterion of the ranking with its weight
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct WeightedCriterion {
+    #[serde(rename = "Criterion")]
+    pub criterion: Criterion,
+    #[serde(rename = "Weight")]
+    pub weight: f64,
+    #[serde(rename = "Direction", default)]
+    pub direction: Direction,
+}
+
+impl FromStr for WeightedCriterion {
+    type Err = String;
+
+    /// `name=weight` or `name=weight:benefit`, e.g. `p99=0.5`
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (name, rest) = s.split_once('=')
+            .ok_or_else(|| format!("expected <criterion>=<weight>, got '{}'", s))?;
+        let (weight, direction) = match rest.split_once(':') {
+            Some((weight, "cost")) => (weight, Direction::Cost),
+            Some((weight, "benefit")) => (weight, Direction::Benefit),
+            Some((_, other)) => return Err(format!("unknown direction '{}' (expected cost or benefit)", other)),
+            None => (rest, Direction::Cost),
+        };
+        let criterion = Criterion::ALL.into_iter()
+            .find(|c| c.name() == name.trim())
+            .ok_or_else(|| format!("unknown criterion '{}' (expected one of: {})", name,
+                Criterion::ALL.map(|c| c.name()).join(", ")))?;
+        let weight = weight.trim().parse::<f64>().map_err(|e| format!("weight of {}: {}", name, e))?;
+        Ok(Self { criterion, weight, direction })
+    }
+}
+
+/// Named criteria sets
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, clap::ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub enum CriteriaPreset {
+    /// P95 40%, MAD 30%, P99 20%, CI width 10%
+    #[default]
+    Balanced,
+    /// Tail latency first: P99 45%, P95 30%, P50 15%, CI width 10%
+    Latency,
+    /// Steadiness first: MAD 45%, stdev 25%, P99 20%, CI width 10%
+    Consistency,
+}
+
+impl CriteriaPreset {
+    fn criteria(self) -> Vec<WeightedCriterion> {
+        let weights: &[(Criterion, f64)] = match self {
+            CriteriaPreset::Balanced => &[
+                (Criterion::P95, 0.40), (Criterion::Mad, 0.30), (Criterion::P99, 0.20), (Criterion::CiWidth, 0.10),
+            ],
+            CriteriaPreset::Latency => &[
+                (Criterion::P99, 0.45), (Criterion::P95, 0.30), (Criterion::P50, 0.15), (Criterion::CiWidth, 0.10),
+            ],
+            CriteriaPreset::Consistency => &[
+                (Criterion::Mad, 0.45), (Criterion::Stdev, 0.25), (Criterion::P99, 0.20), (Criterion::CiWidth, 0.10),
+            ],
+        };
+        weights.iter()
+            .map(|&(criterion, weight)| WeightedCriterion { criterion, weight, direction: Direction::Cost })
+            .collect()
+    }
+}
+
+/// Where the criteria weights come from
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, clap::ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub enum WeightingMethod {
+    /// Weights from the preset or the explicit list
+    #[default]
+    Manual,
+    /// Shannon entropy of each criterion over the measurements
+    Entropy,
+    /// CRITIC: contrast and conflict between criteria
+    Critic,
+}
+
+impl WeightingMethod {
+    pub fn name(&self) -> &'static str {
+        match self {
+            WeightingMethod::Manual => "manual",
+            WeightingMethod::Entropy => "entropy",
+            WeightingMethod::Critic => "critic",
+        }
+    }
+}
+
+/// Criteria and normalised weights a ranking was made with
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RankingCriteria {
+    /// Preset the criteria come from, or `None` for an explicit list
+    pub preset: Option<CriteriaPreset>,
+    pub criteria: Vec<WeightedCriterion>,
+    /// Objective methods replace the weights with ones derived from the data
+    #[serde(default)]
+    pub weighting: WeightingMethod,
+}
+
+impl Default for RankingCriteria {
+    fn default() -> Self {
+        Self::from_preset(CriteriaPreset::default())
+    }
+}
+
+impl RankingCriteria {
+    pub fn from_preset(preset: CriteriaPreset) -> Self {
+        Self { preset: Some(preset), criteria: preset.criteria(), weighting: WeightingMethod::Manual }
+    }
+
+    pub fn with_weighting(self, weighting: WeightingMethod) -> Self {
+        Self { weighting, ..self }
+    }
+
+    /// Explicit criteria list; weights are normalised to sum to 1
+    pub fn custom(criteria: &[WeightedCriterion]) -> io::Result<Self> {
+        let invalid = |message: String| Error::new(ErrorKind::InvalidInput, message);
+        if criteria.is_empty() {
+            return Err(invalid("at least one ranking criterion is required".to_string()));
+        }
+        for (i, c) in criteria.iter().enumerate() {
+            if !(c.weight >= 0.0 && c.weight.is_finite()) {
+                return Err(invalid(format!("weight of {} must be a non-negative number", c.criterion.name())));
+            }
+            if criteria[..i].iter().any(|other| other.criterion == c.criterion) {
+                return Err(invalid(format!("criterion {} is listed twice", c.criterion.name())));
+            }
+        }
+        let total: f64 = criteria.iter().map(|c| c.weight).sum();
+        if total <= 0.0 {
+            return Err(invalid("ranking weights must not all be zero".to_string()));
+        }
+        Ok(Self {
+            preset: None,
+            criteria: criteria.iter()
+                .map(|c| WeightedCriterion { weight: c.weight / total, ..*c })
+                .collect(),
+            weighting: WeightingMethod::Manual,
+        })
+    }
+
+    /// Weights for the scalar score of the hybrid search
+    ///
+    /// Cost criteria are grouped into accuracy (P50, P95, mean), consistency
+    /// (MAD, stdev, CI width, bimodality) and worst case (P99, outliers); benefit criteria
+    /// have no place in a lower-is-better score and are left out.
+    pub fn performance_weights(&self) -> PerformanceWeights {
+        let mut weights = PerformanceWeights { accuracy: 0.0, consistency: 0.0, worst_case: 0.0 };
+        for c in self.criteria.iter().filter(|c| c.direction == Direction::Cost) {
+            match c.criterion {
+                Criterion::P50 | Criterion::P95 | Criterion::Mean => weights.accuracy += c.weight,
+                Criterion::Mad | Criterion::Stdev | Criterion::CiWidth | Criterion::Bimodality => {
+                    weights.consistency += c.weight
+                }
+                Criterion::P99 | Criterion::Outliers => weights.worst_case += c.weight,
+            }
+        }
+        let total = weights.accuracy + weights.consistency + weights.worst_case;
+        if total <= 0.0 {
+            return PerformanceWeights::default();
+        }
+        PerformanceWeights {
+            accuracy: weights.accuracy / total,
+            consistency: weights.consistency / total,
+            worst_case: weights.worst_case / total,
+        }
+    }
+}
+
+impl fmt::Display for RankingCriteria {
+    /// e.g. "balanced: p95=0.4, mad=0.3, p99=0.2, ci_width=0.1", with
+    /// "(entropy weights)" after the label for objective weighting; weights
+    /// are printed in full so that `from_str` reads back the same criteria
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let label = match self.preset {
+            Some(CriteriaPreset::Balanced) => "balanced",
+            Some(CriteriaPreset::Latency) => "latency",
+            Some(CriteriaPreset::Consistency) => "consistency",
+            None => "custom",
+        };
+        let list: Vec<String> = self.criteria.iter()
+            .map(|c| match c.direction {
+                Direction::Cost => format!("{}={}", c.criterion.name(), c.weight),
+                Direction::Benefit => format!("{}={}:benefit", c.criterion.name(), c.weight),
+            })
+            .collect();
+        match self.weighting {
+            WeightingMethod::Manual => write!(f, "{}: {}", label, list.join(", ")),
+            method => write!(f, "{} ({} weights): {}", label, method.name(), list.join(", ")),
+        }
+    }
+}
+
+impl FromStr for RankingCriteria {
+    type Err = String;
+
+    /// Inverse of `Display`, used to read the criteria back from results.txt
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (label, list) = s.split_once(": ").ok_or_else(|| format!("expected <label>: <criteria>, got '{}'", s))?;
+        let (label, weighting) = match label.split_once(" (") {
+            Some((label, method)) => {
+                let method = method.trim_end_matches(" weights)");
+                let weighting = [WeightingMethod::Manual, WeightingMethod::Entropy, WeightingMethod::Critic]
+                    .into_iter()
+                    .find(|w| w.name().eq_ignore_ascii_case(method))  // Older files print "CRITIC"
+                    .ok_or_else(|| format!("unknown weighting '{}'", method))?;
+                (label, weighting)
+            }
+            None => (label, WeightingMethod::Manual),
+        };
+        let preset = match label.trim() {
+            "balanced" => Some(CriteriaPreset::Balanced),
+            "latency" => Some(CriteriaPreset::Latency),
+            "consistency" => Some(CriteriaPreset::Consistency),
+            "custom" => None,
+            other => return Err(format!("unknown criteria preset '{}'", other)),
+        };
+        let criteria = list.split(", ").map(str::parse).collect::<Result<Vec<WeightedCriterion>, _>>()?;
+        Ok(Self { preset, criteria, weighting })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn weighted(criterion: Criterion, weight: f64) -> WeightedCriterion {
+        WeightedCriterion { criterion, weight, direction: Direction::Cost }
+    }
+
+    #[test]
+    fn display_round_trips() {
+        let mut criteria = vec![weighted(Criterion::P99, 1.0), weighted(Criterion::Mad, 1.0), weighted(Criterion::CiWidth, 1.0)];
+        criteria.push(WeightedCriterion { direction: Direction::Benefit, ..weighted(Criterion::Outliers, 4.0) });
+        for criteria in [
+            RankingCriteria::default(),
+            RankingCriteria::from_preset(CriteriaPreset::Latency).with_weighting(WeightingMethod::Entropy),
+            RankingCriteria::custom(&criteria).unwrap(),
+        ] {
+            let text = criteria.to_string();
+            assert_eq!(text.parse::<RankingCriteria>().unwrap(), criteria, "{}", text);
+        }
+        assert_eq!(RankingCriteria::default().to_string(), "balanced: p95=0.4, mad=0.3, p99=0.2, ci_width=0.1");
+    }
+
+    #[test]
+    fn custom_normalises_weights() {
+        let criteria = RankingCriteria::custom(&[weighted(Criterion::P95, 3.0), weighted(Criterion::Mad, 1.0)]).unwrap();
+        assert_eq!(criteria.preset, None);
+        assert_eq!(criteria.criteria.iter().map(|c| c.weight).collect::<Vec<_>>(), vec![0.75, 0.25]);
+    }
+
+    #[test]
+    fn custom_rejects_invalid_lists() {
+        assert!(RankingCriteria::custom(&[]).is_err());
+        assert!(RankingCriteria::custom(&[weighted(Criterion::P95, 0.5), weighted(Criterion::P95, 0.5)]).is_err());
+        assert!(RankingCriteria::custom(&[weighted(Criterion::P95, 1.0), weighted(Criterion::Mad, -0.5)]).is_err());
+        assert!(RankingCriteria::custom(&[weighted(Criterion::P95, f64::NAN)]).is_err());
+        assert!(RankingCriteria::custom(&[weighted(Criterion::P95, 0.0), weighted(Criterion::Mad, 0.0)]).is_err());
+    }
+}
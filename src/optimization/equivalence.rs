@@ -0,0 +1,241 @@
+//! Statistical significance of the TOPSIS winner
+//!
+//! TOPSIS always picks a winner, even when the top candidates differ by less
+//! than their own noise. This module compares the winner with each runner-up
+//! over their raw samples (bootstrap difference of median and P95) and reports
+//! the set of candidates the winner cannot be told apart from. The direction
+//! of each difference is kept: a runner-up significantly better on median or
+//! P95 is reported as such, not as a win.
+//!
+//! The two tests per runner-up are Bonferroni-corrected over every test made,
+//! so the chance of any false difference across the comparison stays at 5%.
+
+use serde::{Deserialize, Serialize};
+
+use crate::optimization::topsis::TopsisScore;
+use crate::stats::significance::{bootstrap_difference_at, BootstrapDifference, Statistic, DEFAULT_RESAMPLES};
+use crate::stats::timer_measurement::TimerMeasurement;
+
+/// Number of runner-up candidates compared with the winner
+pub const RUNNERS_UP_TESTED: usize = 4;
+
+/// Family-wise confidence of all winner vs runner-up tests together
+pub const FAMILY_CONFIDENCE: f64 = 0.95;
+
+/// Significant shift of a statistic from the winner to a runner-up
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Shift {
+    /// The interval of the difference contains zero
+    #[default]
+    None,
+    /// Runner-up significantly higher, i.e. worse
+    Higher,
+    /// Runner-up significantly lower, i.e. better
+    Lower,
+}
+
+impl Shift {
+    fn of(difference: &BootstrapDifference) -> Self {
+        let (lower, upper) = difference.confidence_interval;
+        if lower > 0.0 {
+            Shift::Higher
+        } else if upper < 0.0 {
+            Shift::Lower
+        } else {
+            Shift::None
+        }
+    }
+}
+
+/// Outcome of a winner vs runner-up comparison
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Verdict {
+    /// Neither median nor P95 differs significantly
+    #[default]
+    Equivalent,
+    /// The winner is significantly lower on median or P95 and not higher on either
+    WinnerBetter,
+    /// The runner-up is significantly lower on median or P95, whatever the other says
+    RunnerUpBetter,
+}
+
+/// Winner vs one runner-up
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RunnerUpComparison {
+    pub resolution_ms: f64,
+    pub rank: usize,
+    pub median_difference_ms: f64,  // Runner-up minus winner
+    pub p95_difference_ms: f64,     // Runner-up minus winner
+    #[serde(default)]
+    pub median_shift: Shift,
+    #[serde(default)]
+    pub p95_shift: Shift,
+    #[serde(default)]
+    pub verdict: Verdict,
+}
+
+impl RunnerUpComparison {
+    /// Names of the statistics the runner-up is significantly better on, e.g. ["P95"]
+    pub fn runner_up_better_on(&self) -> Vec<&'static str> {
+        [("P50", self.median_shift), ("P95", self.p95_shift)].into_iter()
+            .filter(|&(_, shift)| shift == Shift::Lower)
+            .map(|(name, _)| name)
+            .collect()
+    }
+}
+
+/// Whether the recommendation stands out from the runners-up
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WinnerSignificance {
+    pub winner_ms: f64,
+    pub comparisons: Vec<RunnerUpComparison>,
+    /// Winner plus every runner-up it cannot be distinguished from, in rank order
+    pub equivalent_set_ms: Vec<f64>,
+}
+
+impl WinnerSignificance {
+    /// The winner is statistically indistinguishable from at least one runner-up
+    pub fn is_tie(&self) -> bool {
+        self.equivalent_set_ms.len() > 1
+    }
+
+    /// Runners-up significantly better than the winner on median or P95
+    pub fn better_runners_up(&self) -> impl Iterator<Item = &RunnerUpComparison> {
+        self.comparisons.iter().filter(|c| c.verdict == Verdict::RunnerUpBetter)
+    }
+
+    /// The winner is significantly better than every runner-up compared
+    pub fn is_clear_win(&self) -> bool {
+        !self.comparisons.is_empty() && self.comparisons.iter().all(|c| c.verdict == Verdict::WinnerBetter)
+    }
+}
+
+/// Compare the TOPSIS winner with the next `runners_up` candidates
+///
+/// Quantiles are estimated the way the winner's statistics were. Each of the
+/// median and P95 tests uses a Bonferroni-corrected interval, so all of them
+/// hold together at `FAMILY_CONFIDENCE`. Returns `None` when the ranking is
+/// empty or the winner has no measurement.
+pub fn winner_significance(
+    rankings: &[TopsisScore],
+    measurements: &[TimerMeasurement],
+    runners_up: usize,
+) -> Option<WinnerSignificance> {
+    let find = |resolution_ms: f64| {
+        measurements.iter().find(|m| (m.resolution_ms - resolution_ms).abs() < 0.0001)
+    };
+    let winner = rankings.first()?;
+    let winner_measurement = find(winner.resolution_ms)?;
+    let (winner_samples, estimator) = (&winner_measurement.raw_samples, winner_measurement.statistics.quantile_estimator);
+
+    let candidates: Vec<(&TopsisScore, &TimerMeasurement)> = rankings.iter().skip(1).take(runners_up)
+        .filter_map(|candidate| Some((candidate, find(candidate.resolution_ms)?)))
+        .collect();
+    let tests = 2 * candidates.len();
+    let confidence = 1.0 - (1.0 - FAMILY_CONFIDENCE) / tests.max(1) as f64;
+
+    let mut comparisons = Vec::new();
+    let mut equivalent_set_ms = vec![winner.resolution_ms];
+    for (candidate, measurement) in candidates {
+        let test = |statistic| bootstrap_difference_at(
+            winner_samples, &measurement.raw_samples, statistic, estimator, DEFAULT_RESAMPLES, confidence);
+        let (median, p95) = (test(Statistic::Median), test(Statistic::P95));
+        let (median_shift, p95_shift) = (Shift::of(&median), Shift::of(&p95));
+        let verdict = if median_shift == Shift::Lower || p95_shift == Shift::Lower {
+            Verdict::RunnerUpBetter
+        } else if median_shift == Shift::Higher || p95_shift == Shift::Higher {
+            Verdict::WinnerBetter
+        } else {
+            Verdict::Equivalent
+        };
+        if verdict == Verdict::Equivalent {
+            equivalent_set_ms.push(candidate.resolution_ms);
+        }
+        comparisons.push(RunnerUpComparison {
+            resolution_ms: candidate.resolution_ms,
+            rank: candidate.rank,
+            median_difference_ms: median.difference,
+            p95_difference_ms: p95.difference,
+            median_shift,
+            p95_shift,
+            verdict,
+        });
+    }
+
+    Some(WinnerSignificance {
+        winner_ms: winner.resolution_ms,
+        comparisons,
+        equivalent_set_ms,
+    })
+}
+
+/// Comma-separated list of resolutions, e.g. "0.5000, 0.5020"
+pub fn format_resolutions(resolutions_ms: &[f64]) -> String {
+    resolutions_ms.iter().map(|r| format!("{:.4}", r)).collect::<Vec<_>>().join(", ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Deltas spread evenly over `centre ± spread / 2`
+    fn measurement(resolution_ms: f64, centre: f64, spread: f64) -> TimerMeasurement {
+        let samples = (0..200)
+            .map(|i| centre + spread * ((i * 67 % 200) as f64 / 199.0 - 0.5))
+            .collect();
+        TimerMeasurement::from_samples(resolution_ms, samples)
+    }
+
+    fn ranking(resolutions_ms: &[f64]) -> Vec<TopsisScore> {
+        resolutions_ms.iter().enumerate()
+            .map(|(i, &resolution_ms)| TopsisScore {
+                resolution_ms,
+                closeness_coefficient: 1.0 - i as f64 * 0.1,
+                rank: i + 1,
+                criterion_values: Vec::new(),
+            })
+            .collect()
+    }
+
+    fn compare(winner: TimerMeasurement, runner_up: TimerMeasurement) -> WinnerSignificance {
+        let rankings = ranking(&[winner.resolution_ms, runner_up.resolution_ms]);
+        winner_significance(&rankings, &[winner, runner_up], RUNNERS_UP_TESTED).unwrap()
+    }
+
+    #[test]
+    fn identical_samples_tie() {
+        let significance = compare(measurement(0.5, 0.05, 0.02), measurement(0.501, 0.05, 0.02));
+        assert!(significance.is_tie());
+        assert_eq!(significance.equivalent_set_ms, vec![0.5, 0.501]);
+        let comparison = &significance.comparisons[0];
+        assert_eq!(comparison.verdict, Verdict::Equivalent);
+        assert_eq!((comparison.median_shift, comparison.p95_shift), (Shift::None, Shift::None));
+        assert_eq!(comparison.median_difference_ms, 0.0);
+    }
+
+    #[test]
+    fn higher_runner_up_loses() {
+        let significance = compare(measurement(0.5, 0.05, 0.02), measurement(0.501, 0.08, 0.02));
+        assert!(significance.is_clear_win());
+        let comparison = &significance.comparisons[0];
+        assert_eq!(comparison.verdict, Verdict::WinnerBetter);
+        assert_eq!((comparison.median_shift, comparison.p95_shift), (Shift::Higher, Shift::Higher));
+        // Differences are runner-up minus winner
+        assert!((comparison.median_difference_ms - 0.03).abs() < 1e-9);
+        assert!(comparison.p95_difference_ms > 0.0);
+    }
+
+    #[test]
+    fn lower_runner_up_is_reported_better() {
+        let significance = compare(measurement(0.5, 0.08, 0.02), measurement(0.501, 0.05, 0.02));
+        assert!(!significance.is_tie());
+        let comparison = &significance.comparisons[0];
+        assert_eq!(comparison.verdict, Verdict::RunnerUpBetter);
+        assert_eq!(comparison.runner_up_better_on(), vec!["P50", "P95"]);
+        assert!((comparison.median_difference_ms + 0.03).abs() < 1e-9);
+        assert!(comparison.p95_difference_ms < 0.0);
+        assert_eq!(significance.better_runners_up().count(), 1);
+    }
+}
@@ -0,0 +1,415 @@
+//! Gaussian-process regression over one input dimension
+//!
+//! Used as the surrogate model of `BayesianOptimizer`. Targets are standardised
+//! before fitting; kernel length scale, signal variance and noise variance are
+//! chosen by maximising the log marginal likelihood over a log-spaced grid, which
+//! is cheap at the handful of observations a benchmark run makes.
+
+use serde::{Deserialize, Serialize};
+
+use crate::utils::rng::SplitMix64;
+
+/// Smallest diagonal jitter added when the kernel matrix is not positive definite
+const JITTER: f64 = 1e-9;
+
+/// Covariance function
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Kernel {
+    /// Squared exponential: very smooth surrogate
+    Rbf,
+    /// Matérn ν = 5/2: twice differentiable, tolerates sharper changes
+    Matern52,
+}
+
+impl Kernel {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Kernel::Rbf => "RBF",
+            Kernel::Matern52 => "Matérn 5/2",
+        }
+    }
+
+    fn correlation(&self, distance: f64, length_scale: f64) -> f64 {
+        let r = distance.abs() / length_scale;
+        match self {
+            Kernel::Rbf => (-0.5 * r * r).exp(),
+            Kernel::Matern52 => {
+                let s = 5f64.sqrt() * r;
+                (1.0 + s + s * s / 3.0) * (-s).exp()
+            }
+        }
+    }
+}
+
+/// Kernel hyperparameters, in standardised target units
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct Hyperparameters {
+    pub length_scale: f64,     // In input units (ms)
+    pub signal_variance: f64,
+    pub noise_variance: f64,
+}
+
+/// Posterior of the surrogate at one input
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct PosteriorPoint {
+    pub resolution_ms: f64,
+    pub mean: f64,
+    pub variance: f64,
+}
+
+/// Fitted surrogate and its posterior over a range, for reports and charts
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SurrogateCurve {
+    pub kernel: Kernel,
+    pub hyperparameters: Hyperparameters,
+    pub log_marginal_likelihood: f64,
+    pub observations: Vec<(f64, f64)>,  // (input, target) pairs the process was fitted to
+    pub points: Vec<PosteriorPoint>,
+}
+
+/// Gaussian process fitted to a set of observations
+#[derive(Debug, Clone)]
+pub struct GaussianProcess {
+    kernel: Kernel,
+    hyperparameters: Hyperparameters,
+    xs: Vec<f64>,
+    ys: Vec<f64>,
+    y_mean: f64,
+    y_scale: f64,
+    cholesky: Vec<Vec<f64>>,   // Lower-triangular factor of K + σ²ₙI
+    alpha: Vec<f64>,           // (K + σ²ₙI)⁻¹ y
+    log_marginal_likelihood: f64,
+}
+
+impl GaussianProcess {
+    /// Fit with hyperparameters chosen by marginal likelihood
+    ///
+    /// `length_scale_hint` centres the length-scale grid. Returns `None` without
+    /// observations.
+    pub fn fit(xs: &[f64], ys: &[f64], kernel: Kernel, length_scale_hint: f64) -> Option<Self> {
+        if xs.is_empty() || xs.len() != ys.len() {
+            return None;
+        }
+        let hint = if length_scale_hint > 0.0 { length_scale_hint } else { 1.0 };
+
+        let mut best: Option<Self> = None;
+        for length_factor in log_grid(0.1, 10.0, 13) {
+            for signal_variance in log_grid(0.25, 4.0, 5) {
+                for noise_variance in log_grid(1e-4, 1.0, 9) {
+                    let hyperparameters = Hyperparameters {
+                        length_scale: hint * length_factor,
+                        signal_variance,
+                        noise_variance,
+                    };
+                    let Some(gp) = Self::fit_with(xs, ys, kernel, hyperparameters) else { continue };
+                    if best.as_ref().is_none_or(|b| gp.log_marginal_likelihood > b.log_marginal_likelihood) {
+                        best = Some(gp);
+                    }
+                }
+            }
+        }
+        best
+    }
+
+    /// Fit with fixed hyperparameters
+    pub fn fit_with(xs: &[f64], ys: &[f64], kernel: Kernel, hyperparameters: Hyperparameters) -> Option<Self> {
+        let n = xs.len();
+        if n == 0 || n != ys.len() {
+            return None;
+        }
+
+        let y_mean = ys.iter().sum::<f64>() / n as f64;
+        let y_var = ys.iter().map(|y| (y - y_mean).powi(2)).sum::<f64>() / n as f64;
+        let y_scale = if y_var > 1e-24 { y_var.sqrt() } else { 1.0 };
+        let targets: Vec<f64> = ys.iter().map(|y| (y - y_mean) / y_scale).collect();
+
+        let mut covariance = vec![vec![0.0; n]; n];
+        for i in 0..n {
+            for j in 0..=i {
+                let k = hyperparameters.signal_variance
+                    * kernel.correlation(xs[i] - xs[j], hyperparameters.length_scale);
+                covariance[i][j] = k;
+                covariance[j][i] = k;
+            }
+            covariance[i][i] += hyperparameters.noise_variance;
+        }
+        let cholesky = cholesky(&covariance)?;
+        let alpha = cholesky_solve(&cholesky, &targets);
+
+        // log p(y) = -½ yᵀα - Σ log Lᵢᵢ - n/2 log 2π
+        let data_fit: f64 = targets.iter().zip(&alpha).map(|(y, a)| y * a).sum();
+        let log_det: f64 = (0..n).map(|i| cholesky[i][i].ln()).sum();
+        let log_marginal_likelihood =
+            -0.5 * data_fit - log_det - 0.5 * n as f64 * (2.0 * std::f64::consts::PI).ln();
+
+        Some(Self {
+            kernel,
+            hyperparameters,
+            xs: xs.to_vec(),
+            ys: ys.to_vec(),
+            y_mean,
+            y_scale,
+            cholesky,
+            alpha,
+            log_marginal_likelihood,
+        })
+    }
+
+    pub fn kernel(&self) -> Kernel {
+        self.kernel
+    }
+
+    pub fn hyperparameters(&self) -> Hyperparameters {
+        self.hyperparameters
+    }
+
+    pub fn log_marginal_likelihood(&self) -> f64 {
+        self.log_marginal_likelihood
+    }
+
+    /// Posterior mean and variance of the latent function at `x`, in target units
+    pub fn predict(&self, x: f64) -> (f64, f64) {
+        let h = &self.hyperparameters;
+        let k_star: Vec<f64> = self.xs.iter()
+            .map(|&xi| h.signal_variance * self.kernel.correlation(x - xi, h.length_scale))
+            .collect();
+        let mean: f64 = k_star.iter().zip(&self.alpha).map(|(k, a)| k * a).sum();
+        let v = forward_substitute(&self.cholesky, &k_star);
+        let variance = (h.signal_variance - v.iter().map(|v| v * v).sum::<f64>()).max(0.0);
+        (
+            self.y_mean + self.y_scale * mean,
+            variance * self.y_scale * self.y_scale,
+        )
+    }
+
+    /// Same process with one more observation and unchanged hyperparameters
+    pub fn with_observation(&self, x: f64, y: f64) -> Option<Self> {
+        let mut xs = self.xs.clone();
+        let mut ys = self.ys.clone();
+        xs.push(x);
+        ys.push(y);
+        Self::fit_with(&xs, &ys, self.kernel, self.hyperparameters)
+    }
+
+    /// Smallest posterior mean at the observed inputs (noise-robust incumbent)
+    pub fn best_mean_at_observations(&self) -> f64 {
+        self.xs.iter().map(|&x| self.predict(x).0).fold(f64::INFINITY, f64::min)
+    }
+
+    /// One joint draw of the latent function at `inputs`
+    ///
+    /// Falls back to independent draws from the marginals when the posterior
+    /// covariance cannot be factorised.
+    pub fn sample_posterior(&self, inputs: &[f64], rng: &mut SplitMix64) -> Vec<f64> {
+        let h = &self.hyperparameters;
+        let n = inputs.len();
+        let mut means = Vec::with_capacity(n);
+        let mut projections = Vec::with_capacity(n);  // L⁻¹ k(X, x) per input
+        for &x in inputs {
+            let k_star: Vec<f64> = self.xs.iter()
+                .map(|&xi| h.signal_variance * self.kernel.correlation(x - xi, h.length_scale))
+                .collect();
+            means.push(k_star.iter().zip(&self.alpha).map(|(k, a)| k * a).sum::<f64>());
+            projections.push(forward_substitute(&self.cholesky, &k_star));
+        }
+
+        let mut covariance = vec![vec![0.0; n]; n];
+        for i in 0..n {
+            for j in 0..=i {
+                let prior = h.signal_variance * self.kernel.correlation(inputs[i] - inputs[j], h.length_scale);
+                let explained: f64 = projections[i].iter().zip(&projections[j]).map(|(a, b)| a * b).sum();
+                covariance[i][j] = prior - explained;
+                covariance[j][i] = covariance[i][j];
+            }
+        }
+
+        let normals: Vec<f64> = (0..n).map(|_| rng.next_gaussian()).collect();
+        let draws: Vec<f64> = match cholesky(&covariance) {
+            Some(l) => (0..n)
+                .map(|i| means[i] + (0..=i).map(|k| l[i][k] * normals[k]).sum::<f64>())
+                .collect(),
+            None => (0..n)
+                .map(|i| means[i] + covariance[i][i].max(0.0).sqrt() * normals[i])
+                .collect(),
+        };
+        draws.iter().map(|d| self.y_mean + self.y_scale * d).collect()
+    }
+
+    /// Posterior at `n_points` evenly spaced inputs covering `bounds`
+    pub fn posterior(&self, bounds: (f64, f64), n_points: usize) -> Vec<PosteriorPoint> {
+        let (low, high) = bounds;
+        let step = if n_points > 1 { (high - low) / (n_points - 1) as f64 } else { 0.0 };
+        (0..n_points)
+            .map(|i| {
+                let x = low + i as f64 * step;
+                let (mean, variance) = self.predict(x);
+                PosteriorPoint { resolution_ms: x, mean, variance }
+            })
+            .collect()
+    }
+
+    /// Posterior over `bounds` together with the fitted model and its data
+    pub fn curve(&self, bounds: (f64, f64), n_points: usize) -> SurrogateCurve {
+        SurrogateCurve {
+            kernel: self.kernel,
+            hyperparameters: self.hyperparameters,
+            log_marginal_likelihood: self.log_marginal_likelihood,
+            observations: self.xs.iter().copied().zip(self.ys.iter().copied()).collect(),
+            points: self.posterior(bounds, n_points),
+        }
+    }
+}
+
+/// `n` log-spaced values from `low` to `high`
+fn log_grid(low: f64, high: f64, n: usize) -> impl Iterator<Item = f64> {
+    let (log_low, log_high) = (low.ln(), high.ln());
+    (0..n).map(move |i| (log_low + (log_high - log_low) * i as f64 / (n - 1) as f64).exp())
+}
+
+/// Lower-triangular Cholesky factor, retrying with growing jitter
+fn cholesky(matrix: &[Vec<f64>]) -> Option<Vec<Vec<f64>>> {
+    let mut jitter = 0.0;
+    for _ in 0..6 {
+        if let Some(l) = try_cholesky(matrix, jitter) {
+            return Some(l);
+        }
+        jitter = if jitter == 0.0 { JITTER } else { jitter * 100.0 };
+    }
+    None
+}
+
+fn try_cholesky(matrix: &[Vec<f64>], jitter: f64) -> Option<Vec<Vec<f64>>> {
+    let n = matrix.len();
+    let mut l = vec![vec![0.0; n]; n];
+    for i in 0..n {
+        for j in 0..=i {
+            let sum: f64 = (0..j).map(|k| l[i][k] * l[j][k]).sum();
+            if i == j {
+                let d = matrix[i][i] + jitter - sum;
+                if d <= 0.0 || !d.is_finite() {
+                    return None;
+                }
+                l[i][j] = d.sqrt();
+            } else {
+                l[i][j] = (matrix[i][j] - sum) / l[j][j];
+            }
+        }
+    }
+    Some(l)
+}
+
+/// Solve L v = b
+fn forward_substitute(l: &[Vec<f64>], b: &[f64]) -> Vec<f64> {
+    let n = b.len();
+    let mut v = vec![0.0; n];
+    for i in 0..n {
+        let sum: f64 = (0..i).map(|k| l[i][k] * v[k]).sum();
+        v[i] = (b[i] - sum) / l[i][i];
+    }
+    v
+}
+
+/// Solve (L Lᵀ) x = b
+fn cholesky_solve(l: &[Vec<f64>], b: &[f64]) -> Vec<f64> {
+    let n = b.len();
+    let v = forward_substitute(l, b);
+    let mut x = vec![0.0; n];
+    for i in (0..n).rev() {
+        let sum: f64 = (i + 1..n).map(|k| l[k][i] * x[k]).sum();
+        x[i] = (v[i] - sum) / l[i][i];
+    }
+    x
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_close(actual: f64, expected: f64, tolerance: f64) {
+        assert!((actual - expected).abs() <= tolerance, "{} is not within {} of {}", actual, tolerance, expected);
+    }
+
+    #[test]
+    fn cholesky_factor_reproduces_the_matrix() {
+        let matrix = vec![
+            vec![4.0, 2.0, 0.4],
+            vec![2.0, 5.0, 1.0],
+            vec![0.4, 1.0, 3.0],
+        ];
+        let l = cholesky(&matrix).unwrap();
+        for i in 0..3 {
+            for j in 0..3 {
+                let product: f64 = (0..3).map(|k| l[i][k] * l[j][k]).sum();
+                assert_close(product, matrix[i][j], 1e-12);
+            }
+            assert!(l[i][i + 1..].iter().all(|&v| v == 0.0));
+        }
+    }
+
+    #[test]
+    fn cholesky_solve_inverts_the_matrix() {
+        let matrix = vec![vec![4.0, 2.0], vec![2.0, 3.0]];
+        let l = cholesky(&matrix).unwrap();
+        let x = cholesky_solve(&l, &[2.0, 1.0]);
+        assert_close(4.0 * x[0] + 2.0 * x[1], 2.0, 1e-12);
+        assert_close(2.0 * x[0] + 3.0 * x[1], 1.0, 1e-12);
+    }
+
+    #[test]
+    fn cholesky_adds_jitter_to_singular_matrices_only() {
+        // Two identical inputs without noise give a singular kernel matrix
+        let singular = vec![vec![1.0, 1.0], vec![1.0, 1.0]];
+        assert!(try_cholesky(&singular, 0.0).is_none());
+        assert!(cholesky(&singular).is_some());
+        assert!(cholesky(&[vec![-1.0]]).is_none());
+    }
+
+    #[test]
+    fn low_noise_process_interpolates_its_observations() {
+        let xs = [0.50, 0.51, 0.52, 0.53];
+        let ys = [3.0, 1.0, 2.0, 4.0];
+        let hyperparameters = Hyperparameters { length_scale: 0.01, signal_variance: 1.0, noise_variance: 1e-6 };
+        let gp = GaussianProcess::fit_with(&xs, &ys, Kernel::Matern52, hyperparameters).unwrap();
+        for (&x, &y) in xs.iter().zip(&ys) {
+            let (mean, variance) = gp.predict(x);
+            assert_close(mean, y, 1e-3);
+            assert!(variance < 1e-3);
+        }
+        // Far from the data the posterior falls back to the prior
+        let (_, far_variance) = gp.predict(1.0);
+        assert!(far_variance > gp.predict(0.515).1);
+        assert_close(gp.best_mean_at_observations(), 1.0, 1e-3);
+    }
+
+    #[test]
+    fn with_observation_matches_refitting() {
+        let hyperparameters = Hyperparameters { length_scale: 0.02, signal_variance: 1.0, noise_variance: 0.01 };
+        let gp = GaussianProcess::fit_with(&[0.50, 0.52], &[2.0, 1.0], Kernel::Rbf, hyperparameters).unwrap();
+        let updated = gp.with_observation(0.54, 3.0).unwrap();
+        let refitted = GaussianProcess::fit_with(&[0.50, 0.52, 0.54], &[2.0, 1.0, 3.0], Kernel::Rbf, hyperparameters).unwrap();
+        assert_close(updated.predict(0.53).0, refitted.predict(0.53).0, 1e-12);
+        assert_close(updated.log_marginal_likelihood(), refitted.log_marginal_likelihood(), 1e-12);
+    }
+
+    #[test]
+    fn fitted_surrogate_finds_the_minimum_of_a_bowl() {
+        let xs: Vec<f64> = (0..9).map(|i| 0.50 + 0.0025 * i as f64).collect();
+        let ys: Vec<f64> = xs.iter().map(|x| 1.0 + 1e4 * (x - 0.51) * (x - 0.51)).collect();
+        let gp = GaussianProcess::fit(&xs, &ys, Kernel::Matern52, 0.003).unwrap();
+        let minimum = gp.posterior((0.50, 0.52), 201).into_iter()
+            .min_by(|a, b| a.mean.total_cmp(&b.mean))
+            .unwrap();
+        assert_close(minimum.resolution_ms, 0.51, 0.0005);
+        assert!(GaussianProcess::fit(&[], &[], Kernel::Matern52, 0.003).is_none());
+    }
+
+    #[test]
+    fn posterior_draws_are_reproducible() {
+        let gp = GaussianProcess::fit(&[0.50, 0.51, 0.52], &[2.0, 1.0, 2.0], Kernel::Matern52, 0.01).unwrap();
+        let inputs = [0.505, 0.515];
+        let first = gp.sample_posterior(&inputs, &mut SplitMix64::new(7));
+        let second = gp.sample_posterior(&inputs, &mut SplitMix64::new(7));
+        assert_eq!(first, second);
+    }
+}
@@ -0,0 +1,117 @@
+//! Golden-section search on the resolution grid
+//!
+//! Narrows a bracket around the minimum of a unimodal objective: of the two
+//! interior probes, the one with the worse value cuts off its side of the
+//! bracket, which shrinks by 1/φ per step. The bracket itself is kept exact so
+//! that one probe of the next step falls on a probe of the previous one; only
+//! the probes are snapped to the 0.0001 ms grid the timer resolution is set in.
+
+/// Spacing of settable timer resolutions (100 ns)
+pub const GRID_MS: f64 = 0.0001;
+
+/// 1/φ, the share of the bracket kept at each step
+const INVERSE_PHI: f64 = 0.618_033_988_749_894_9;
+
+/// Nearest point of the 0.0001 ms grid
+pub fn snap_to_grid(resolution_ms: f64) -> f64 {
+    (resolution_ms * 10000.0).round() / 10000.0
+}
+
+/// Bracket of a golden-section search
+#[derive(Debug, Clone, Copy)]
+pub struct GoldenSection {
+    low: f64,
+    high: f64,
+}
+
+impl GoldenSection {
+    pub fn new(low: f64, high: f64) -> Self {
+        Self { low: low.min(high), high: low.max(high) }
+    }
+
+    pub fn bounds(&self) -> (f64, f64) {
+        (self.low, self.high)
+    }
+
+    /// Lower and upper interior probes, snapped to the grid
+    pub fn probes(&self) -> (f64, f64) {
+        let width = self.high - self.low;
+        (snap_to_grid(self.high - INVERSE_PHI * width), snap_to_grid(self.low + INVERSE_PHI * width))
+    }
+
+    /// The probes fall on the same grid point, so no step can tell them apart
+    pub fn converged(&self) -> bool {
+        let (lower, upper) = self.probes();
+        upper - lower < GRID_MS / 2.0
+    }
+
+    /// Keep the side of the better probe: up to the upper probe when the lower
+    /// one is better, from the lower probe otherwise
+    pub fn narrow(&mut self, lower_is_better: bool) {
+        let width = self.high - self.low;
+        if lower_is_better {
+            self.high = self.low + INVERSE_PHI * width;
+        } else {
+            self.low = self.high - INVERSE_PHI * width;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn on_grid(x: f64) -> bool {
+        (x * 10000.0 - (x * 10000.0).round()).abs() < 1e-9
+    }
+
+    #[test]
+    fn snaps_to_the_nearest_grid_point() {
+        assert_eq!(snap_to_grid(0.50004), 0.5);
+        assert_eq!(snap_to_grid(0.50006), 0.5001);
+        assert_eq!(snap_to_grid(0.5), 0.5);
+    }
+
+    #[test]
+    fn bracket_is_ordered_and_shrinks_by_inverse_phi() {
+        let mut bracket = GoldenSection::new(0.6, 0.5);
+        assert_eq!(bracket.bounds(), (0.5, 0.6));
+        bracket.narrow(true);
+        let (low, high) = bracket.bounds();
+        assert_eq!(low, 0.5);
+        assert!(((high - low) - 0.1 * INVERSE_PHI).abs() < 1e-12);
+        bracket.narrow(false);
+        let (low, high) = bracket.bounds();
+        assert!(((high - low) - 0.1 * INVERSE_PHI * INVERSE_PHI).abs() < 1e-12);
+    }
+
+    #[test]
+    fn probes_are_interior_and_on_the_grid() {
+        let bracket = GoldenSection::new(0.5, 0.51);
+        let (lower, upper) = bracket.probes();
+        assert!(0.5 < lower && lower < upper && upper < 0.51);
+        assert!(on_grid(lower) && on_grid(upper));
+    }
+
+    #[test]
+    fn converges_once_the_probes_share_a_grid_point() {
+        assert!(GoldenSection::new(0.5, 0.50005).converged());
+        assert!(!GoldenSection::new(0.5, 0.501).converged());
+    }
+
+    #[test]
+    fn narrows_to_the_minimum_of_a_unimodal_function() {
+        let objective = |x: f64| (x - 0.5037).powi(2);
+        let mut bracket = GoldenSection::new(0.5, 0.51);
+        let mut steps = 0;
+        while !bracket.converged() {
+            let (lower, upper) = bracket.probes();
+            bracket.narrow(objective(lower) <= objective(upper));
+            steps += 1;
+            assert!(steps < 30, "did not converge");
+        }
+        let (lower, upper) = bracket.probes();
+        let minimum = if objective(lower) <= objective(upper) { lower } else { upper };
+        assert!((minimum - 0.5037).abs() <= GRID_MS + 1e-12, "minimum {}", minimum);
+    }
+}
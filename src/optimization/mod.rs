@@ -1,4 +1,11 @@
 //! Optimization modules for timer resolution benchmarking
 
+pub mod acquisition;
 pub mod bayesian_optimizer;
+pub mod criteria;
+pub mod equivalence;
+pub mod gaussian_process;
+pub mod golden_section;
+pub mod rankers;
+pub mod sensitivity;
 pub mod topsis;
\ No newline at end of file
@@ -0,0 +1,317 @@
+//! Alternative multi-criteria rankers
+//!
+//! TOPSIS depends on the whole candidate set: a single bad point moves the
+//! anti-ideal and can reorder the leaders. To show how robust the choice is the
+//! same measurements are also ranked with VIKOR and a plain weighted sum, and
+//! the Pareto-optimal resolutions (not beaten on every criterion by another
+//! point) are listed. All rankers use the same criteria and resolved weights.
+
+use serde::{Deserialize, Serialize};
+
+use crate::optimization::criteria::{Direction, RankingCriteria};
+use crate::optimization::topsis::{criteria_weights, decision_matrix, min_max_scaled, topsis_ranking};
+use crate::stats::timer_measurement::TimerMeasurement;
+
+/// VIKOR weight of group utility against individual regret
+const VIKOR_V: f64 = 0.5;
+
+/// One resolution in a ranker's order
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RankedResolution {
+    pub resolution_ms: f64,
+    pub score: f64,  // Ranker-specific: closeness, VIKOR Q, weighted score or Pareto front
+    pub rank: usize,
+}
+
+/// Multi-criteria decision method over aggregated measurements
+pub trait Ranker {
+    fn name(&self) -> &'static str;
+
+    /// Measurements ordered best first, ranks starting at 1
+    fn rank(&self, measurements: &[TimerMeasurement], criteria: &RankingCriteria) -> Vec<RankedResolution>;
+}
+
+/// TOPSIS: relative closeness to the ideal point
+pub struct Topsis;
+
+impl Ranker for Topsis {
+    fn name(&self) -> &'static str {
+        "TOPSIS"
+    }
+
+    fn rank(&self, measurements: &[TimerMeasurement], criteria: &RankingCriteria) -> Vec<RankedResolution> {
+        topsis_ranking(measurements, criteria).into_iter()
+            .map(|s| RankedResolution { resolution_ms: s.resolution_ms, score: s.closeness_coefficient, rank: s.rank })
+            .collect()
+    }
+}
+
+/// VIKOR: compromise between the weighted total regret (S) and the largest
+/// single-criterion regret (R)
+pub struct Vikor {
+    pub v: f64,
+}
+
+impl Default for Vikor {
+    fn default() -> Self {
+        Self { v: VIKOR_V }
+    }
+}
+
+impl Ranker for Vikor {
+    fn name(&self) -> &'static str {
+        "VIKOR"
+    }
+
+    fn rank(&self, measurements: &[TimerMeasurement], criteria: &RankingCriteria) -> Vec<RankedResolution> {
+        let (scaled, weights) = scaled_with_weights(measurements, criteria);
+        // Scaled values are 1 at the best and 0 at the worst value, so 1 − x is the normalised regret
+        let regrets: Vec<(f64, f64)> = scaled.iter()
+            .map(|row| row.iter().zip(&weights)
+                .map(|(x, w)| w * (1.0 - x))
+                .fold((0.0, 0.0), |(sum, max): (f64, f64), r| (sum + r, max.max(r))))
+            .collect();
+        let range = |values: &mut dyn Iterator<Item = f64>| {
+            values.fold((f64::INFINITY, f64::NEG_INFINITY), |(lo, hi), x| (lo.min(x), hi.max(x)))
+        };
+        let (s_best, s_worst) = range(&mut regrets.iter().map(|r| r.0));
+        let (r_best, r_worst) = range(&mut regrets.iter().map(|r| r.1));
+        let relative = |x: f64, best: f64, worst: f64| {
+            if worst - best < 1e-12 { 0.0 } else { (x - best) / (worst - best) }
+        };
+        let scores = regrets.iter()
+            .map(|&(s, r)| self.v * relative(s, s_best, s_worst) + (1.0 - self.v) * relative(r, r_best, r_worst))
+            .collect();
+        order(measurements, scores, false)
+    }
+}
+
+/// Weighted sum of min-max scaled criteria
+pub struct WeightedSum;
+
+impl Ranker for WeightedSum {
+    fn name(&self) -> &'static str {
+        "weighted sum"
+    }
+
+    fn rank(&self, measurements: &[TimerMeasurement], criteria: &RankingCriteria) -> Vec<RankedResolution> {
+        let (scaled, weights) = scaled_with_weights(measurements, criteria);
+        let scores = scaled.iter()
+            .map(|row| row.iter().zip(&weights).map(|(x, w)| x * w).sum())
+            .collect();
+        order(measurements, scores, true)
+    }
+}
+
+/// Non-dominated sorting: the score is the Pareto front a resolution belongs to
+/// (1 = Pareto-optimal); within a front the weighted sum breaks ties
+///
+/// Criteria with zero weight are ignored, the other weights are not used for
+/// dominance.
+pub struct ParetoFront;
+
+impl Ranker for ParetoFront {
+    fn name(&self) -> &'static str {
+        "Pareto"
+    }
+
+    fn rank(&self, measurements: &[TimerMeasurement], criteria: &RankingCriteria) -> Vec<RankedResolution> {
+        if measurements.is_empty() {
+            return Vec::new();
+        }
+        let (matrix, _) = decision_matrix(measurements, criteria);
+        let active: Vec<(usize, Direction)> = criteria.criteria.iter().enumerate()
+            .filter(|(_, c)| c.weight > 0.0)
+            .map(|(j, c)| (j, c.direction))
+            .collect();
+        let dominates = |a: &[f64], b: &[f64]| {
+            let mut strictly = false;
+            for &(j, direction) in &active {
+                let (better, worse) = match direction {
+                    Direction::Cost => (a[j] < b[j], a[j] > b[j]),
+                    Direction::Benefit => (a[j] > b[j], a[j] < b[j]),
+                };
+                if worse {
+                    return false;
+                }
+                strictly |= better;
+            }
+            strictly
+        };
+
+        let mut front = vec![0usize; measurements.len()];
+        let mut remaining: Vec<usize> = (0..measurements.len()).collect();
+        let mut current = 0;
+        while !remaining.is_empty() {
+            current += 1;
+            let (this_front, rest): (Vec<usize>, Vec<usize>) = remaining.iter()
+                .partition(|&&i| !remaining.iter().any(|&k| dominates(&matrix[k], &matrix[i])));
+            for &i in &this_front {
+                front[i] = current;
+            }
+            remaining = rest;
+        }
+
+        let tie_break: Vec<f64> = WeightedSum.rank(measurements, criteria).into_iter()
+            .map(|r| r.score)
+            .collect();
+        let mut indices: Vec<usize> = (0..measurements.len()).collect();
+        indices.sort_by(|&a, &b| front[a].cmp(&front[b])
+            .then(tie_break[b].partial_cmp(&tie_break[a]).unwrap_or(std::cmp::Ordering::Equal)));
+        indices.into_iter().enumerate()
+            .map(|(rank, i)| RankedResolution {
+                resolution_ms: measurements[i].resolution_ms,
+                score: front[i] as f64,
+                rank: rank + 1,
+            })
+            .collect()
+    }
+}
+
+/// Pareto-optimal resolutions in ascending order
+pub fn pareto_front(measurements: &[TimerMeasurement], criteria: &RankingCriteria) -> Vec<f64> {
+    let mut front: Vec<f64> = ParetoFront.rank(measurements, criteria).into_iter()
+        .filter(|r| r.score == 1.0)
+        .map(|r| r.resolution_ms)
+        .collect();
+    front.sort_by(|a, b| a.partial_cmp(b).unwrap_or(std::cmp::Ordering::Equal));
+    front
+}
+
+/// Winner of one ranker
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RankerWinner {
+    pub ranker: String,
+    pub resolution_ms: f64,
+    pub pareto_optimal: bool,
+}
+
+/// Winners of the scoring rankers and the Pareto front of the same measurements
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RankerComparison {
+    pub winners: Vec<RankerWinner>,
+    pub pareto_front_ms: Vec<f64>,
+}
+
+impl RankerComparison {
+    /// Every ranker picks the same resolution
+    pub fn agree(&self) -> bool {
+        self.winners.windows(2).all(|w| (w[0].resolution_ms - w[1].resolution_ms).abs() < 0.0001)
+    }
+}
+
+/// Rank `measurements` with TOPSIS, VIKOR and the weighted sum and extract the Pareto front
+///
+/// Returns `None` when there is nothing to rank.
+pub fn compare_rankers(measurements: &[TimerMeasurement], criteria: &RankingCriteria) -> Option<RankerComparison> {
+    if measurements.is_empty() {
+        return None;
+    }
+    let pareto_front_ms = pareto_front(measurements, criteria);
+    let rankers: [&dyn Ranker; 3] = [&Topsis, &Vikor::default(), &WeightedSum];
+    let winners = rankers.iter()
+        .filter_map(|ranker| {
+            let best = ranker.rank(measurements, criteria).into_iter().next()?;
+            Some(RankerWinner {
+                ranker: ranker.name().to_string(),
+                resolution_ms: best.resolution_ms,
+                pareto_optimal: pareto_front_ms.iter().any(|&p| (p - best.resolution_ms).abs() < 0.0001),
+            })
+        })
+        .collect();
+    Some(RankerComparison { winners, pareto_front_ms })
+}
+
+/// Min-max scaled matrix (1 = best) and the weights of the configured method
+fn scaled_with_weights(measurements: &[TimerMeasurement], criteria: &RankingCriteria) -> (Vec<Vec<f64>>, Vec<f64>) {
+    if measurements.is_empty() {
+        return (Vec::new(), Vec::new());
+    }
+    let (matrix, normalized) = decision_matrix(measurements, criteria);
+    let weights = criteria_weights(&matrix, &normalized, criteria);
+    (min_max_scaled(&matrix, criteria), weights)
+}
+
+/// Sort measurements by score and assign ranks
+fn order(measurements: &[TimerMeasurement], scores: Vec<f64>, higher_is_better: bool) -> Vec<RankedResolution> {
+    let mut ranked: Vec<RankedResolution> = measurements.iter().zip(scores)
+        .map(|(m, score)| RankedResolution { resolution_ms: m.resolution_ms, score, rank: 0 })
+        .collect();
+    ranked.sort_by(|a, b| {
+        let ordering = a.score.partial_cmp(&b.score).unwrap_or(std::cmp::Ordering::Equal);
+        if higher_is_better { ordering.reverse() } else { ordering }
+    });
+    for (rank, r) in ranked.iter_mut().enumerate() {
+        r.rank = rank + 1;
+    }
+    ranked
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::optimization::criteria::{Criterion, WeightedCriterion};
+
+    /// `n` evenly spaced samples of the given width around `center`
+    fn measurement(resolution_ms: f64, center: f64, width: f64) -> TimerMeasurement {
+        let n = 101;
+        let samples = (0..n).map(|i| center - width / 2.0 + width * i as f64 / (n - 1) as f64).collect();
+        TimerMeasurement::from_samples(resolution_ms, samples)
+    }
+
+    fn p95_and_mad(p95: f64, mad: f64) -> RankingCriteria {
+        RankingCriteria::custom(&[
+            WeightedCriterion { criterion: Criterion::P95, weight: p95, direction: Direction::Cost },
+            WeightedCriterion { criterion: Criterion::Mad, weight: mad, direction: Direction::Cost },
+        ]).unwrap()
+    }
+
+    #[test]
+    fn dominating_point_wins_every_ranker() {
+        let measurements = vec![
+            measurement(0.5, 0.8, 0.4),
+            measurement(0.501, 0.05, 0.002),
+            measurement(0.502, 0.1, 0.2),
+        ];
+        let criteria = p95_and_mad(0.5, 0.5);
+        let comparison = compare_rankers(&measurements, &criteria).unwrap();
+        assert_eq!(comparison.winners.len(), 3);
+        assert!(comparison.agree());
+        assert!(comparison.winners.iter().all(|w| w.resolution_ms == 0.501 && w.pareto_optimal));
+        assert_eq!(comparison.pareto_front_ms, vec![0.501]);
+    }
+
+    #[test]
+    fn trade_off_forms_the_front_and_dominated_point_ranks_last() {
+        let measurements = vec![
+            measurement(0.5, 0.1, 0.2),     // Low P95, high MAD
+            measurement(0.501, 0.5, 0.004), // High P95, low MAD
+            measurement(0.502, 0.8, 0.4),   // Worse than the first on both
+        ];
+        let criteria = p95_and_mad(0.5, 0.5);
+        assert_eq!(pareto_front(&measurements, &criteria), vec![0.5, 0.501]);
+
+        let ranked = ParetoFront.rank(&measurements, &criteria);
+        let last = ranked.last().unwrap();
+        assert_eq!((last.resolution_ms, last.score, last.rank), (0.502, 2.0, 3));
+        for ranker in [&Topsis as &dyn Ranker, &Vikor::default(), &WeightedSum] {
+            let order = ranker.rank(&measurements, &criteria);
+            assert_eq!(order.last().unwrap().resolution_ms, 0.502, "{}", ranker.name());
+            assert_eq!(order.iter().map(|r| r.rank).collect::<Vec<_>>(), vec![1, 2, 3]);
+        }
+    }
+
+    #[test]
+    fn zero_weight_criteria_do_not_count_for_dominance() {
+        let measurements = vec![measurement(0.5, 0.1, 0.2), measurement(0.501, 0.5, 0.004)];
+        assert_eq!(pareto_front(&measurements, &p95_and_mad(1.0, 0.0)), vec![0.5]);
+        assert_eq!(pareto_front(&measurements, &p95_and_mad(0.0, 1.0)), vec![0.501]);
+    }
+
+    #[test]
+    fn nothing_to_rank() {
+        let criteria = RankingCriteria::default();
+        assert!(compare_rankers(&[], &criteria).is_none());
+        assert!(ParetoFront.rank(&[], &criteria).is_empty());
+    }
+}
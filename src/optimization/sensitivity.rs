@@ -0,0 +1,212 @@
+//! Weight sensitivity of the TOPSIS recommendation
+//!
+//! The criteria weights are a judgement call. To show how much the
+//! recommendation depends on them the ranking is repeated with random weights
+//! drawn uniformly from the simplex (Monte Carlo), counting how often each
+//! resolution comes first, and with one weight at a time swept from 0 to 1
+//! (the others keep their proportions) to find the range over which the winner
+//! holds.
+
+use serde::{Deserialize, Serialize};
+
+use crate::optimization::criteria::{RankingCriteria, WeightingMethod};
+use crate::optimization::topsis::topsis_ranking;
+use crate::stats::timer_measurement::TimerMeasurement;
+use crate::utils::rng::SplitMix64;
+
+/// Fixed seed so a saved run reproduces its sensitivity report
+const SENSITIVITY_SEED: u64 = 0x5E45_1717_1E55_0019;
+
+/// Steps of the one-at-a-time sweep over [0, 1]
+const SWEEP_STEPS: usize = 20;
+
+/// Minimum share of random weightings the winner must win to count as robust
+pub const ROBUST_WIN_RATE: f64 = 0.5;
+
+/// Share of random weightings in which a resolution ranked first
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WinRate {
+    pub resolution_ms: f64,
+    pub fraction: f64,
+}
+
+/// Range of one criterion's weight over which the winner stays first
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WeightRange {
+    pub criterion: String,
+    pub weight: f64,  // Configured (or derived) weight
+    pub lower: f64,
+    pub upper: f64,
+}
+
+/// Robustness of the recommendation to the ranking weights
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WeightSensitivity {
+    pub winner_ms: f64,
+    pub samples: usize,
+    /// Resolutions that won at least once, most wins first
+    pub win_rates: Vec<WinRate>,
+    pub weight_ranges: Vec<WeightRange>,
+}
+
+impl WeightSensitivity {
+    /// Share of random weightings in which the recommended resolution won
+    pub fn winner_rate(&self) -> f64 {
+        self.win_rates.iter()
+            .find(|r| (r.resolution_ms - self.winner_ms).abs() < 0.0001)
+            .map_or(0.0, |r| r.fraction)
+    }
+
+    /// The recommendation wins for most weight settings
+    pub fn is_robust(&self) -> bool {
+        self.winner_rate() >= ROBUST_WIN_RATE
+    }
+}
+
+/// Rank `measurements` under `samples` random weightings and one-at-a-time sweeps
+///
+/// `criteria` should carry the weights the recommendation was made with (see
+/// `resolve_weights`). Returns `None` when `samples` is 0 or there is nothing
+/// to rank.
+pub fn weight_sensitivity(
+    measurements: &[TimerMeasurement],
+    criteria: &RankingCriteria,
+    samples: usize,
+) -> Option<WeightSensitivity> {
+    if samples == 0 {
+        return None;
+    }
+    let baseline: Vec<f64> = criteria.criteria.iter().map(|c| c.weight).collect();
+    let winner_ms = winner(measurements, criteria, &baseline)?;
+
+    // Monte Carlo: normalised exponential draws are uniform on the simplex
+    let mut rng = SplitMix64::new(SENSITIVITY_SEED);
+    let mut wins: Vec<(f64, usize)> = Vec::new();
+    for _ in 0..samples {
+        let draws: Vec<f64> = baseline.iter().map(|_| -rng.next_f64().ln()).collect();
+        let total: f64 = draws.iter().sum();
+        let weights: Vec<f64> = draws.iter().map(|d| d / total).collect();
+        let Some(first) = winner(measurements, criteria, &weights) else { continue };
+        match wins.iter_mut().find(|(resolution_ms, _)| (resolution_ms - first).abs() < 0.0001) {
+            Some((_, count)) => *count += 1,
+            None => wins.push((first, 1)),
+        }
+    }
+    wins.sort_by_key(|&(_, count)| std::cmp::Reverse(count));
+    let win_rates = wins.into_iter()
+        .map(|(resolution_ms, count)| WinRate { resolution_ms, fraction: count as f64 / samples as f64 })
+        .collect();
+
+    // One at a time: walk away from the configured weight until the winner changes
+    let weight_ranges = criteria.criteria.iter().enumerate()
+        .map(|(j, c)| {
+            let holds = |t: f64| winner(measurements, criteria, &with_weight(&baseline, j, t))
+                .is_some_and(|w| (w - winner_ms).abs() < 0.0001);
+            let start = (baseline[j] * SWEEP_STEPS as f64).round() as usize;
+            let mut lower = start;
+            while lower > 0 && holds((lower - 1) as f64 / SWEEP_STEPS as f64) {
+                lower -= 1;
+            }
+            let mut upper = start;
+            while upper < SWEEP_STEPS && holds((upper + 1) as f64 / SWEEP_STEPS as f64) {
+                upper += 1;
+            }
+            WeightRange {
+                criterion: c.criterion.name().to_string(),
+                weight: baseline[j],
+                lower: (lower as f64 / SWEEP_STEPS as f64).min(baseline[j]),
+                upper: (upper as f64 / SWEEP_STEPS as f64).max(baseline[j]),
+            }
+        })
+        .collect();
+
+    Some(WeightSensitivity { winner_ms, samples, win_rates, weight_ranges })
+}
+
+/// `weights` with entry `j` set to `value` and the others scaled to fill the rest
+fn with_weight(weights: &[f64], j: usize, value: f64) -> Vec<f64> {
+    let others: f64 = weights.iter().enumerate().filter(|&(k, _)| k != j).map(|(_, w)| w).sum();
+    let count = weights.len().saturating_sub(1).max(1) as f64;
+    weights.iter().enumerate()
+        .map(|(k, &w)| match (k == j, others > 1e-12) {
+            (true, _) => value,
+            (false, true) => w / others * (1.0 - value),
+            (false, false) => (1.0 - value) / count,
+        })
+        .collect()
+}
+
+/// TOPSIS winner with the given weights
+fn winner(measurements: &[TimerMeasurement], criteria: &RankingCriteria, weights: &[f64]) -> Option<f64> {
+    let mut weighted = criteria.clone().with_weighting(WeightingMethod::Manual);
+    for (c, &w) in weighted.criteria.iter_mut().zip(weights) {
+        c.weight = w;
+    }
+    topsis_ranking(measurements, &weighted).first().map(|s| s.resolution_ms)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::optimization::criteria::{Criterion, Direction, WeightedCriterion};
+
+    /// `n` evenly spaced samples of the given width around `center`
+    fn measurement(resolution_ms: f64, center: f64, width: f64) -> TimerMeasurement {
+        let n = 101;
+        let samples = (0..n).map(|i| center - width / 2.0 + width * i as f64 / (n - 1) as f64).collect();
+        TimerMeasurement::from_samples(resolution_ms, samples)
+    }
+
+    fn criteria() -> RankingCriteria {
+        RankingCriteria::custom(&[
+            WeightedCriterion { criterion: Criterion::P95, weight: 0.6, direction: Direction::Cost },
+            WeightedCriterion { criterion: Criterion::Mad, weight: 0.4, direction: Direction::Cost },
+        ]).unwrap()
+    }
+
+    #[test]
+    fn no_samples_no_report() {
+        let measurements = vec![measurement(0.5, 0.1, 0.2)];
+        assert!(weight_sensitivity(&measurements, &criteria(), 0).is_none());
+        assert!(weight_sensitivity(&[], &criteria(), 100).is_none());
+    }
+
+    #[test]
+    fn dominating_winner_holds_for_every_weighting() {
+        let measurements = vec![
+            measurement(0.5, 0.8, 0.4),
+            measurement(0.501, 0.05, 0.002),
+            measurement(0.502, 0.1, 0.2),
+        ];
+        let sensitivity = weight_sensitivity(&measurements, &criteria(), 200).unwrap();
+        assert_eq!(sensitivity.winner_ms, 0.501);
+        assert_eq!(sensitivity.winner_rate(), 1.0);
+        assert!(sensitivity.is_robust());
+        for range in &sensitivity.weight_ranges {
+            assert_eq!((range.lower, range.upper), (0.0, 1.0), "{}", range.criterion);
+        }
+    }
+
+    #[test]
+    fn trade_off_winner_depends_on_the_weights() {
+        let measurements = vec![measurement(0.5, 0.1, 0.2), measurement(0.501, 0.5, 0.004)];
+        let first = weight_sensitivity(&measurements, &criteria(), 200).unwrap();
+        let again = weight_sensitivity(&measurements, &criteria(), 200).unwrap();
+        assert_eq!(first.win_rates.len(), 2);
+        let total: f64 = first.win_rates.iter().map(|r| r.fraction).sum();
+        assert!((total - 1.0).abs() < 1e-12);
+        assert!(first.weight_ranges.iter().any(|r| r.lower > 0.0 || r.upper < 1.0));
+        // Fixed seed: the same input gives the same report
+        let rates = |s: &WeightSensitivity| s.win_rates.iter().map(|r| (r.resolution_ms, r.fraction)).collect::<Vec<_>>();
+        assert_eq!(rates(&first), rates(&again));
+    }
+
+    #[test]
+    fn set_weight_rescales_the_others() {
+        let weights = with_weight(&[0.5, 0.3, 0.2], 0, 0.8);
+        for (w, expected) in weights.iter().zip([0.8, 0.12, 0.08]) {
+            assert!((w - expected).abs() < 1e-12);
+        }
+        assert_eq!(with_weight(&[1.0, 0.0, 0.0], 0, 0.5), vec![0.5, 0.25, 0.25]);
+    }
+}
@@ -2,49 +2,194 @@
 //! implementation for multi-criteria decision making
 //!
 //! This module provides TOPSIS ranking for selecting optimal timer resolution values
-//! based on multiple criteria.
+//! based on multiple criteria. Which criteria and weights are used is set by
+//! `RankingCriteria`.
 
+use std::borrow::Borrow;
+
+use serde::{Deserialize, Serialize};
+
+use crate::optimization::criteria::{Criterion, Direction, RankingCriteria, WeightingMethod};
 use crate::stats::timer_measurement::TimerMeasurement;
 
 /// TOPSIS score for ranking solutions
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TopsisScore {
     pub resolution_ms: f64,
     pub closeness_coefficient: f64,
     pub rank: usize,
-    pub criteria_scores: CriteriaScores,
+    /// Values of the ranking criteria, in the order of `RankingCriteria::criteria`
+    pub criterion_values: Vec<CriterionValue>,
 }
 
-/// Individual criteria scores for TOPSIS analysis
-#[derive(Debug, Clone)]
-pub struct CriteriaScores {
-    pub p95_delta: f64,      // Lower is better
-    pub mad: f64,            // Lower is better
-    pub p99_delta: f64,      // Lower is better
-    pub confidence_width: f64, // Lower is better (narrow CI = more reliable)
+/// Value of one ranking criterion for a resolution
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct CriterionValue {
+    pub criterion: Criterion,
+    pub value: f64,
+}
+
+/// Criteria with the weights a ranking of `measurements` uses
+///
+/// Manual weights are returned as given; entropy and CRITIC weights are derived
+/// from the decision matrix of these measurements.
+pub fn resolve_weights(measurements: &[TimerMeasurement], criteria: &RankingCriteria) -> RankingCriteria {
+    if criteria.weighting == WeightingMethod::Manual || measurements.is_empty() {
+        return criteria.clone();
+    }
+    let (matrix, normalized) = decision_matrix(measurements, criteria);
+    let weights = criteria_weights(&matrix, &normalized, criteria);
+    let mut resolved = criteria.clone();
+    for (criterion, weight) in resolved.criteria.iter_mut().zip(weights) {
+        criterion.weight = weight;
+    }
+    resolved
 }
 
 /// Perform TOPSIS ranking on measurements
-pub fn topsis_ranking(measurements: &[TimerMeasurement]) -> Vec<TopsisScore> {
-    if measurements.is_empty() {
+pub fn topsis_ranking<M: Borrow<TimerMeasurement>>(measurements: &[M], criteria: &RankingCriteria) -> Vec<TopsisScore> {
+    let Some(model) = TopsisModel::fit(measurements, criteria) else {
         return Vec::new();
+    };
+
+    // Step 6: Closeness coefficients (proximity to ideal)
+    let mut scores: Vec<TopsisScore> = Vec::new();
+    for m in measurements {
+        let m = m.borrow();
+        scores.push(TopsisScore {
+            resolution_ms: m.resolution_ms,
+            closeness_coefficient: model.closeness(m),
+            rank: 0, // Will be filled after sorting
+            criterion_values: criteria.criteria.iter()
+                .map(|c| CriterionValue { criterion: c.criterion, value: c.criterion.value(m) })
+                .collect(),
+        });
+    }
+
+    // Step 7: Ranking (higher CC = better)
+    // ✅ ЗАЩИТА ОТ NaN В СОРТИРОВКЕ
+    scores.sort_by(|a, b| {
+        b.closeness_coefficient.partial_cmp(&a.closeness_coefficient).unwrap_or(std::cmp::Ordering::Equal)  // Fallback если NaN
+    });
+    for (rank, score) in scores.iter_mut().enumerate() {
+        score.rank = rank + 1;
+    }
+    scores
+}
+
+/// Weighted normalisation of one criterion
+#[derive(Debug, Clone, Copy)]
+enum Column {
+    Scaled(f64),    // Weight divided by the vector norm of the column
+    Constant(f64),  // Column is zero everywhere: every value maps to weight / √n
+}
+
+/// Normalisation, weights and ideal points of a TOPSIS ranking
+///
+/// Fitted to a set of measurements, the model scores any measurement in time
+/// proportional to the number of criteria. For the measurements it was fitted
+/// to the scores are those of `topsis_ranking`; a measurement added later is
+/// scored as if it had not changed the normalisation.
+#[derive(Debug, Clone)]
+pub struct TopsisModel {
+    criteria: RankingCriteria,
+    columns: Vec<Column>,
+    ideal: Vec<f64>,
+    anti_ideal: Vec<f64>,
+}
+
+impl TopsisModel {
+    /// `None` without measurements
+    pub fn fit<M: Borrow<TimerMeasurement>>(measurements: &[M], criteria: &RankingCriteria) -> Option<Self> {
+        if measurements.is_empty() {
+            return None;
+        }
+
+        // Steps 1-3: Decision matrix, vector normalization and weights
+        let n = measurements.len();
+        let (matrix, normalized) = decision_matrix(measurements, criteria);
+        let weights = criteria_weights(&matrix, &normalized, criteria);
+        let columns: Vec<Column> = weights.iter().enumerate()
+            .map(|(j, weight)| {
+                let norm = matrix.iter().map(|row| row[j].powi(2)).sum::<f64>().sqrt();
+                if norm < 1e-10 {
+                    Column::Constant(weight / (n as f64).sqrt())
+                } else {
+                    Column::Scaled(weight / norm)
+                }
+            })
+            .collect();
+        let mut model = Self {
+            criteria: criteria.clone(),
+            columns,
+            ideal: Vec::new(),
+            anti_ideal: Vec::new(),
+        };
+
+        // Step 4: Ideal and anti-ideal solutions
+        // Cost criteria: lower is better; benefit criteria: higher is better
+        let weighted: Vec<Vec<f64>> = matrix.iter().map(|row| model.weighted(row)).collect();
+        for (j, criterion) in criteria.criteria.iter().enumerate() {
+            let (min, max) = weighted.iter()
+                .fold((f64::MAX, f64::MIN), |(lo, hi), row| (lo.min(row[j]), hi.max(row[j])));
+            let (ideal, anti_ideal) = match criterion.direction {
+                Direction::Cost => (min, max),
+                Direction::Benefit => (max, min),
+            };
+            model.ideal.push(ideal);
+            model.anti_ideal.push(anti_ideal);
+        }
+        Some(model)
+    }
+
+    fn weighted(&self, row: &[f64]) -> Vec<f64> {
+        row.iter().zip(&self.columns)
+            .map(|(&value, column)| match *column {
+                Column::Scaled(factor) => value * factor,
+                Column::Constant(constant) => constant,
+            })
+            .collect()
+    }
+
+    /// Closeness coefficient of a measurement, 0.5 when it cannot be computed
+    pub fn closeness(&self, measurement: &TimerMeasurement) -> f64 {
+        let row: Vec<f64> = self.criteria.criteria.iter().map(|c| c.criterion.value(measurement)).collect();
+        let weighted = self.weighted(&row);
+
+        // Step 5: Distances to ideal and anti-ideal solutions
+        let distance = |target: &[f64]| weighted.iter().zip(target)
+            .map(|(v, t)| (v - t).powi(2))
+            .sum::<f64>()
+            .sqrt();
+        let distance_ideal = distance(&self.ideal);
+        let distance_anti = distance(&self.anti_ideal);
+
+        // ✅ ЗАЩИТА ОТ ДЕЛЕНИЯ НА 0
+        let denominator = distance_ideal + distance_anti;
+        let cc = if denominator.abs() < 1e-10 {
+            0.5  // Нейтральный score если обе дистанции близки к 0
+        } else {
+            distance_anti / denominator
+        };
+        if cc.is_nan() || cc.is_infinite() {
+            0.5  // Return neutral value as fallback
+        } else {
+            cc
+        }
     }
-    
+}
+
+/// Decision matrix (one row per measurement) and its vector-normalized form
+pub(crate) fn decision_matrix<M: Borrow<TimerMeasurement>>(measurements: &[M], criteria: &RankingCriteria) -> (Vec<Vec<f64>>, Vec<Vec<f64>>) {
     // Step 1: Build decision matrix
     let n = measurements.len();
+    let num_criteria = criteria.criteria.len();
     let mut matrix: Vec<Vec<f64>> = Vec::new();
     for m in measurements {
-        let ci_width = m.statistics.confidence_interval_95.1 - m.statistics.confidence_interval_95.0;
-        matrix.push(vec![
-            m.statistics.p95,
-            m.statistics.mad,
-            m.statistics.p99,
-            ci_width,
-        ]);
+        matrix.push(criteria.criteria.iter().map(|c| c.criterion.value(m.borrow())).collect());
     }
 
     // Step 2: Normalization (vector normalization) ✅ С ЗАЩИТОЙ!
-    let num_criteria = 4;
     let mut normalized: Vec<Vec<f64>> = vec![vec![0.0; num_criteria]; n];
     for j in 0..num_criteria {
         let sum_sq: f64 = matrix.iter().map(|row| row[j].powi(2)).sum();
@@ -53,8 +198,8 @@ pub fn topsis_ranking(measurements: &[TimerMeasurement]) -> Vec<TopsisScore> {
         // ✅ ЗАЩИТА ОТ ДЕЛЕНИЯ НА 0
         if norm < 1e-10 {
             // Если все значения ≈ 0, используем равномерное распределение
-            for i in 0..n {
-                normalized[i][j] = 1.0 / (n as f64).sqrt();
+            for row in normalized.iter_mut() {
+                row[j] = 1.0 / (n as f64).sqrt();
             }
         } else {
             for i in 0..n {
@@ -62,80 +207,161 @@ pub fn topsis_ranking(measurements: &[TimerMeasurement]) -> Vec<TopsisScore> {
             }
         }
     }
+    (matrix, normalized)
+}
 
-    // Step 3: Weighted normalized matrix
-    let weights = vec![0.40, 0.30, 0.20, 0.10]; // Criteria weights
-    let mut weighted: Vec<Vec<f64>> = vec![vec![0.0; num_criteria]; n];
-    for i in 0..n {
-        for j in 0..num_criteria {
-            weighted[i][j] = normalized[i][j] * weights[j];
-        }
+/// Weights for the configured method, falling back to the manual weights when
+/// the data cannot tell the criteria apart (e.g. a single measurement)
+pub(crate) fn criteria_weights(matrix: &[Vec<f64>], normalized: &[Vec<f64>], criteria: &RankingCriteria) -> Vec<f64> {
+    let manual = || criteria.criteria.iter().map(|c| c.weight).collect();
+    let derived = match criteria.weighting {
+        WeightingMethod::Manual => None,
+        WeightingMethod::Entropy => entropy_weights(normalized),
+        WeightingMethod::Critic => critic_weights(matrix, criteria),
+    };
+    derived.unwrap_or_else(manual)
+}
+
+/// Shannon entropy weights: criteria whose values vary more across the
+/// measurements carry more information and get more weight
+fn entropy_weights(normalized: &[Vec<f64>]) -> Option<Vec<f64>> {
+    let n = normalized.len();
+    let num_criteria = normalized.first()?.len();
+    if n < 2 {
+        return None;
     }
+    let k = 1.0 / (n as f64).ln();
+    let divergence: Vec<f64> = (0..num_criteria)
+        .map(|j| {
+            // Shift so every value is non-negative before taking proportions
+            let min = normalized.iter().map(|row| row[j]).fold(f64::INFINITY, f64::min).min(0.0);
+            let total: f64 = normalized.iter().map(|row| row[j] - min).sum();
+            if total < 1e-12 {
+                return 0.0;
+            }
+            let entropy: f64 = normalized.iter()
+                .map(|row| (row[j] - min) / total)
+                .filter(|&p| p > 0.0)
+                .map(|p| -p * p.ln())
+                .sum::<f64>() * k;
+            (1.0 - entropy).max(0.0)
+        })
+        .collect();
+    normalize_weights(divergence)
+}
 
-    // Step 4: Ideal and anti-ideal solutions
-    // All criteria are "lower is better" (cost criteria)
-    let mut ideal = vec![f64::MAX; num_criteria];
-    let mut anti_ideal = vec![f64::MIN; num_criteria];
-    for j in 0..num_criteria {
-        for i in 0..n {
-            ideal[j] = ideal[j].min(weighted[i][j]);
-            anti_ideal[j] = anti_ideal[j].max(weighted[i][j]);
-        }
+/// CRITIC weights: contrast (standard deviation of the min-max scaled column)
+/// times conflict (sum of 1 − correlation with the other criteria)
+fn critic_weights(matrix: &[Vec<f64>], criteria: &RankingCriteria) -> Option<Vec<f64>> {
+    let n = matrix.len();
+    if n < 2 {
+        return None;
     }
+    let num_criteria = criteria.criteria.len();
+    let scaled = min_max_scaled(matrix, criteria);
+    let columns: Vec<Vec<f64>> = (0..num_criteria)
+        .map(|j| scaled.iter().map(|row| row[j]).collect())
+        .collect();
 
-    // Step 5: Distances to ideal and anti-ideal solutions
-    let mut distances_ideal = vec![0.0; n];
-    let mut distances_anti = vec![0.0; n];
-    for i in 0..n {
-        let mut sum_ideal = 0.0;
-        let mut sum_anti = 0.0;
-        for j in 0..num_criteria {
-            sum_ideal += (weighted[i][j] - ideal[j]).powi(2);
-            sum_anti += (weighted[i][j] - anti_ideal[j]).powi(2);
+    let mean = |column: &[f64]| column.iter().sum::<f64>() / n as f64;
+    let stdev = |column: &[f64]| {
+        let m = mean(column);
+        (column.iter().map(|x| (x - m).powi(2)).sum::<f64>() / (n - 1) as f64).sqrt()
+    };
+    let deviations: Vec<f64> = columns.iter().map(|c| stdev(c)).collect();
+    let correlation = |a: usize, b: usize| {
+        if deviations[a] < 1e-12 || deviations[b] < 1e-12 {
+            return 0.0;
         }
-        distances_ideal[i] = sum_ideal.sqrt();
-        distances_anti[i] = sum_anti.sqrt();
+        let (ma, mb) = (mean(&columns[a]), mean(&columns[b]));
+        let covariance = columns[a].iter().zip(&columns[b])
+            .map(|(x, y)| (x - ma) * (y - mb))
+            .sum::<f64>() / (n - 1) as f64;
+        covariance / (deviations[a] * deviations[b])
+    };
+
+    let information: Vec<f64> = (0..num_criteria)
+        .map(|j| deviations[j] * (0..num_criteria).map(|k| 1.0 - correlation(j, k)).sum::<f64>())
+        .collect();
+    normalize_weights(information)
+}
+
+/// Min-max scaling of every criterion so that 1 is its best value and 0 its
+/// worst (0 everywhere when all values are equal)
+pub(crate) fn min_max_scaled(matrix: &[Vec<f64>], criteria: &RankingCriteria) -> Vec<Vec<f64>> {
+    let ranges: Vec<(f64, f64)> = (0..criteria.criteria.len())
+        .map(|j| matrix.iter()
+            .fold((f64::INFINITY, f64::NEG_INFINITY), |(lo, hi), row| (lo.min(row[j]), hi.max(row[j]))))
+        .collect();
+    matrix.iter()
+        .map(|row| criteria.criteria.iter().zip(&ranges).enumerate()
+            .map(|(j, (c, &(min, max)))| {
+                let range = max - min;
+                match (range < 1e-12, c.direction) {
+                    (true, _) => 0.0,
+                    (false, Direction::Cost) => (max - row[j]) / range,
+                    (false, Direction::Benefit) => (row[j] - min) / range,
+                }
+            })
+            .collect())
+        .collect()
+}
+
+/// Scale to sum to 1, or `None` when every value is zero
+fn normalize_weights(values: Vec<f64>) -> Option<Vec<f64>> {
+    let total: f64 = values.iter().sum();
+    if total.is_nan() || total <= 1e-12 {
+        return None;
     }
+    Some(values.into_iter().map(|v| v / total).collect())
+}
 
-    // Step 6: Closeness coefficients (proximity to ideal)
-    let mut scores: Vec<TopsisScore> = Vec::new();
-    for (i, m) in measurements.iter().enumerate() {
-        let ci_width = m.statistics.confidence_interval_95.1 - m.statistics.confidence_interval_95.0;
-        
-        // ✅ ЗАЩИТА ОТ ДЕЛЕНИЯ НА 0
-        let denominator = distances_ideal[i] + distances_anti[i];
-        let cc = if denominator.abs() < 1e-10 {
-            0.5  // Нейтральный score если обе дистанции близки к 0
-        } else {
-            distances_anti[i] / denominator
-        };
-        
-        let final_cc = if cc.is_nan() || cc.is_infinite() {
-            0.5  // Return neutral value as fallback
-        } else {
-            cc
-        };
-        
-        scores.push(TopsisScore {
-            resolution_ms: m.resolution_ms,
-            closeness_coefficient: final_cc,
-            rank: 0, // Will be filled after sorting
-            criteria_scores: CriteriaScores {
-                p95_delta: m.statistics.p95,
-                mad: m.statistics.mad,
-                p99_delta: m.statistics.p99,
-                confidence_width: ci_width,
-            },
-        });
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::optimization::criteria::WeightedCriterion;
+
+    /// Evenly spread deltas: no outliers, P95 and MAD set by `centre` and `spread`
+    fn measurement(resolution_ms: f64, centre: f64, spread: f64) -> TimerMeasurement {
+        let samples = (0..100).map(|i| centre + spread * (i as f64 / 99.0 - 0.5)).collect();
+        TimerMeasurement::from_samples(resolution_ms, samples)
     }
 
-    // Step 7: Ranking (higher CC = better)
-    // ✅ ЗАЩИТА ОТ NaN В СОРТИРОВКЕ
-    scores.sort_by(|a, b| {
-        b.closeness_coefficient.partial_cmp(&a.closeness_coefficient).unwrap_or(std::cmp::Ordering::Equal)  // Fallback если NaN
-    });
-    for (rank, score) in scores.iter_mut().enumerate() {
-        score.rank = rank + 1;
+    /// P95, MAD and outliers, the last constant across `measurements`
+    fn objective_weights(weighting: WeightingMethod) -> Vec<f64> {
+        let measurements = [
+            measurement(0.5, 0.10, 0.02),
+            measurement(0.501, 0.12, 0.08),
+            measurement(0.502, 0.09, 0.05),
+            measurement(0.503, 0.15, 0.03),
+        ];
+        assert!(measurements.iter().all(|m| m.statistics.outliers_removed == 0));
+        let criteria = RankingCriteria::custom(&[Criterion::P95, Criterion::Mad, Criterion::Outliers]
+            .map(|criterion| WeightedCriterion { criterion, weight: 1.0, direction: Direction::Cost }))
+            .unwrap()
+            .with_weighting(weighting);
+        resolve_weights(&measurements, &criteria).criteria.iter().map(|c| c.weight).collect()
     }
-    scores
-}
\ No newline at end of file
+
+    fn assert_objective(weights: &[f64]) {
+        assert!((weights.iter().sum::<f64>() - 1.0).abs() < 1e-12, "{:?}", weights);
+        assert!(weights[0] > 0.0 && weights[1] > 0.0, "{:?}", weights);
+        assert_eq!(weights[2], 0.0, "constant criterion weighted: {:?}", weights);
+    }
+
+    #[test]
+    fn entropy_weights_skip_constant_criteria() {
+        assert_objective(&objective_weights(WeightingMethod::Entropy));
+    }
+
+    #[test]
+    fn critic_weights_skip_constant_criteria() {
+        assert_objective(&objective_weights(WeightingMethod::Critic));
+    }
+
+    #[test]
+    fn manual_weights_are_kept() {
+        let weights = objective_weights(WeightingMethod::Manual);
+        assert!(weights.iter().all(|&w| (w - 1.0 / 3.0).abs() < 1e-12));
+    }
+}
@@ -0,0 +1,358 @@
+//! Chart rendering with plotters (SVG and PNG)
+//!
+//! Charts are drawn from an `OptimizationResult`:
+//! - P50/P95/P99 delta vs resolution with the 95% CI band of the mean
+//! - MAD vs resolution
+//! - TOPSIS closeness vs resolution with the winner highlighted
+//! - Raw sample scatter per resolution
+//! - GP surrogate posterior (hybrid and refine searches only)
+//!
+//! Text is rendered with the bundled DejaVu Sans font, so no system fonts,
+//! font configuration or GPU are needed.
+
+use std::io;
+use std::ops::Range;
+use std::path::{Path, PathBuf};
+use std::sync::Once;
+
+use plotters::coord::Shift;
+use plotters::prelude::*;
+use plotters::style::text_anchor::{HPos, Pos, VPos};
+
+use crate::core::OptimizationResult;
+use crate::optimization::gaussian_process::{PosteriorPoint, SurrogateCurve};
+use crate::stats::timer_measurement::TimerMeasurement;
+
+const FONT_FAMILY: &str = "sans-serif";
+static FONT_DATA: &[u8] = include_bytes!("../../assets/fonts/DejaVuSans.ttf");
+static REGISTER_FONT: Once = Once::new();
+
+const CHART_SIZE: (u32, u32) = (1200, 700);
+const MAX_SCATTER_SAMPLES_PER_POINT: usize = 200;
+
+/// Image format of rendered charts
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum ChartFormat {
+    Svg,
+    Png,
+}
+
+impl ChartFormat {
+    fn extension(self) -> &'static str {
+        match self {
+            ChartFormat::Svg => "svg",
+            ChartFormat::Png => "png",
+        }
+    }
+}
+
+/// Charts produced for a run
+#[derive(Debug, Clone, Copy)]
+enum ChartKind {
+    Delta,
+    Mad,
+    Topsis,
+    Samples,
+    Surrogate,  // Only when the search fitted a surrogate
+}
+
+impl ChartKind {
+    const ALL: [ChartKind; 5] = [ChartKind::Delta, ChartKind::Mad, ChartKind::Topsis, ChartKind::Samples, ChartKind::Surrogate];
+
+    fn file_stem(self) -> &'static str {
+        match self {
+            ChartKind::Delta => "delta_vs_resolution",
+            ChartKind::Mad => "mad_vs_resolution",
+            ChartKind::Topsis => "topsis_closeness",
+            ChartKind::Samples => "samples_scatter",
+            ChartKind::Surrogate => "gp_surrogate",
+        }
+    }
+}
+
+/// Measurements sorted by resolution with their TOPSIS scores
+struct ChartData<'a> {
+    points: Vec<(&'a TimerMeasurement, f64)>,
+    optimal_resolution: f64,
+    surrogate: Option<&'a SurrogateCurve>,
+}
+
+impl<'a> ChartData<'a> {
+    fn new(result: &'a OptimizationResult) -> Self {
+        let mut points: Vec<(&TimerMeasurement, f64)> = result.aggregated_measurements.iter()
+            .map(|m| {
+                let closeness = result.topsis_rankings.iter()
+                    .find(|t| (t.resolution_ms - m.resolution_ms).abs() < 0.0001)
+                    .map_or(0.0, |t| t.closeness_coefficient);
+                (m, closeness)
+            })
+            .collect();
+        points.sort_by(|a, b| a.0.resolution_ms.total_cmp(&b.0.resolution_ms));
+        Self { points, optimal_resolution: result.optimal_resolution, surrogate: result.surrogate.as_ref() }
+    }
+
+    fn x_range(&self) -> Range<f64> {
+        padded_range(self.points.iter().map(|(m, _)| m.resolution_ms), 0.0005)
+    }
+}
+
+/// Render all charts into `out_dir`, returning the written files
+pub fn render_charts(
+    result: &OptimizationResult,
+    out_dir: impl AsRef<Path>,
+    formats: &[ChartFormat],
+) -> io::Result<Vec<PathBuf>> {
+    let out_dir = out_dir.as_ref();
+    if result.aggregated_measurements.is_empty() {
+        return Err(io::Error::new(io::ErrorKind::InvalidInput, "No measurements to chart"));
+    }
+    std::fs::create_dir_all(out_dir)?;
+    register_font()?;
+
+    let data = ChartData::new(result);
+    let mut written = Vec::new();
+    for &format in formats {
+        for kind in ChartKind::ALL {
+            if matches!(kind, ChartKind::Surrogate) && data.surrogate.is_none() {
+                continue;
+            }
+            let path = out_dir.join(format!("{}.{}", kind.file_stem(), format.extension()));
+            let rendered = match format {
+                ChartFormat::Svg => draw(SVGBackend::new(&path, CHART_SIZE).into_drawing_area(), kind, &data)
+                    .map_err(|e| e.to_string()),
+                ChartFormat::Png => draw(BitMapBackend::new(&path, CHART_SIZE).into_drawing_area(), kind, &data)
+                    .map_err(|e| e.to_string()),
+            };
+            rendered.map_err(|e| io::Error::other(format!("Failed to render {}: {}", path.display(), e)))?;
+            written.push(path);
+        }
+    }
+    Ok(written)
+}
+
+fn register_font() -> io::Result<()> {
+    let mut result = Ok(());
+    REGISTER_FONT.call_once(|| {
+        if plotters::style::register_font(FONT_FAMILY, FontStyle::Normal, FONT_DATA).is_err() {
+            result = Err(io::Error::new(io::ErrorKind::InvalidData, "Bundled chart font is invalid"));
+        }
+    });
+    result
+}
+
+type StatisticFn = fn(&TimerMeasurement) -> f64;
+type DrawResult<DB> = Result<(), DrawingAreaErrorKind<<DB as DrawingBackend>::ErrorType>>;
+
+fn draw<DB: DrawingBackend>(root: DrawingArea<DB, Shift>, kind: ChartKind, data: &ChartData) -> DrawResult<DB> {
+    root.fill(&WHITE)?;
+    match kind {
+        ChartKind::Delta => draw_delta_chart(&root, data)?,
+        ChartKind::Mad => draw_mad_chart(&root, data)?,
+        ChartKind::Topsis => draw_topsis_chart(&root, data)?,
+        ChartKind::Samples => draw_samples_chart(&root, data)?,
+        ChartKind::Surrogate => draw_surrogate_chart(&root, data)?,
+    }
+    root.present()
+}
+
+fn draw_delta_chart<DB: DrawingBackend>(root: &DrawingArea<DB, Shift>, data: &ChartData) -> DrawResult<DB> {
+    let values = data.points.iter().flat_map(|(m, _)| {
+        let s = &m.statistics;
+        [s.median, s.p95, s.p99, s.confidence_interval_95.0, s.confidence_interval_95.1]
+    });
+    let mut chart = ChartBuilder::on(root)
+        .caption("Sleep delta vs timer resolution", (FONT_FAMILY, 26))
+        .margin(16)
+        .x_label_area_size(48)
+        .y_label_area_size(72)
+        .build_cartesian_2d(data.x_range(), padded_range(values, 0.001))?;
+    chart.configure_mesh()
+        .x_desc("Resolution (ms)")
+        .y_desc("Delta (ms)")
+        .label_style((FONT_FAMILY, 14))
+        .draw()?;
+
+    // 95% CI of the mean as a band
+    let band: Vec<(f64, f64)> = data.points.iter()
+        .map(|(m, _)| (m.resolution_ms, m.statistics.confidence_interval_95.1))
+        .chain(data.points.iter().rev().map(|(m, _)| (m.resolution_ms, m.statistics.confidence_interval_95.0)))
+        .collect();
+    chart.draw_series(std::iter::once(Polygon::new(band, BLUE.mix(0.15))))?
+        .label("95% CI (mean)")
+        .legend(|(x, y)| Rectangle::new([(x, y - 5), (x + 20, y + 5)], BLUE.mix(0.15).filled()));
+
+    let series: [(&str, RGBColor, StatisticFn); 3] = [
+        ("P50", GREEN, |m| m.statistics.median),
+        ("P95", BLUE, |m| m.statistics.p95),
+        ("P99", RED, |m| m.statistics.p99),
+    ];
+    for (label, color, value) in series {
+        chart.draw_series(LineSeries::new(
+            data.points.iter().map(|(m, _)| (m.resolution_ms, value(m))),
+            color.stroke_width(2),
+        ))?
+            .label(label)
+            .legend(move |(x, y)| PathElement::new(vec![(x, y), (x + 20, y)], color.stroke_width(2)));
+    }
+
+    chart.configure_series_labels()
+        .label_font((FONT_FAMILY, 14))
+        .background_style(WHITE.mix(0.8))
+        .border_style(BLACK)
+        .draw()
+}
+
+fn draw_mad_chart<DB: DrawingBackend>(root: &DrawingArea<DB, Shift>, data: &ChartData) -> DrawResult<DB> {
+    let mut chart = ChartBuilder::on(root)
+        .caption("MAD vs timer resolution", (FONT_FAMILY, 26))
+        .margin(16)
+        .x_label_area_size(48)
+        .y_label_area_size(72)
+        .build_cartesian_2d(data.x_range(), padded_range(data.points.iter().map(|(m, _)| m.statistics.mad), 0.001))?;
+    chart.configure_mesh()
+        .x_desc("Resolution (ms)")
+        .y_desc("MAD (ms)")
+        .label_style((FONT_FAMILY, 14))
+        .draw()?;
+
+    let points = data.points.iter().map(|(m, _)| (m.resolution_ms, m.statistics.mad));
+    chart.draw_series(LineSeries::new(points.clone(), MAGENTA.stroke_width(2)))?;
+    chart.draw_series(points.map(|p| Circle::new(p, 3, MAGENTA.filled())))?;
+    Ok(())
+}
+
+fn draw_topsis_chart<DB: DrawingBackend>(root: &DrawingArea<DB, Shift>, data: &ChartData) -> DrawResult<DB> {
+    let mut chart = ChartBuilder::on(root)
+        .caption("TOPSIS closeness vs timer resolution", (FONT_FAMILY, 26))
+        .margin(16)
+        .x_label_area_size(48)
+        .y_label_area_size(72)
+        .build_cartesian_2d(data.x_range(), 0.0..1.05)?;
+    chart.configure_mesh()
+        .x_desc("Resolution (ms)")
+        .y_desc("Closeness coefficient")
+        .label_style((FONT_FAMILY, 14))
+        .draw()?;
+
+    let points = data.points.iter().map(|(m, cc)| (m.resolution_ms, *cc));
+    chart.draw_series(LineSeries::new(points.clone(), BLUE.stroke_width(2)))?;
+    chart.draw_series(points.map(|p| Circle::new(p, 3, BLUE.filled())))?;
+
+    if let Some((winner, cc)) = data.points.iter()
+        .find(|(m, _)| (m.resolution_ms - data.optimal_resolution).abs() < 0.0001)
+    {
+        let position = (winner.resolution_ms, *cc);
+        chart.draw_series(std::iter::once(Circle::new(position, 8, RED.filled())))?;
+        chart.draw_series(std::iter::once(Text::new(
+            format!("Optimal {:.4} ms ({:.4})", winner.resolution_ms, cc),
+            position,
+            (FONT_FAMILY, 16).into_font().color(&RED).pos(Pos::new(HPos::Left, VPos::Bottom)),
+        )))?;
+    }
+    Ok(())
+}
+
+fn draw_samples_chart<DB: DrawingBackend>(root: &DrawingArea<DB, Shift>, data: &ChartData) -> DrawResult<DB> {
+    // Spikes would flatten the plot, so samples far above every P99 are cut off
+    let y_max = data.points.iter()
+        .map(|(m, _)| m.statistics.p99)
+        .fold(0.0_f64, f64::max) * 2.0;
+    let y_min = data.points.iter()
+        .flat_map(|(m, _)| m.raw_samples.iter().copied())
+        .fold(f64::INFINITY, f64::min)
+        .min(0.0);
+    let hidden = data.points.iter()
+        .flat_map(|(m, _)| m.raw_samples.iter())
+        .filter(|&&delta| delta > y_max)
+        .count();
+
+    let mut chart = ChartBuilder::on(root)
+        .caption(format!("Raw samples per resolution ({} above {:.3} ms not shown)", hidden, y_max), (FONT_FAMILY, 22))
+        .margin(16)
+        .x_label_area_size(48)
+        .y_label_area_size(72)
+        .build_cartesian_2d(data.x_range(), y_min..y_max.max(y_min + 0.001))?;
+    chart.configure_mesh()
+        .x_desc("Resolution (ms)")
+        .y_desc("Delta (ms)")
+        .label_style((FONT_FAMILY, 14))
+        .draw()?;
+
+    for (m, _) in &data.points {
+        let stride = m.raw_samples.len().div_ceil(MAX_SCATTER_SAMPLES_PER_POINT).max(1);
+        chart.draw_series(m.raw_samples.iter()
+            .step_by(stride)
+            .filter(|&&delta| delta <= y_max)
+            .map(|&delta| Circle::new((m.resolution_ms, delta), 2, BLUE.mix(0.35).filled())))?;
+    }
+    chart.draw_series(LineSeries::new(
+        data.points.iter().map(|(m, _)| (m.resolution_ms, m.statistics.median)),
+        RED.stroke_width(2),
+    ))?
+        .label("Median")
+        .legend(|(x, y)| PathElement::new(vec![(x, y), (x + 20, y)], RED.stroke_width(2)));
+
+    chart.configure_series_labels()
+        .label_font((FONT_FAMILY, 14))
+        .background_style(WHITE.mix(0.8))
+        .border_style(BLACK)
+        .draw()
+}
+
+fn draw_surrogate_chart<DB: DrawingBackend>(root: &DrawingArea<DB, Shift>, data: &ChartData) -> DrawResult<DB> {
+    let Some(curve) = data.surrogate else { return Ok(()) };
+    let upper = |p: &PosteriorPoint| p.mean + 2.0 * p.variance.sqrt();
+    let lower = |p: &PosteriorPoint| p.mean - 2.0 * p.variance.sqrt();
+    let values = curve.points.iter()
+        .flat_map(|p| [upper(p), lower(p)])
+        .chain(curve.observations.iter().map(|&(_, y)| y));
+    let x_range = padded_range(curve.points.iter().map(|p| p.resolution_ms), 0.0005);
+
+    let mut chart = ChartBuilder::on(root)
+        .caption(format!("GP surrogate ({} kernel, length scale {:.4} ms)",
+            curve.kernel.name(), curve.hyperparameters.length_scale), (FONT_FAMILY, 24))
+        .margin(16)
+        .x_label_area_size(48)
+        .y_label_area_size(72)
+        .build_cartesian_2d(x_range, padded_range(values, 0.001))?;
+    chart.configure_mesh()
+        .x_desc("Resolution (ms)")
+        .y_desc("Performance score (lower is better)")
+        .label_style((FONT_FAMILY, 14))
+        .draw()?;
+
+    let band: Vec<(f64, f64)> = curve.points.iter()
+        .map(|p| (p.resolution_ms, upper(p)))
+        .chain(curve.points.iter().rev().map(|p| (p.resolution_ms, lower(p))))
+        .collect();
+    chart.draw_series(std::iter::once(Polygon::new(band, BLUE.mix(0.15))))?
+        .label("Mean ± 2σ")
+        .legend(|(x, y)| Rectangle::new([(x, y - 5), (x + 20, y + 5)], BLUE.mix(0.15).filled()));
+    chart.draw_series(LineSeries::new(
+        curve.points.iter().map(|p| (p.resolution_ms, p.mean)),
+        BLUE.stroke_width(2),
+    ))?
+        .label("Posterior mean")
+        .legend(|(x, y)| PathElement::new(vec![(x, y), (x + 20, y)], BLUE.stroke_width(2)));
+    chart.draw_series(curve.observations.iter().map(|&p| Circle::new(p, 4, RED.filled())))?
+        .label("Observations")
+        .legend(|(x, y)| Circle::new((x + 10, y), 4, RED.filled()));
+
+    chart.configure_series_labels()
+        .label_font((FONT_FAMILY, 14))
+        .background_style(WHITE.mix(0.8))
+        .border_style(BLACK)
+        .draw()
+}
+
+/// Value range with 5% padding on both sides (at least `min_pad`)
+fn padded_range(values: impl Iterator<Item = f64>, min_pad: f64) -> Range<f64> {
+    let (min, max) = values
+        .filter(|v| v.is_finite())
+        .fold((f64::INFINITY, f64::NEG_INFINITY), |(lo, hi), v| (lo.min(v), hi.max(v)));
+    if !min.is_finite() {
+        return 0.0..1.0;
+    }
+    let pad = ((max - min) * 0.05).max(min_pad);
+    (min - pad)..(max + pad)
+}
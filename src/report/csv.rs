@@ -0,0 +1,285 @@
+//! CSV export with a stable schema
+//!
+//! A run is written as three files:
+//! - `results.csv`: one row per resolution in rank order (same values as results.txt),
+//!   followed by one `criterion_<name>` column per ranking criterion
+//! - `results_samples.csv`: one row per raw sample (resolution, run, sample, delta)
+//! - `results.meta.json`: parameters, method, system info and timing
+//!
+//! Both CSV files start with the header line and contain nothing but records,
+//! so they import directly into spreadsheets, pandas or Plotly.
+
+use std::collections::BTreeMap;
+use std::io::{self, Error, ErrorKind};
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use crate::core::{BenchmarkingParameters, FailedPoint, OptimizationResult};
+use crate::optimization::criteria::RankingCriteria;
+use crate::stats::drift::{suggested_settle_ms, Stationarity};
+use crate::stats::quantile::QuantileEstimator;
+use crate::stats::robust_statistics::RobustStatistics;
+use crate::stats::timer_measurement::TimerMeasurement;
+
+use super::json::{RunMetadata, SystemInfo, SCHEMA_VERSION};
+use super::text::{result_rows, ResultRow};
+use super::write_atomic;
+
+/// One raw sample in the long CSV
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SampleRow {
+    pub resolution_ms: f64,
+    pub run: usize,      // 0-based measurement run
+    pub sample: usize,   // 0-based sample within the run
+    pub delta_ms: f64,
+}
+
+/// Metadata sidecar describing a CSV export
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CsvMetadata {
+    pub schema_version: u32,
+    pub tool_version: String,
+    pub summary_file: String,
+    pub samples_file: String,
+    pub run: RunMetadata,
+    pub system: SystemInfo,
+    pub parameters: BenchmarkingParameters,
+    pub optimal_resolution_ms: f64,
+    pub topsis_score: f64,
+    #[serde(default)]
+    pub criteria: RankingCriteria,
+    #[serde(default)]
+    pub partial: bool,
+    /// Resolutions statistically equivalent to the optimum, including it
+    #[serde(default)]
+    pub equivalent_set_ms: Vec<f64>,
+    /// Resolutions not beaten on every criterion by another point
+    #[serde(default)]
+    pub pareto_front_ms: Vec<f64>,
+    /// TOPSIS, VIKOR and weighted sum pick the same resolution
+    #[serde(default)]
+    pub rankers_agree: Option<bool>,
+    /// Share of random weightings in which the optimum ranked first (when enabled)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub winner_win_rate: Option<f64>,
+    /// Points skipped because their samples were unusable
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub failed_points: Vec<FailedPoint>,
+    /// Warm-up samples dropped per resolution (points without warm-up are left out)
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub warmup_samples: BTreeMap<String, usize>,
+    /// Settle time that would have covered the longest warm-up
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub suggested_settle_ms: Option<u64>,
+}
+
+/// Path of the per-sample CSV belonging to a summary CSV
+pub fn samples_path(summary_path: &Path) -> PathBuf {
+    sibling_path(summary_path, "_samples.csv")
+}
+
+/// Path of the metadata sidecar belonging to a summary CSV
+pub fn metadata_path(summary_path: &Path) -> PathBuf {
+    sibling_path(summary_path, ".meta.json")
+}
+
+fn sibling_path(path: &Path, suffix: &str) -> PathBuf {
+    let stem = path.file_stem().map(|s| s.to_string_lossy().into_owned()).unwrap_or_default();
+    path.with_file_name(format!("{}{}", stem, suffix))
+}
+
+/// Write the summary CSV, the per-sample CSV and the metadata sidecar
+pub fn save_csv_results(
+    result: &OptimizationResult,
+    parameters: &BenchmarkingParameters,
+    run: &RunMetadata,
+    summary_path: impl AsRef<Path>,
+) -> io::Result<()> {
+    let summary_path = summary_path.as_ref();
+    let samples_path = samples_path(summary_path);
+
+    write_atomic(summary_path, &summary_csv(result)?)?;
+
+    let mut measurements: Vec<&TimerMeasurement> = result.aggregated_measurements.iter().collect();
+    measurements.sort_by(|a, b| a.resolution_ms.total_cmp(&b.resolution_ms));
+    let sample_rows: Vec<SampleRow> = measurements.iter()
+        .flat_map(|m| m.runs().into_iter().enumerate().flat_map(move |(run, deltas)| {
+            deltas.iter().enumerate().map(move |(sample, &delta_ms)| SampleRow {
+                resolution_ms: m.resolution_ms,
+                run,
+                sample,
+                delta_ms,
+            })
+        }))
+        .collect();
+    write_atomic(&samples_path, &to_csv(&sample_rows)?)?;
+
+    let file_name = |path: &Path| path.file_name().map(|n| n.to_string_lossy().into_owned()).unwrap_or_default();
+    let metadata = CsvMetadata {
+        schema_version: SCHEMA_VERSION,
+        tool_version: crate::VERSION.to_string(),
+        summary_file: file_name(summary_path),
+        samples_file: file_name(&samples_path),
+        run: run.clone(),
+        system: SystemInfo::collect(),
+        parameters: parameters.clone(),
+        optimal_resolution_ms: result.optimal_resolution,
+        topsis_score: result.topsis_score,
+        criteria: result.criteria.clone(),
+        partial: result.partial,
+        equivalent_set_ms: result.significance.as_ref()
+            .map(|s| s.equivalent_set_ms.clone())
+            .unwrap_or_default(),
+        pareto_front_ms: result.rankers.as_ref()
+            .map(|r| r.pareto_front_ms.clone())
+            .unwrap_or_default(),
+        rankers_agree: result.rankers.as_ref().map(|r| r.agree()),
+        winner_win_rate: result.sensitivity.as_ref().map(|s| s.winner_rate()),
+        failed_points: result.failed_points.clone(),
+        warmup_samples: result.aggregated_measurements.iter()
+            .filter(|m| m.stationarity.warmup_samples > 0)
+            .map(|m| (format!("{:.4}", m.resolution_ms), m.stationarity.warmup_samples))
+            .collect(),
+        suggested_settle_ms: suggested_settle_ms(result.aggregated_measurements.iter().map(|m| &m.stationarity)),
+    };
+    write_atomic(&metadata_path(summary_path), &serde_json::to_vec_pretty(&metadata)?)
+}
+
+/// Results table with the values of the ranking criteria appended to each row
+fn summary_csv(result: &OptimizationResult) -> io::Result<Vec<u8>> {
+    let table = to_csv(&result_rows(result)?)?;
+    let Some(first) = result.topsis_rankings.first() else {
+        return Ok(table);
+    };
+    let mut reader = ::csv::Reader::from_reader(table.as_slice());
+    let mut writer = ::csv::Writer::from_writer(Vec::new());
+    let mut header = reader.headers().map_err(csv_error)?.clone();
+    for score in &first.criterion_values {
+        header.push_field(&format!("criterion_{}", score.criterion.name()));
+    }
+    writer.write_record(&header).map_err(csv_error)?;
+    // result_rows keeps the order of the ranking
+    for (record, topsis) in reader.records().zip(&result.topsis_rankings) {
+        let mut record = record.map_err(csv_error)?;
+        for score in &topsis.criterion_values {
+            record.push_field(&score.value.to_string());
+        }
+        writer.write_record(&record).map_err(csv_error)?;
+    }
+    writer.into_inner().map_err(|e| Error::other(e.to_string()))
+}
+
+fn to_csv<T: Serialize>(rows: &[T]) -> io::Result<Vec<u8>> {
+    let mut writer = ::csv::Writer::from_writer(Vec::new());
+    for row in rows {
+        writer.serialize(row).map_err(csv_error)?;
+    }
+    writer.into_inner().map_err(|e| Error::other(e.to_string()))
+}
+
+fn from_csv<T: for<'de> Deserialize<'de>>(path: &Path) -> io::Result<Vec<T>> {
+    let mut reader = ::csv::Reader::from_path(path).map_err(csv_error)?;
+    reader.deserialize().map(|row| row.map_err(csv_error)).collect()
+}
+
+fn csv_error(e: ::csv::Error) -> Error {
+    match e.kind() {
+        ::csv::ErrorKind::Io(_) => Error::other(e),
+        _ => Error::new(ErrorKind::InvalidData, e),
+    }
+}
+
+/// Read a summary CSV written by `save_csv_results`
+pub fn load_summary_csv(path: impl AsRef<Path>) -> io::Result<Vec<ResultRow>> {
+    from_csv(path.as_ref())
+}
+
+/// (sample index, delta) pairs keyed by run index
+type RunSamples = BTreeMap<usize, Vec<(usize, f64)>>;
+
+/// Read a per-sample CSV back into measurements, one per resolution
+///
+/// Statistics are recomputed from the raw samples, percentiles by `estimator`.
+pub fn load_measurements_csv(path: impl AsRef<Path>, estimator: QuantileEstimator) -> io::Result<Vec<TimerMeasurement>> {
+    let rows: Vec<SampleRow> = from_csv(path.as_ref())?;
+
+    // Group by resolution (0.0001 ms grid, as in aggregation), then by run
+    let mut groups: BTreeMap<i64, (f64, RunSamples)> = BTreeMap::new();
+    for row in rows {
+        let key = (row.resolution_ms * 10000.0).round() as i64;
+        groups.entry(key)
+            .or_insert_with(|| (row.resolution_ms, BTreeMap::new()))
+            .1.entry(row.run).or_default()
+            .push((row.sample, row.delta_ms));
+    }
+
+    groups.into_values().map(|(resolution_ms, runs)| {
+        let mut raw_samples = Vec::new();
+        let mut run_lengths = Vec::with_capacity(runs.len());
+        for (_, mut samples) in runs {
+            samples.sort_by_key(|&(index, _)| index);
+            run_lengths.push(samples.len());
+            raw_samples.extend(samples.into_iter().map(|(_, delta)| delta));
+        }
+        let statistics = RobustStatistics::try_from_samples(raw_samples.clone(), estimator)
+            .map_err(|e| Error::new(ErrorKind::InvalidData, format!("{:.4} ms: {}", resolution_ms, e)))?;
+        Ok(TimerMeasurement {
+            resolution_ms,
+            statistics,
+            raw_samples,
+            run_lengths,
+            histogram: None,
+            stationarity: Stationarity::default(),
+        })
+    }).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::report::{scratch_dir, test_run};
+
+    /// Three runs of evenly spread deltas
+    fn measurement(resolution_ms: f64, center: f64, width: f64) -> TimerMeasurement {
+        let samples = (0..101).map(|i| center - width / 2.0 + width * (i * 37 % 101) as f64 / 100.0).collect();
+        let mut measurement = TimerMeasurement::from_samples(resolution_ms, samples);
+        measurement.run_lengths = vec![40, 30, 31];
+        measurement
+    }
+
+    #[test]
+    fn csv_files_round_trip() {
+        let result = OptimizationResult::ranked(vec![
+            measurement(0.5, 0.02, 0.01),
+            measurement(0.5010, 0.01, 0.004),
+            measurement(0.5020, 0.03, 0.02),
+        ]);
+        let (parameters, run) = test_run();
+        let path = scratch_dir("csv-round-trip").join("results.csv");
+        save_csv_results(&result, &parameters, &run, &path).unwrap();
+
+        let rows = load_summary_csv(&path).unwrap();
+        let saved = result_rows(&result).unwrap();
+        assert_eq!(rows.len(), saved.len());
+        for (loaded, saved) in rows.iter().zip(&saved) {
+            assert_eq!((loaded.resolution_ms, loaded.rank), (saved.resolution_ms, saved.rank));
+            assert_eq!((loaded.median, loaded.p95, loaded.p99), (saved.median, saved.p95, saved.p99));
+            assert_eq!((loaded.mean, loaded.stdev, loaded.mad), (saved.mean, saved.stdev, saved.mad));
+            assert_eq!(loaded.topsis_score, saved.topsis_score);
+        }
+
+        let measurements = load_measurements_csv(samples_path(&path), QuantileEstimator::Type7).unwrap();
+        let mut saved: Vec<&TimerMeasurement> = result.aggregated_measurements.iter().collect();
+        saved.sort_by(|a, b| a.resolution_ms.total_cmp(&b.resolution_ms));
+        assert_eq!(measurements.len(), saved.len());
+        for (loaded, saved) in measurements.iter().zip(saved) {
+            assert_eq!(loaded.resolution_ms, saved.resolution_ms);
+            assert_eq!(loaded.run_lengths, saved.run_lengths);
+            assert_eq!(loaded.raw_samples, saved.raw_samples);
+            let (l, s) = (&loaded.statistics, &saved.statistics);
+            assert_eq!((l.median, l.p95, l.p99, l.mad), (s.median, s.p95, s.p99, s.mad));
+            assert_eq!((l.mean, l.stdev, l.outliers_removed), (s.mean, s.stdev, s.outliers_removed));
+        }
+    }
+}
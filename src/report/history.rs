@@ -0,0 +1,317 @@
+//! Local run history and cross-run comparison
+//!
+//! Every finished run is stored as a `ResultDocument` in the history directory
+//! under `<UTC timestamp>-<system fingerprint>.json`. Runs are referenced by
+//! that id (or a unique prefix of it), by `latest` / `previous`, or by a path
+//! to any results.json file.
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::io::{self, Error, ErrorKind};
+use std::path::{Path, PathBuf};
+
+use comfy_table::{presets::UTF8_FULL, Table};
+
+use crate::stats::significance::{bootstrap_difference, Statistic, DEFAULT_RESAMPLES};
+use crate::stats::timer_measurement::TimerMeasurement;
+use crate::ui::localization::{Localization, LocalizationKey};
+
+use super::json::{load_json_results, ResultDocument};
+use super::write_atomic;
+
+/// Default history directory, relative to the working directory
+pub const HISTORY_DIR: &str = "history";
+
+/// A stored run
+#[derive(Debug, Clone)]
+pub struct HistoryEntry {
+    pub id: String,
+    pub path: PathBuf,
+    pub document: ResultDocument,
+}
+
+/// Store a run in the history directory and return its id
+pub fn save_to_history(document: &ResultDocument, dir: impl AsRef<Path>) -> io::Result<String> {
+    let dir = dir.as_ref();
+    fs::create_dir_all(dir)?;
+    let base = format!("{}-{}", format_utc(document.run.started_at_unix_ms), document.system.fingerprint);
+    let mut id = base.clone();
+    let mut suffix = 1;
+    while dir.join(format!("{}.json", id)).exists() {
+        suffix += 1;
+        id = format!("{}-{}", base, suffix);
+    }
+    write_atomic(&dir.join(format!("{}.json", id)), &serde_json::to_vec_pretty(document)?)?;
+    Ok(id)
+}
+
+/// All stored runs, oldest first (unreadable files are skipped with a warning)
+pub fn list_history(dir: impl AsRef<Path>) -> io::Result<Vec<HistoryEntry>> {
+    let dir = dir.as_ref();
+    if !dir.exists() {
+        return Ok(Vec::new());
+    }
+    let mut entries = Vec::new();
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path.extension().and_then(|e| e.to_str()) != Some("json") {
+            continue;
+        }
+        match load_json_results(&path) {
+            Ok(document) => entries.push(HistoryEntry {
+                id: path.file_stem().map(|s| s.to_string_lossy().into_owned()).unwrap_or_default(),
+                path,
+                document,
+            }),
+            Err(e) => eprintln!("⚠️ Skipping {}: {}", path.display(), e),
+        }
+    }
+    entries.sort_by(|a, b| a.document.run.started_at_unix_ms.cmp(&b.document.run.started_at_unix_ms)
+        .then_with(|| a.id.cmp(&b.id)));
+    Ok(entries)
+}
+
+/// Find a run by id, id prefix, `latest`, `previous` or file path
+pub fn resolve_run(dir: impl AsRef<Path>, reference: &str) -> io::Result<HistoryEntry> {
+    let path = Path::new(reference);
+    if path.is_file() {
+        return Ok(HistoryEntry {
+            id: path.file_stem().map(|s| s.to_string_lossy().into_owned()).unwrap_or_default(),
+            path: path.to_path_buf(),
+            document: load_json_results(path)?,
+        });
+    }
+
+    let mut entries = list_history(dir)?;
+    let not_found = || Error::new(ErrorKind::NotFound, format!("No run matches '{}'", reference));
+    match reference {
+        "latest" => return entries.pop().ok_or_else(not_found),
+        "previous" => {
+            entries.pop();
+            return entries.pop().ok_or_else(not_found);
+        },
+        _ => {},
+    }
+    let mut matches: Vec<HistoryEntry> = entries.into_iter().filter(|e| e.id.starts_with(reference)).collect();
+    match matches.len() {
+        0 => Err(not_found()),
+        1 => Ok(matches.remove(0)),
+        n => Err(Error::new(ErrorKind::InvalidInput, format!("'{}' matches {} runs, use a longer id", reference, n))),
+    }
+}
+
+/// Print the stored runs with their recommended resolution
+pub fn print_history(dir: impl AsRef<Path>, localization: &Localization) -> io::Result<()> {
+    let dir = dir.as_ref();
+    let entries = list_history(dir)?;
+    if entries.is_empty() {
+        println!("{}", localization.get_history_empty(&dir.display().to_string()));
+        return Ok(());
+    }
+
+    let mut table = Table::new();
+    table.load_preset(UTF8_FULL);
+    table.set_header(vec![
+        localization.get(LocalizationKey::HistoryId),
+        localization.get(LocalizationKey::HistoryMethod),
+        localization.get(LocalizationKey::HistoryBackend),
+        localization.get(LocalizationKey::HistoryPoints),
+        "HPET",
+        localization.get(LocalizationKey::HistoryOptimal),
+        "TOPSIS",
+    ]);
+    for entry in &entries {
+        let doc = &entry.document;
+        let mut optimal = format!("{:.4}", doc.optimal_resolution_ms);
+        if let Some(significance) = doc.significance.as_ref().filter(|s| s.is_tie()) {
+            optimal.push_str(&localization.get_history_tie(significance.equivalent_set_ms.len()));
+        }
+        if doc.partial {
+            optimal.push_str(localization.get(LocalizationKey::HistoryPartial));
+        }
+        table.add_row(vec![
+            entry.id.clone(),
+            format!("{:?}", doc.run.method),
+            doc.run.backend.clone(),
+            doc.measurements.len().to_string(),
+            doc.run.hpet_status.clone().unwrap_or_else(|| "-".to_string()),
+            optimal,
+            format!("{:.4}", doc.topsis_score),
+        ]);
+    }
+    println!("{}", table);
+    println!("{}", localization.get_history_count(entries.len(), &dir.display().to_string()));
+    Ok(())
+}
+
+/// Line up two runs per resolution and report significant changes in P95, P99 and MAD
+pub fn compare_runs(a: &HistoryEntry, b: &HistoryEntry, localization: &Localization) -> io::Result<()> {
+    println!("{}", localization.get(LocalizationKey::CompareTitle));
+    println!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
+    for (label, entry) in [("A", a), ("B", b)] {
+        println!("{}", localization.get_compare_run(label, &entry.id, &entry.document));
+    }
+    if a.document.system.fingerprint != b.document.system.fingerprint {
+        println!("{}", localization.get_compare_different_systems(
+            &a.document.system.fingerprint, &b.document.system.fingerprint));
+    }
+    println!();
+
+    let grid = |doc: &ResultDocument| -> BTreeMap<i64, TimerMeasurement> {
+        doc.measurements.iter()
+            .map(|m| ((m.resolution_ms * 10000.0).round() as i64, m.clone()))
+            .collect()
+    };
+    let (grid_a, grid_b) = (grid(&a.document), grid(&b.document));
+    let common: Vec<i64> = grid_a.keys().filter(|k| grid_b.contains_key(k)).copied().collect();
+    if common.is_empty() {
+        return Err(Error::new(ErrorKind::InvalidData, "The runs have no resolutions in common"));
+    }
+
+    let statistics = [Statistic::P95, Statistic::P99, Statistic::Mad];
+    let mut table = Table::new();
+    table.load_preset(UTF8_FULL);
+    let mut header = vec![localization.get(LocalizationKey::CompareResolution).to_string()];
+    for statistic in &statistics {
+        header.push(format!("{} A", statistic.name()));
+        header.push(format!("{} B", statistic.name()));
+        header.push(format!("Δ{}", statistic.name()));
+    }
+    table.set_header(header);
+
+    let (mut improved, mut regressed) = (0, 0);
+    for key in &common {
+        let (ma, mb) = (&grid_a[key], &grid_b[key]);
+        let mut row = vec![format!("{:.4}", ma.resolution_ms)];
+        for statistic in statistics {
+            let (value_a, value_b) = match statistic {
+                Statistic::P95 => (ma.statistics.p95, mb.statistics.p95),
+                Statistic::P99 => (ma.statistics.p99, mb.statistics.p99),
+                Statistic::Mad => (ma.statistics.mad, mb.statistics.mad),
+                Statistic::Median => (ma.statistics.median, mb.statistics.median),
+            };
+            let test = bootstrap_difference(&ma.raw_samples, &mb.raw_samples, statistic, ma.statistics.quantile_estimator, DEFAULT_RESAMPLES);
+            let marker = if !test.significant {
+                ""
+            } else if test.difference < 0.0 {
+                improved += 1;
+                " ▼*"
+            } else {
+                regressed += 1;
+                " ▲*"
+            };
+            row.push(format!("{:.4}", value_a));
+            row.push(format!("{:.4}", value_b));
+            row.push(format!("{:+.4}{}", value_b - value_a, marker));
+        }
+        table.add_row(row);
+    }
+    println!("{}", table);
+    println!("{}", localization.get(LocalizationKey::CompareLegend));
+    println!("{}", localization.get_compare_summary(common.len(), improved, regressed));
+    println!("{}", localization.get_compare_optimal(a.document.optimal_resolution_ms, b.document.optimal_resolution_ms));
+    Ok(())
+}
+
+/// `YYYYMMDD-HHMMSS` in UTC
+fn format_utc(unix_ms: u64) -> String {
+    let secs = unix_ms / 1000;
+    let (days, rem) = (secs / 86_400, secs % 86_400);
+    // Civil date from days since 1970-01-01 (Howard Hinnant's algorithm)
+    let z = days as i64 + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + i64::from(month <= 2);
+    format!("{:04}{:02}{:02}-{:02}{:02}{:02}", year, month, day, rem / 3600, rem % 3600 / 60, rem % 60)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::OptimizationResult;
+    use crate::report::{scratch_dir, test_run};
+    use crate::ui::language::Language;
+
+    #[test]
+    fn format_utc_dates() {
+        assert_eq!(format_utc(0), "19700101-000000");
+        assert_eq!(format_utc(1_709_210_096_000), "20240229-123456");
+        // 2100 is not a leap year
+        assert_eq!(format_utc(4_107_542_399_999), "21000228-235959");
+        assert_eq!(format_utc(4_107_542_400_000), "21000301-000000");
+    }
+
+    /// Run started `started_at_secs` after the epoch, measured at `resolutions_ms`
+    fn document(started_at_secs: u64, resolutions_ms: &[f64]) -> ResultDocument {
+        let result = OptimizationResult::ranked(resolutions_ms.iter()
+            .map(|&resolution_ms| {
+                let centre = 0.02 + (resolution_ms - 0.5).abs();
+                TimerMeasurement::from_samples(resolution_ms, (0..50).map(|i| centre + i as f64 * 0.0002).collect())
+            })
+            .collect());
+        let (parameters, mut run) = test_run();
+        run.started_at_unix_ms = started_at_secs * 1000;
+        let mut document = ResultDocument::new(&result, &parameters, &run);
+        document.system.fingerprint = "0123456789ab".to_string();
+        document
+    }
+
+    /// History directory with runs started at 00:00:10, 00:00:20 and 00:10:00
+    fn history(test: &str) -> PathBuf {
+        let dir = scratch_dir(test);
+        for started_at_secs in [20, 600, 10] {
+            save_to_history(&document(started_at_secs, &[0.5, 0.501]), &dir).unwrap();
+        }
+        dir
+    }
+
+    #[test]
+    fn runs_resolve_by_alias_and_prefix() {
+        let dir = history("history-resolve");
+        let id = |reference| resolve_run(&dir, reference).unwrap().id;
+        assert_eq!(id("latest"), "19700101-001000-0123456789ab");
+        assert_eq!(id("previous"), "19700101-000020-0123456789ab");
+        assert_eq!(id("19700101-0010"), "19700101-001000-0123456789ab");
+
+        let error = resolve_run(&dir, "19700101-0000").unwrap_err();
+        assert_eq!(error.kind(), ErrorKind::InvalidInput);
+        assert!(error.to_string().contains("matches 2 runs"), "{}", error);
+        assert_eq!(resolve_run(&dir, "2024").unwrap_err().kind(), ErrorKind::NotFound);
+
+        let path = dir.join("19700101-000010-0123456789ab.json");
+        assert_eq!(resolve_run(&dir, path.to_str().unwrap()).unwrap().document.run.started_at_unix_ms, 10_000);
+    }
+
+    #[test]
+    fn previous_needs_two_runs() {
+        let dir = scratch_dir("history-previous");
+        save_to_history(&document(10, &[0.5]), &dir).unwrap();
+        assert!(resolve_run(&dir, "latest").is_ok());
+        assert_eq!(resolve_run(&dir, "previous").unwrap_err().kind(), ErrorKind::NotFound);
+    }
+
+    #[test]
+    fn runs_at_the_same_second_get_distinct_ids() {
+        let dir = scratch_dir("history-suffix");
+        let first = save_to_history(&document(10, &[0.5]), &dir).unwrap();
+        let second = save_to_history(&document(10, &[0.5]), &dir).unwrap();
+        assert_eq!(second, format!("{}-2", first));
+    }
+
+    #[test]
+    fn compare_needs_common_resolutions() {
+        let localization = Localization::new(Language::English);
+        let entry = |id: &str, document| HistoryEntry { id: id.to_string(), path: PathBuf::new(), document };
+        let a = entry("a", document(10, &[0.5, 0.501, 0.502]));
+        let b = entry("b", document(20, &[0.501, 0.502, 0.503]));
+        compare_runs(&a, &b, &localization).unwrap();
+
+        let c = entry("c", document(30, &[0.6, 0.601]));
+        assert_eq!(compare_runs(&a, &c, &localization).unwrap_err().kind(), ErrorKind::InvalidData);
+    }
+}
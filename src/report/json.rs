@@ -0,0 +1,299 @@
+//! Versioned JSON result document (results.json)
+//!
+//! Contains everything results.txt has plus the parameters, raw samples,
+//! system information and timing, so other tools can consume a run without
+//! parsing text.
+
+use std::io;
+use std::path::Path;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::backend::{SleepProbe, TimerBackend};
+use crate::core::{BenchmarkingParameters, FailedPoint, OptimizationMethod, OptimizationResult};
+use crate::optimization::criteria::{Criterion, RankingCriteria};
+use crate::optimization::equivalence::WinnerSignificance;
+use crate::optimization::gaussian_process::SurrogateCurve;
+use crate::optimization::rankers::RankerComparison;
+use crate::optimization::sensitivity::WeightSensitivity;
+use crate::optimization::topsis::{CriterionValue, TopsisScore};
+use crate::stats::timer_measurement::TimerMeasurement;
+
+use super::write_atomic;
+
+/// Bumped whenever a field is renamed, removed or changes meaning
+///
+/// Version 2 replaced the fixed `criteria_scores` of each ranking entry with
+/// `criterion_values`, one per ranking criterion; version 1 documents are
+/// upgraded on load.
+pub const SCHEMA_VERSION: u32 = 2;
+
+/// Complete record of one benchmark run
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ResultDocument {
+    pub schema_version: u32,
+    pub tool_version: String,
+    pub run: RunMetadata,
+    pub system: SystemInfo,
+    pub parameters: BenchmarkingParameters,
+    pub optimal_resolution_ms: f64,
+    pub topsis_score: f64,
+    /// Criteria and normalised weights of the ranking (balanced preset in older documents)
+    #[serde(default)]
+    pub criteria: RankingCriteria,
+    #[serde(default)]
+    pub partial: bool,  // Run was cancelled before the search finished
+    /// Winner vs runners-up significance (absent in documents from older versions)
+    #[serde(default)]
+    pub significance: Option<WinnerSignificance>,
+    /// Winners of VIKOR and the weighted sum next to TOPSIS, and the Pareto front
+    #[serde(default)]
+    pub rankers: Option<RankerComparison>,
+    /// Win rates under random weights and per-criterion weight ranges (when enabled)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sensitivity: Option<WeightSensitivity>,
+    /// Posterior of the GP surrogate of the hybrid and refine searches (performance score vs resolution)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub surrogate: Option<SurrogateCurve>,
+    /// Points skipped because their samples were unusable
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub failed_points: Vec<FailedPoint>,
+    pub ranking: Vec<TopsisScore>,
+    pub measurements: Vec<TimerMeasurement>,
+}
+
+/// How and when the run was made
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RunMetadata {
+    pub method: OptimizationMethod,
+    pub backend: String,
+    pub probe: String,
+    pub started_at_unix_ms: u64,
+    pub finished_at_unix_ms: u64,
+    pub duration_secs: f64,
+    pub hpet_status: Option<String>,
+}
+
+impl RunMetadata {
+    /// Metadata for a run that started at `started_at` and finished now
+    pub fn new(
+        method: OptimizationMethod,
+        backend: &dyn TimerBackend,
+        probe: &dyn SleepProbe,
+        started_at: SystemTime,
+        hpet_status: Option<String>,
+    ) -> Self {
+        let finished_at = SystemTime::now();
+        Self {
+            method,
+            backend: backend.name().to_string(),
+            probe: probe.name().to_string(),
+            started_at_unix_ms: unix_ms(started_at),
+            finished_at_unix_ms: unix_ms(finished_at),
+            duration_secs: finished_at.duration_since(started_at).unwrap_or_default().as_secs_f64(),
+            hpet_status,
+        }
+    }
+}
+
+/// Machine the run was made on
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SystemInfo {
+    pub os: String,
+    pub os_version: String,
+    pub cpu: Option<String>,
+    pub logical_cores: Option<usize>,
+    #[serde(default)]
+    pub host_name: Option<String>,
+    /// Stable machine identifier derived from OS, CPU, core count and host name
+    #[serde(default)]
+    pub fingerprint: String,
+}
+
+impl SystemInfo {
+    pub fn collect() -> Self {
+        let os_info = os_info::get();
+        let cpu = raw_cpuid::CpuId::new()
+            .get_processor_brand_string()
+            .map(|brand| brand.as_str().trim().to_string());
+        let mut info = Self {
+            os: os_info.os_type().to_string(),
+            os_version: os_info.version().to_string(),
+            cpu,
+            logical_cores: std::thread::available_parallelism().ok().map(|n| n.get()),
+            host_name: sysinfo::System::host_name(),
+            fingerprint: String::new(),
+        };
+        info.fingerprint = info.compute_fingerprint();
+        info
+    }
+
+    /// FNV-1a hash of the fields that identify the machine (not the OS version,
+    /// so runs before and after an update share a fingerprint)
+    fn compute_fingerprint(&self) -> String {
+        let key = format!(
+            "{}|{}|{}|{}",
+            self.os,
+            self.cpu.as_deref().unwrap_or(""),
+            self.logical_cores.unwrap_or(0),
+            self.host_name.as_deref().unwrap_or(""),
+        );
+        let hash = key.bytes().fold(0xcbf2_9ce4_8422_2325_u64, |hash, byte| {
+            (hash ^ byte as u64).wrapping_mul(0x0000_0100_0000_01b3)
+        });
+        format!("{:012x}", hash >> 16)
+    }
+}
+
+impl ResultDocument {
+    pub fn new(result: &OptimizationResult, parameters: &BenchmarkingParameters, run: &RunMetadata) -> Self {
+        Self {
+            schema_version: SCHEMA_VERSION,
+            tool_version: crate::VERSION.to_string(),
+            run: run.clone(),
+            system: SystemInfo::collect(),
+            parameters: parameters.clone(),
+            optimal_resolution_ms: result.optimal_resolution,
+            topsis_score: result.topsis_score,
+            criteria: result.criteria.clone(),
+            partial: result.partial,
+            significance: result.significance.clone(),
+            rankers: result.rankers.clone(),
+            sensitivity: result.sensitivity.clone(),
+            surrogate: result.surrogate.clone(),
+            failed_points: result.failed_points.clone(),
+            ranking: result.topsis_rankings.clone(),
+            measurements: result.aggregated_measurements.clone(),
+        }
+    }
+}
+
+/// Write the JSON result document, replacing `path` atomically
+pub fn save_json_results(
+    result: &OptimizationResult,
+    parameters: &BenchmarkingParameters,
+    run: &RunMetadata,
+    path: impl AsRef<Path>,
+) -> io::Result<()> {
+    let document = ResultDocument::new(result, parameters, run);
+    let json = serde_json::to_vec_pretty(&document)?;
+    write_atomic(path.as_ref(), &json)
+}
+
+/// Read a JSON result document
+pub fn load_json_results(path: impl AsRef<Path>) -> io::Result<ResultDocument> {
+    let content = std::fs::read(path)?;
+    let mut document: Value = serde_json::from_slice(&content)?;
+    let schema_version = document.get("schema_version").and_then(Value::as_u64).unwrap_or(0);
+    if schema_version > SCHEMA_VERSION as u64 {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("unsupported result schema version {} (expected at most {})", schema_version, SCHEMA_VERSION),
+        ));
+    }
+    if schema_version < 2 {
+        upgrade_criteria_scores(&mut document);
+    }
+    Ok(serde_json::from_value(document)?)
+}
+
+/// Rewrite the version 1 `criteria_scores` of each ranking entry as `criterion_values`
+///
+/// Version 1 always ranked on P95, MAD, P99 and the CI width, in that order.
+fn upgrade_criteria_scores(document: &mut Value) {
+    const FIELDS: [(&str, Criterion); 4] = [
+        ("p95_delta", Criterion::P95),
+        ("mad", Criterion::Mad),
+        ("p99_delta", Criterion::P99),
+        ("confidence_width", Criterion::CiWidth),
+    ];
+    let Some(ranking) = document.get_mut("ranking").and_then(Value::as_array_mut) else { return };
+    for entry in ranking.iter_mut().filter_map(Value::as_object_mut) {
+        let Some(scores) = entry.remove("criteria_scores") else { continue };
+        let values: Vec<Value> = FIELDS.iter()
+            .filter_map(|&(field, criterion)| {
+                let value = scores.get(field)?.as_f64()?;
+                serde_json::to_value(CriterionValue { criterion, value }).ok()
+            })
+            .collect();
+        entry.insert("criterion_values".to_string(), Value::Array(values));
+    }
+}
+
+fn unix_ms(time: SystemTime) -> u64 {
+    time.duration_since(UNIX_EPOCH).map(|d| d.as_millis() as u64).unwrap_or(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+    use crate::report::{scratch_dir, test_run};
+
+    fn measurement(resolution_ms: f64, center: f64, width: f64) -> TimerMeasurement {
+        let samples = (0..101).map(|i| center - width / 2.0 + width * i as f64 / 100.0).collect();
+        TimerMeasurement::from_samples(resolution_ms, samples)
+    }
+
+    fn write_document(test: &str) -> (std::path::PathBuf, OptimizationResult) {
+        let result = OptimizationResult::ranked(vec![
+            measurement(0.5, 0.02, 0.01),
+            measurement(0.5010, 0.01, 0.004),
+            measurement(0.5020, 0.03, 0.02),
+        ]);
+        let (parameters, run) = test_run();
+        let path = scratch_dir(test).join("results.json");
+        save_json_results(&result, &parameters, &run, &path).unwrap();
+        (path, result)
+    }
+
+    #[test]
+    fn document_round_trips() {
+        let (path, result) = write_document("json-round-trip");
+        let document = load_json_results(&path).unwrap();
+        assert_eq!(document.schema_version, SCHEMA_VERSION);
+        assert_eq!(document.optimal_resolution_ms, result.optimal_resolution);
+        assert_eq!(document.measurements.len(), 3);
+        for (loaded, saved) in document.ranking.iter().zip(&result.topsis_rankings) {
+            assert_eq!(loaded.resolution_ms, saved.resolution_ms);
+            assert_eq!(loaded.rank, saved.rank);
+            assert_eq!(loaded.criterion_values, saved.criterion_values);
+        }
+    }
+
+    #[test]
+    fn version_1_criteria_scores_are_upgraded() {
+        let (path, _) = write_document("json-version-1");
+        let mut document: Value = serde_json::from_slice(&std::fs::read(&path).unwrap()).unwrap();
+        document["schema_version"] = json!(1);
+        for entry in document["ranking"].as_array_mut().unwrap() {
+            let entry = entry.as_object_mut().unwrap();
+            entry.remove("criterion_values");
+            entry.insert("criteria_scores".to_string(), json!({
+                "p95_delta": 0.02, "mad": 0.003, "p99_delta": 0.025, "confidence_width": 0.001,
+            }));
+        }
+        std::fs::write(&path, serde_json::to_vec(&document).unwrap()).unwrap();
+
+        let loaded = load_json_results(&path).unwrap();
+        let values: Vec<(Criterion, f64)> = loaded.ranking[0].criterion_values.iter()
+            .map(|v| (v.criterion, v.value))
+            .collect();
+        assert_eq!(values, vec![
+            (Criterion::P95, 0.02), (Criterion::Mad, 0.003), (Criterion::P99, 0.025), (Criterion::CiWidth, 0.001),
+        ]);
+    }
+
+    #[test]
+    fn newer_schema_is_rejected() {
+        let (path, _) = write_document("json-newer");
+        let mut document: Value = serde_json::from_slice(&std::fs::read(&path).unwrap()).unwrap();
+        document["schema_version"] = json!(SCHEMA_VERSION + 1);
+        std::fs::write(&path, serde_json::to_vec(&document).unwrap()).unwrap();
+        let error = load_json_results(&path).unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::InvalidData);
+    }
+}
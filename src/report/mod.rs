@@ -0,0 +1,76 @@
+//! Result files written after a benchmark and read back by the `report` command
+
+pub mod charts;
+pub mod csv;
+pub mod history;
+pub mod json;
+pub mod text;
+
+use std::fs::{self, File};
+use std::io::{self, Write};
+use std::path::Path;
+
+pub use charts::{render_charts, ChartFormat};
+pub use self::csv::{load_measurements_csv, load_summary_csv, save_csv_results, CsvMetadata, SampleRow};
+pub use history::{compare_runs, list_history, print_history, resolve_run, save_to_history, HistoryEntry};
+pub use json::{load_json_results, save_json_results, ResultDocument, RunMetadata, SystemInfo};
+pub use text::{load_results, print_report, print_top_ranking, result_rows, save_detailed_results, ResultRow, SavedResults};
+
+/// Result file formats written in addition to results.txt
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, clap::ValueEnum)]
+pub enum OutputFormat {
+    #[default]
+    Text,   // results.txt only
+    Json,   // results.txt and results.json
+    Csv,    // results.txt, results.csv, results_samples.csv and results.meta.json
+}
+
+/// Write `contents` to a temporary file next to `path`, then rename it over `path`
+///
+/// Readers never see a half-written file, and an interrupted write leaves the
+/// previous file intact.
+pub(crate) fn write_atomic(path: &Path, contents: &[u8]) -> io::Result<()> {
+    let file_name = path.file_name().ok_or_else(|| {
+        io::Error::new(io::ErrorKind::InvalidInput, format!("{} is not a file path", path.display()))
+    })?;
+    let mut tmp_name = file_name.to_os_string();
+    tmp_name.push(format!(".{}.tmp", std::process::id()));
+    let tmp_path = path.with_file_name(tmp_name);
+
+    let write = || -> io::Result<()> {
+        let mut file = File::create(&tmp_path)?;
+        file.write_all(contents)?;
+        file.sync_all()?;
+        fs::rename(&tmp_path, path)
+    };
+    write().inspect_err(|_| {
+        let _ = fs::remove_file(&tmp_path);
+    })
+}
+
+/// Empty directory for the files written by one test
+#[cfg(test)]
+pub(crate) fn scratch_dir(test: &str) -> std::path::PathBuf {
+    let dir = std::env::temp_dir().join(format!("timer_res_benchmark-{}-{}", test, std::process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+/// Parameters and metadata of a short simulated linear run, for result files written by tests
+#[cfg(test)]
+pub(crate) fn test_run() -> (crate::core::BenchmarkingParameters, RunMetadata) {
+    let parameters = serde_json::from_value(serde_json::json!({
+        "StartValue": 0.5, "IncrementValue": 0.001, "EndValue": 0.502, "SampleValue": 101,
+    })).unwrap();
+    let run = RunMetadata {
+        method: crate::core::OptimizationMethod::Linear,
+        backend: "simulated".to_string(),
+        probe: "simulated".to_string(),
+        started_at_unix_ms: 0,
+        finished_at_unix_ms: 1000,
+        duration_secs: 1.0,
+        hpet_status: None,
+    };
+    (parameters, run)
+}
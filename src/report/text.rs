@@ -0,0 +1,366 @@
+//! Plain-text results file (results.txt)
+//!
+//! `#` lines are comments, the rest is a comma-separated table with one row per
+//! tested resolution in TOPSIS rank order.
+
+use std::fs::{self, File};
+use std::io::{self, BufWriter, Error, ErrorKind, Write};
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+use crate::core::OptimizationResult;
+use crate::optimization::criteria::{Criterion, RankingCriteria};
+use crate::optimization::equivalence::format_resolutions;
+use crate::optimization::topsis::{CriterionValue, TopsisScore};
+use crate::stats::drift::suggested_settle_ms;
+use crate::ui::localization::{Localization, LocalizationKey};
+
+const PARTIAL_MARKER: &str = "# PARTIAL RESULTS: run was cancelled before the search finished";
+const HEADER: &str = "Resolution_ms,P50_Delta,P95_Delta,P99_Delta,Mean_Delta,StdDev,MAD,Outliers_Removed,CI_Lower,CI_Upper,TOPSIS_Score,Rank";
+
+/// Results shown in the console ranking
+const TOP_RANKED: usize = 5;
+
+/// One row of the results table
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ResultRow {
+    pub resolution_ms: f64,
+    #[serde(rename = "p50_delta_ms")]
+    pub median: f64,
+    #[serde(rename = "p95_delta_ms")]
+    pub p95: f64,
+    #[serde(rename = "p99_delta_ms")]
+    pub p99: f64,
+    #[serde(rename = "mean_delta_ms")]
+    pub mean: f64,
+    #[serde(rename = "stdev_ms")]
+    pub stdev: f64,
+    #[serde(rename = "mad_ms")]
+    pub mad: f64,
+    pub outliers_removed: usize,
+    #[serde(rename = "ci95_lower_ms")]
+    pub ci_lower: f64,
+    #[serde(rename = "ci95_upper_ms")]
+    pub ci_upper: f64,
+    pub topsis_score: f64,
+    pub rank: usize,
+}
+
+impl ResultRow {
+    /// Value of a ranking criterion, `None` for criteria the table has no column for
+    pub fn criterion_value(&self, criterion: Criterion) -> Option<f64> {
+        match criterion {
+            Criterion::P50 => Some(self.median),
+            Criterion::P95 => Some(self.p95),
+            Criterion::P99 => Some(self.p99),
+            Criterion::Mean => Some(self.mean),
+            Criterion::Stdev => Some(self.stdev),
+            Criterion::Mad => Some(self.mad),
+            Criterion::CiWidth => Some(self.ci_upper - self.ci_lower),
+            Criterion::Outliers => Some(self.outliers_removed as f64),
+            Criterion::Bimodality => None,
+        }
+    }
+
+    /// TOPSIS result this row was written from
+    fn topsis_score(&self, criteria: &RankingCriteria) -> TopsisScore {
+        TopsisScore {
+            resolution_ms: self.resolution_ms,
+            closeness_coefficient: self.topsis_score,
+            rank: self.rank,
+            criterion_values: criteria.criteria.iter()
+                .filter_map(|c| Some(CriterionValue { criterion: c.criterion, value: self.criterion_value(c.criterion)? }))
+                .collect(),
+        }
+    }
+}
+
+/// Contents of a results file
+#[derive(Debug, Clone)]
+pub struct SavedResults {
+    pub rows: Vec<ResultRow>,
+    pub optimal_resolution: Option<f64>,
+    pub topsis_score: Option<f64>,
+    pub partial: bool,
+    /// Resolutions statistically equivalent to the optimum (empty unless tied)
+    pub equivalent_set: Vec<f64>,
+    /// Criteria the ranking used; `None` for files written before they were recorded
+    pub criteria: Option<RankingCriteria>,
+}
+
+/// Results table rows in TOPSIS rank order
+pub fn result_rows(result: &OptimizationResult) -> io::Result<Vec<ResultRow>> {
+    result.topsis_rankings.iter().map(|topsis| {
+        // ИСПОЛЬЗУЕМ aggregated_measurements! и сравнение с tolerance для float
+        let m = result.aggregated_measurements.iter()
+            .find(|m| (m.resolution_ms - topsis.resolution_ms).abs() < 0.0001)
+            .ok_or_else(|| {
+                Error::new(
+                    ErrorKind::NotFound, 
+                    format!("Measurement not found for resolution {:.4} ms", topsis.resolution_ms)
+                )
+            })?;
+        Ok(ResultRow {
+            resolution_ms: m.resolution_ms,
+            median: m.statistics.median,
+            p95: m.statistics.p95,
+            p99: m.statistics.p99,
+            mean: m.statistics.mean,
+            stdev: m.statistics.stdev,
+            mad: m.statistics.mad,
+            outliers_removed: m.statistics.outliers_removed,
+            ci_lower: m.statistics.confidence_interval_95.0,
+            ci_upper: m.statistics.confidence_interval_95.1,
+            topsis_score: topsis.closeness_coefficient,
+            rank: topsis.rank,
+        })
+    }).collect()
+}
+
+pub fn save_detailed_results(result: &OptimizationResult, filename: impl AsRef<Path>) -> io::Result<()> {
+    let file = File::create(filename)?;
+    let mut writer = BufWriter::new(file);
+    writeln!(writer, "# Timer Resolution Optimization Results")?;
+    writeln!(writer, "# Generated: {:?}", std::time::SystemTime::now())?;
+    if result.partial {
+        writeln!(writer, "{}", PARTIAL_MARKER)?;
+    }
+    writeln!(writer)?;
+    writeln!(writer, "{}", HEADER)?;
+    for row in result_rows(result)? {
+        writeln!(
+            writer,
+            "{:.4},{:.4},{:.4},{:.4},{:.4},{:.4},{:.4},{},{:.4},{:.4},{:.4},{}",
+            row.resolution_ms,
+            row.median,
+            row.p95,
+            row.p99,
+            row.mean,
+            row.stdev,
+            row.mad,
+            row.outliers_removed,
+            row.ci_lower,
+            row.ci_upper,
+            row.topsis_score,
+            row.rank,
+        )?;
+    }
+    writeln!(writer)?;
+    writeln!(writer, "# Optimal Resolution: {:.4} ms", result.optimal_resolution)?;
+    writeln!(writer, "# TOPSIS Score: {:.4}", result.topsis_score)?;
+    writeln!(writer, "# Ranking Criteria: {}", result.criteria)?;
+    if let Some(statistics) = result.aggregated_measurements.first().map(|m| &m.statistics) {
+        writeln!(writer, "# Estimators: quantiles {}, mean CI {}, quantile CIs {}",
+            statistics.quantile_estimator.name(),
+            statistics.mean_interval.name(),
+            statistics.quantile_intervals.as_ref()
+                .map_or("none".to_string(), |q| format!("bootstrap ({} resamples)", q.resamples)))?;
+    }
+    if let Some(intervals) = result.aggregated_measurements.iter()
+        .find(|m| (m.resolution_ms - result.optimal_resolution).abs() < 0.0001)
+        .and_then(|m| m.statistics.quantile_intervals.as_ref())
+    {
+        writeln!(writer, "# Optimal Quantile CIs: P50 [{:.4}, {:.4}], P95 [{:.4}, {:.4}], P99 [{:.4}, {:.4}] ms",
+            intervals.median.0, intervals.median.1, intervals.p95.0, intervals.p95.1, intervals.p99.0, intervals.p99.1)?;
+    }
+    if let Some(significance) = &result.significance {
+        if significance.is_tie() {
+            writeln!(writer, "# Significance: tie")?;
+            writeln!(writer, "# Equivalent Set: {} ms", format_resolutions(&significance.equivalent_set_ms))?;
+        } else if significance.is_clear_win() {
+            writeln!(writer, "# Significance: winner significantly better than the next {} candidates", significance.comparisons.len())?;
+        } else if !significance.comparisons.is_empty() {
+            writeln!(writer, "# Significance: contested")?;
+        }
+        for comparison in significance.better_runners_up() {
+            writeln!(writer, "# Runner-up Better: {:.4} ms on {}", comparison.resolution_ms, comparison.runner_up_better_on().join(", "))?;
+        }
+    }
+    for failed in &result.failed_points {
+        writeln!(writer, "# Failed Point: {:.4} ms ({})", failed.resolution_ms, failed.reason)?;
+    }
+    if let Some(rankers) = &result.rankers {
+        let winners: Vec<String> = rankers.winners.iter()
+            .map(|w| format!("{}={:.4}", w.ranker, w.resolution_ms))
+            .collect();
+        writeln!(writer, "# Rankers: {} ({})", winners.join(", "), if rankers.agree() { "agree" } else { "disagree" })?;
+        writeln!(writer, "# Pareto Front: {} ms", format_resolutions(&rankers.pareto_front_ms))?;
+    }
+    if let Some(sensitivity) = &result.sensitivity {
+        writeln!(writer, "# Weight Sensitivity: {:.4} ms wins {:.1}% of {} random weightings ({})",
+            sensitivity.winner_ms,
+            sensitivity.winner_rate() * 100.0,
+            sensitivity.samples,
+            if sensitivity.is_robust() { "robust" } else { "fragile" })?;
+        let rates: Vec<String> = sensitivity.win_rates.iter()
+            .map(|r| format!("{:.4}={:.3}", r.resolution_ms, r.fraction))
+            .collect();
+        writeln!(writer, "# Win Rates: {}", rates.join(", "))?;
+        for range in &sensitivity.weight_ranges {
+            writeln!(writer, "# Weight Range {}: {:.2}..{:.2} (used {:.2})", range.criterion, range.lower, range.upper, range.weight)?;
+        }
+    }
+    for topsis in &result.topsis_rankings {
+        let Some(m) = result.aggregated_measurements.iter()
+            .find(|m| (m.resolution_ms - topsis.resolution_ms).abs() < 0.0001)
+        else {
+            continue;
+        };
+        let shape = &m.statistics.shape;
+        writeln!(writer, "# Shape {:.4} ms: skewness {:.3}, excess kurtosis {:.3}, bimodality {:.3}{}, P99.9 {:.4}, max {:.4} ms",
+            m.resolution_ms, shape.skewness, shape.excess_kurtosis, shape.bimodality_coefficient,
+            if shape.is_bimodal() { " (bimodal)" } else { "" }, shape.p999, shape.max)?;
+    }
+    let dropped: usize = result.aggregated_measurements.iter().map(|m| m.stationarity.warmup_samples).sum();
+    if dropped > 0 {
+        writeln!(writer, "# Warm-up: {} samples dropped, suggested settle time {}",
+            dropped,
+            suggested_settle_ms(result.aggregated_measurements.iter().map(|m| &m.stationarity))
+                .map_or("unknown".to_string(), |ms| format!("{} ms", ms)))?;
+    }
+    for topsis in &result.topsis_rankings {
+        let Some(stationarity) = result.aggregated_measurements.iter()
+            .find(|m| (m.resolution_ms - topsis.resolution_ms).abs() < 0.0001)
+            .map(|m| &m.stationarity)
+            .filter(|s| s.warmup_samples > 0 || s.drifting_runs > 0)
+        else {
+            continue;
+        };
+        writeln!(writer, "# Warm-up {:.4} ms: {} samples dropped{}, drift in {} of {} runs",
+            topsis.resolution_ms,
+            stationarity.warmup_samples,
+            stationarity.warmup_ms.map_or(String::new(), |ms| format!(" ({:.0} ms)", ms)),
+            stationarity.drifting_runs,
+            stationarity.runs)?;
+    }
+    if let Some(histogram) = result.aggregated_measurements.iter()
+        .find(|m| (m.resolution_ms - result.optimal_resolution).abs() < 0.0001)
+        .and_then(|m| m.histogram.as_ref())
+    {
+        writeln!(writer, "# Optimal Histogram:")?;
+        for line in histogram.render(40) {
+            writeln!(writer, "#   {}", line)?;
+        }
+    }
+    Ok(())
+}
+
+/// Read a results file written by `save_detailed_results`
+pub fn load_results(path: impl AsRef<Path>) -> io::Result<SavedResults> {
+    let content = fs::read_to_string(path)?;
+    let mut rows = Vec::new();
+    let mut optimal_resolution = None;
+    let mut topsis_score = None;
+    let mut partial = false;
+    let mut equivalent_set = Vec::new();
+    let mut criteria = None;
+
+    for (line_no, line) in content.lines().enumerate() {
+        let line = line.trim();
+        if line == PARTIAL_MARKER {
+            partial = true;
+            continue;
+        }
+        if let Some(comment) = line.strip_prefix('#') {
+            let comment = comment.trim();
+            if let Some(value) = comment.strip_prefix("Optimal Resolution:") {
+                optimal_resolution = value.trim().trim_end_matches("ms").trim().parse().ok();
+            } else if let Some(value) = comment.strip_prefix("TOPSIS Score:") {
+                topsis_score = value.trim().parse().ok();
+            } else if let Some(value) = comment.strip_prefix("Equivalent Set:") {
+                equivalent_set = value.trim().trim_end_matches("ms")
+                    .split(',')
+                    .filter_map(|v| v.trim().parse().ok())
+                    .collect();
+            } else if let Some(value) = comment.strip_prefix("Ranking Criteria:") {
+                criteria = value.trim().parse().ok();
+            }
+            continue;
+        }
+        if line.is_empty() || line == HEADER {
+            continue;
+        }
+        rows.push(parse_row(line).map_err(|e| Error::new(
+            ErrorKind::InvalidData,
+            format!("line {}: {}", line_no + 1, e),
+        ))?);
+    }
+
+    Ok(SavedResults { rows, optimal_resolution, topsis_score, partial, equivalent_set, criteria })
+}
+
+fn parse_row(line: &str) -> Result<ResultRow, String> {
+    let fields: Vec<&str> = line.split(',').map(str::trim).collect();
+    if fields.len() != 12 {
+        return Err(format!("expected 12 columns, found {}", fields.len()));
+    }
+    let float = |i: usize| fields[i].parse::<f64>().map_err(|e| format!("column {}: {}", i + 1, e));
+    let int = |i: usize| fields[i].parse::<usize>().map_err(|e| format!("column {}: {}", i + 1, e));
+    Ok(ResultRow {
+        resolution_ms: float(0)?,
+        median: float(1)?,
+        p95: float(2)?,
+        p99: float(3)?,
+        mean: float(4)?,
+        stdev: float(5)?,
+        mad: float(6)?,
+        outliers_removed: int(7)?,
+        ci_lower: float(8)?,
+        ci_upper: float(9)?,
+        topsis_score: float(10)?,
+        rank: int(11)?,
+    })
+}
+
+/// Print the best `TOP_RANKED` results of a ranking, with the criteria it used when known
+pub fn print_top_ranking(rankings: &[TopsisScore], criteria: Option<&RankingCriteria>, localization: &Localization) {
+    println!("\n{}", localization.get(LocalizationKey::TopsisRanking));
+    println!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━\n");
+    if let Some(criteria) = criteria {
+        println!("⚖️  {}\n", criteria);
+    }
+    for (i, result) in rankings.iter().take(TOP_RANKED).enumerate() {
+        let marker = match i {
+            0 => "🥇",
+            1 => "🥈",
+            2 => "🥉",
+            _ => "  ",
+        };
+        println!("{}  {}: {:.4} ms", marker, localization.get_rank(result.rank), result.resolution_ms);
+        println!("{}", localization.get_topsis_score(result.closeness_coefficient));
+        for score in &result.criterion_values {
+            println!("{}", localization.get_criterion_score(score.criterion, score.value));
+        }
+        println!();
+    }
+}
+
+/// Print the top of the ranking from a saved results file
+pub fn print_report(path: impl AsRef<Path>, localization: &Localization) -> io::Result<()> {
+    let path = path.as_ref();
+    let results = load_results(path)?;
+    if results.rows.is_empty() {
+        return Err(Error::new(ErrorKind::InvalidData, format!("{} contains no results", path.display())));
+    }
+
+    if results.partial {
+        println!("{}", localization.get(LocalizationKey::ReportPartial));
+    }
+    let mut rows = results.rows.clone();
+    rows.sort_by_key(|row| row.rank);
+    // Files written before the criteria were recorded were always ranked on the balanced preset
+    let criteria = results.criteria.clone().unwrap_or_default();
+    let rankings: Vec<TopsisScore> = rows.iter().map(|row| row.topsis_score(&criteria)).collect();
+    print_top_ranking(&rankings, results.criteria.as_ref(), localization);
+
+    if results.equivalent_set.len() > 1 {
+        println!("{}", localization.get_significance_tie(&format_resolutions(&results.equivalent_set)));
+        println!("{}\n", localization.get(LocalizationKey::SignificanceTieBreak));
+    }
+    let optimal = results.optimal_resolution.unwrap_or(rows[0].resolution_ms);
+    println!("{}", localization.get_optimal_value(optimal));
+    println!("   {}\n", localization.get_optimal_recommendation((optimal * 10_000.0).round() as i32));
+    println!("{}", localization.get_report_points_in_file(rows.len()));
+    Ok(())
+}
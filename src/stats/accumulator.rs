@@ -0,0 +1,288 @@
+//! Streaming sample accumulator
+//!
+//! Keeps what `RobustStatistics` needs without keeping the samples: count, mean
+//! and variance by Welford's method, and a mergeable quantile sketch with
+//! logarithmic buckets (DDSketch) whose quantiles are within `SKETCH_ACCURACY`
+//! relative error. Merging two accumulators costs time proportional to the
+//! number of buckets, however many samples they have seen.
+
+use std::collections::BTreeMap;
+
+use crate::stats::distribution::DistributionShape;
+
+/// Relative accuracy of sketch quantiles
+pub const SKETCH_ACCURACY: f64 = 0.005;
+
+/// Magnitudes below this count as zero
+const ZERO_THRESHOLD: f64 = 1e-9;
+
+/// Running count, mean, variance, extremes and quantile sketch of a sample stream
+#[derive(Debug, Clone)]
+pub struct SampleAccumulator {
+    count: u64,
+    mean: f64,
+    m2: f64,  // Sum of squared deviations from the mean
+    min: f64,
+    max: f64,
+    non_finite: u64,  // NaN or infinite values seen (not accumulated)
+    sketch: QuantileSketch,
+}
+
+impl Default for SampleAccumulator {
+    fn default() -> Self {
+        Self {
+            count: 0,
+            mean: 0.0,
+            m2: 0.0,
+            min: f64::INFINITY,
+            max: f64::NEG_INFINITY,
+            non_finite: 0,
+            sketch: QuantileSketch::default(),
+        }
+    }
+}
+
+impl SampleAccumulator {
+    pub fn from_samples(samples: &[f64]) -> Self {
+        let mut accumulator = Self::default();
+        for &x in samples {
+            accumulator.add(x);
+        }
+        accumulator
+    }
+
+    pub fn add(&mut self, x: f64) {
+        if !x.is_finite() {
+            self.non_finite += 1;
+            return;
+        }
+        self.count += 1;
+        let delta = x - self.mean;
+        self.mean += delta / self.count as f64;
+        self.m2 += delta * (x - self.mean);
+        self.min = self.min.min(x);
+        self.max = self.max.max(x);
+        self.sketch.add(x);
+    }
+
+    /// Combine with another accumulator (Chan et al. for the variance)
+    pub fn merge(&mut self, other: &Self) {
+        self.non_finite += other.non_finite;
+        if other.count == 0 {
+            return;
+        }
+        if self.count == 0 {
+            let non_finite = self.non_finite;
+            *self = other.clone();
+            self.non_finite = non_finite;
+            return;
+        }
+        let count = self.count + other.count;
+        let delta = other.mean - self.mean;
+        self.mean += delta * other.count as f64 / count as f64;
+        self.m2 += other.m2 + delta * delta * self.count as f64 * other.count as f64 / count as f64;
+        self.count = count;
+        self.min = self.min.min(other.min);
+        self.max = self.max.max(other.max);
+        self.sketch.merge(&other.sketch);
+    }
+
+    /// Finite samples accumulated
+    pub fn count(&self) -> u64 {
+        self.count
+    }
+
+    pub fn non_finite(&self) -> u64 {
+        self.non_finite
+    }
+
+    pub fn mean(&self) -> f64 {
+        self.mean
+    }
+
+    /// Population variance
+    pub fn variance(&self) -> f64 {
+        if self.count == 0 { 0.0 } else { self.m2 / self.count as f64 }
+    }
+
+    /// Sum of squares of the accumulated samples
+    pub fn sum_of_squares(&self) -> f64 {
+        self.m2 + self.count as f64 * self.mean * self.mean
+    }
+
+    /// Skewness, kurtosis, bimodality and tail from the sketch buckets
+    ///
+    /// As for raw samples the moments cover the body up to P99; bucket
+    /// representatives stand in for the samples.
+    pub fn shape(&self) -> DistributionShape {
+        let buckets = self.buckets();
+        let p99 = weighted_percentile(&buckets, 99.0);
+        let body: Vec<(f64, u64)> = buckets.iter().copied().filter(|&(value, _)| value <= p99).collect();
+        let n: u64 = body.iter().map(|&(_, count)| count).sum();
+        let mean = body.iter().map(|&(value, count)| value * count as f64).sum::<f64>() / n.max(1) as f64;
+        let (m2, m3, m4) = body.iter().fold((0.0, 0.0, 0.0), |(m2, m3, m4), &(value, count)| {
+            let d = value - mean;
+            let c = count as f64;
+            (m2 + c * d * d, m3 + c * d * d * d, m4 + c * d * d * d * d)
+        });
+        DistributionShape::from_moments(n as usize, m2, m3, m4, weighted_percentile(&buckets, 99.9), self.max)
+    }
+
+    /// `p`-th percentile (0..=100) from the sketch, NaN when empty
+    pub fn percentile(&self, p: f64) -> f64 {
+        weighted_percentile(&self.buckets(), p)
+    }
+
+    /// Sketch buckets as (representative value, count), ascending
+    ///
+    /// Representatives are clamped to the exact minimum and maximum.
+    pub fn buckets(&self) -> Vec<(f64, u64)> {
+        self.sketch.buckets().into_iter()
+            .map(|(value, count)| (value.clamp(self.min, self.max), count))
+            .collect()
+    }
+}
+
+/// Percentile of (value, count) pairs sorted by value, using the nearest rank
+pub fn weighted_percentile(sorted: &[(f64, u64)], p: f64) -> f64 {
+    let total: u64 = sorted.iter().map(|&(_, count)| count).sum();
+    if total == 0 {
+        return f64::NAN;
+    }
+    let rank = ((p / 100.0).clamp(0.0, 1.0) * (total - 1) as f64).round() as u64;
+    let mut seen = 0;
+    for &(value, count) in sorted {
+        seen += count;
+        if seen > rank {
+            return value;
+        }
+    }
+    sorted[sorted.len() - 1].0
+}
+
+/// Logarithmically bucketed counts of positive and negative values
+#[derive(Debug, Clone, Default)]
+struct QuantileSketch {
+    positive: BTreeMap<i32, u64>,
+    negative: BTreeMap<i32, u64>,
+    zero: u64,
+}
+
+impl QuantileSketch {
+    fn gamma() -> f64 {
+        (1.0 + SKETCH_ACCURACY) / (1.0 - SKETCH_ACCURACY)
+    }
+
+    fn index(magnitude: f64) -> i32 {
+        (magnitude.ln() / Self::gamma().ln()).ceil() as i32
+    }
+
+    /// Value every sample of bucket `index` is within `SKETCH_ACCURACY` of
+    fn representative(index: i32) -> f64 {
+        let gamma = Self::gamma();
+        2.0 * gamma.powi(index) / (gamma + 1.0)
+    }
+
+    fn add(&mut self, x: f64) {
+        if x.abs() < ZERO_THRESHOLD {
+            self.zero += 1;
+        } else if x > 0.0 {
+            *self.positive.entry(Self::index(x)).or_default() += 1;
+        } else {
+            *self.negative.entry(Self::index(-x)).or_default() += 1;
+        }
+    }
+
+    fn merge(&mut self, other: &Self) {
+        for (&index, &count) in &other.positive {
+            *self.positive.entry(index).or_default() += count;
+        }
+        for (&index, &count) in &other.negative {
+            *self.negative.entry(index).or_default() += count;
+        }
+        self.zero += other.zero;
+    }
+
+    fn buckets(&self) -> Vec<(f64, u64)> {
+        let mut buckets: Vec<(f64, u64)> = self.negative.iter().rev()
+            .map(|(&index, &count)| (-Self::representative(index), count))
+            .collect();
+        if self.zero > 0 {
+            buckets.push((0.0, self.zero));
+        }
+        buckets.extend(self.positive.iter().map(|(&index, &count)| (Self::representative(index), count)));
+        buckets
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::stats::quantile::QuantileEstimator;
+
+    /// Overshoot-like values spread over three orders of magnitude, with negatives and zeros
+    fn samples() -> Vec<f64> {
+        (0..2000)
+            .map(|i| match i % 10 {
+                0 => 0.0,
+                1 => -0.001 * (i % 7 + 1) as f64,
+                _ => 0.01 + 0.00005 * i as f64 + 0.5 * ((i % 97) as f64 / 97.0).powi(4),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn moments_match_the_samples() {
+        let samples = samples();
+        let accumulator = SampleAccumulator::from_samples(&samples);
+        let n = samples.len() as f64;
+        let mean = samples.iter().sum::<f64>() / n;
+        let variance = samples.iter().map(|x| (x - mean).powi(2)).sum::<f64>() / n;
+        assert_eq!(accumulator.count(), samples.len() as u64);
+        assert!((accumulator.mean() - mean).abs() < 1e-12);
+        assert!((accumulator.variance() - variance).abs() < 1e-12);
+        assert!((accumulator.sum_of_squares() - samples.iter().map(|x| x * x).sum::<f64>()).abs() < 1e-9);
+    }
+
+    #[test]
+    fn quantiles_are_within_the_relative_accuracy() {
+        let mut sorted = samples();
+        let accumulator = SampleAccumulator::from_samples(&sorted);
+        sorted.sort_by(f64::total_cmp);
+        for p in [0.0, 1.0, 10.0, 25.0, 50.0, 75.0, 95.0, 99.0, 99.9, 100.0] {
+            let exact = QuantileEstimator::NearestRank.percentile(&sorted, p);
+            let sketched = accumulator.percentile(p);
+            assert!((sketched - exact).abs() <= SKETCH_ACCURACY * exact.abs() + 1e-12,
+                "P{}: sketch {} vs exact {}", p, sketched, exact);
+        }
+    }
+
+    #[test]
+    fn merging_equals_accumulating_everything() {
+        let samples = samples();
+        let (left, right) = samples.split_at(700);
+        let mut merged = SampleAccumulator::from_samples(left);
+        merged.merge(&SampleAccumulator::from_samples(right));
+        let whole = SampleAccumulator::from_samples(&samples);
+        assert_eq!(merged.count(), whole.count());
+        assert!((merged.mean() - whole.mean()).abs() < 1e-12);
+        assert!((merged.variance() - whole.variance()).abs() < 1e-12);
+        assert_eq!(merged.buckets(), whole.buckets());
+    }
+
+    #[test]
+    fn non_finite_values_are_counted_not_accumulated() {
+        let mut accumulator = SampleAccumulator::from_samples(&[1.0, f64::NAN, 2.0, f64::INFINITY]);
+        assert_eq!((accumulator.count(), accumulator.non_finite()), (2, 2));
+        assert_eq!(accumulator.mean(), 1.5);
+
+        // Merging into an empty accumulator keeps both non-finite counts
+        let mut empty = SampleAccumulator::from_samples(&[f64::NAN]);
+        empty.merge(&accumulator);
+        assert_eq!((empty.count(), empty.non_finite()), (2, 3));
+
+        accumulator.merge(&SampleAccumulator::default());
+        assert_eq!(accumulator.count(), 2);
+        assert!(SampleAccumulator::default().percentile(50.0).is_nan());
+    }
+}
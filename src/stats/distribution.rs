@@ -0,0 +1,197 @@
+//! Shape of a delta distribution
+//!
+//! Sleeps that alternate between two timer ticks give a two-humped
+//! distribution whose median and P95 can look harmless. Skewness, excess
+//! kurtosis and the bimodality coefficient describe the body of the samples
+//! up to P99 (the MAD outlier filter would cut off a second mode, and a few
+//! spikes alone would pass for one); P99.9 and the maximum summarise the
+//! tail. The histogram shows the whole picture.
+
+use serde::{Deserialize, Serialize};
+
+use crate::stats::quantile::QuantileEstimator;
+
+/// Bimodality coefficient of a uniform distribution; higher values suggest two modes
+pub const BIMODALITY_THRESHOLD: f64 = 5.0 / 9.0;
+
+/// Eighths of a block for the histogram bars
+const BAR_BLOCKS: [char; 8] = ['▏', '▎', '▍', '▌', '▋', '▊', '▉', '█'];
+
+/// Shape of the body and tail of a measurement's samples
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct DistributionShape {
+    pub skewness: f64,
+    pub excess_kurtosis: f64,
+    /// (G1² + 1) / (G2 + 3(n−1)²/((n−2)(n−3))), above `BIMODALITY_THRESHOLD` for two modes
+    pub bimodality_coefficient: f64,
+    pub p999: f64,
+    pub max: f64,
+}
+
+impl DistributionShape {
+    /// Shape from `n` samples with sums of the 2nd, 3rd and 4th powers of their deviations from the mean
+    ///
+    /// Skewness and kurtosis are the bias-corrected sample estimates (G1, G2);
+    /// they and the coefficient stay 0 below four samples or without spread.
+    pub fn from_moments(n: usize, m2: f64, m3: f64, m4: f64, p999: f64, max: f64) -> Self {
+        if n < 4 || m2 <= f64::EPSILON * f64::EPSILON {
+            return Self { p999, max, ..Self::default() };
+        }
+        let n = n as f64;
+        let g1 = n.sqrt() * m3 / m2.powf(1.5);
+        let g2 = n * m4 / (m2 * m2) - 3.0;
+        let skewness = g1 * (n * (n - 1.0)).sqrt() / (n - 2.0);
+        let excess_kurtosis = (n - 1.0) / ((n - 2.0) * (n - 3.0)) * ((n + 1.0) * g2 + 6.0);
+        let bimodality_coefficient = (skewness * skewness + 1.0)
+            / (excess_kurtosis + 3.0 * (n - 1.0).powi(2) / ((n - 2.0) * (n - 3.0)));
+        Self { skewness, excess_kurtosis, bimodality_coefficient, p999, max }
+    }
+
+    /// Shape of sorted samples, moments over the samples up to `p99`
+    pub fn of_sorted(sorted: &[f64], p99: f64, p999: f64) -> Self {
+        let body: Vec<f64> = sorted.iter().copied().take_while(|&x| x <= p99).collect();
+        let n = body.len();
+        let mean = body.iter().sum::<f64>() / n.max(1) as f64;
+        let (m2, m3, m4) = body.iter().fold((0.0, 0.0, 0.0), |(m2, m3, m4), &x| {
+            let d = x - mean;
+            (m2 + d * d, m3 + d * d * d, m4 + d * d * d * d)
+        });
+        Self::from_moments(n, m2, m3, m4, p999, sorted.last().copied().unwrap_or(f64::NAN))
+    }
+
+    pub fn is_bimodal(&self) -> bool {
+        self.bimodality_coefficient > BIMODALITY_THRESHOLD
+    }
+}
+
+/// Equal-width histogram of the body of the samples, from the smallest one to P99
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Histogram {
+    pub lower_ms: f64,
+    pub bin_width_ms: f64,
+    pub counts: Vec<usize>,
+    /// Samples above the last bin (the tail beyond P99)
+    pub overflow: usize,
+}
+
+impl Histogram {
+    /// `bins` bins over the finite `samples`; `None` for 0 bins or no samples
+    ///
+    /// The range ends at the largest sample up to P99 (by `estimator`) so a single spike does
+    /// not squeeze the body of the distribution into one bin.
+    pub fn of_samples(samples: &[f64], bins: usize, estimator: QuantileEstimator) -> Option<Self> {
+        let mut sorted: Vec<f64> = samples.iter().copied().filter(|x| x.is_finite()).collect();
+        if bins == 0 || sorted.is_empty() {
+            return None;
+        }
+        sorted.sort_by(f64::total_cmp);
+        let lower_ms = sorted[0];
+        let p99 = estimator.percentile(&sorted, 99.0);
+        let upper_ms = sorted.iter().copied().take_while(|&x| x <= p99).last().unwrap_or(lower_ms);
+        if upper_ms <= lower_ms {
+            let equal = sorted.iter().take_while(|&&x| x <= lower_ms).count();
+            return Some(Self { lower_ms, bin_width_ms: 0.0, counts: vec![equal], overflow: sorted.len() - equal });
+        }
+        let bin_width_ms = (upper_ms - lower_ms) / bins as f64;
+        let mut counts = vec![0; bins];
+        let mut overflow = 0;
+        for &x in &sorted {
+            if x > upper_ms {
+                overflow += 1;
+            } else {
+                counts[(((x - lower_ms) / bin_width_ms) as usize).min(bins - 1)] += 1;
+            }
+        }
+        Some(Self { lower_ms, bin_width_ms, counts, overflow })
+    }
+
+    /// Upper edge of the last bin
+    pub fn upper_ms(&self) -> f64 {
+        self.lower_ms + self.bin_width_ms * self.counts.len() as f64
+    }
+
+    /// One line per bin, bars at most `width` characters, e.g. "  0.0150 ms │████▌   │ 42"
+    pub fn render(&self, width: usize) -> Vec<String> {
+        let peak = self.counts.iter().copied().max().unwrap_or(0).max(1);
+        let mut lines: Vec<String> = self.counts.iter().enumerate()
+            .map(|(i, &count)| {
+                let eighths = count * width * 8 / peak;
+                let mut bar = BAR_BLOCKS[7].to_string().repeat(eighths / 8);
+                let remainder = eighths % 8;
+                if remainder > 0 {
+                    bar.push(BAR_BLOCKS[remainder - 1]);
+                }
+                let lower = self.lower_ms + self.bin_width_ms * i as f64;
+                format!("{:>9.4} ms │{:<width$}│ {}", lower, bar, count, width = width)
+            })
+            .collect();
+        if self.overflow > 0 {
+            lines.push(format!("{:>9} > {:.4} ms: {}", "", self.upper_ms(), self.overflow));
+        }
+        lines
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ESTIMATOR: QuantileEstimator = QuantileEstimator::Type7;
+
+    #[test]
+    fn histogram_of_nothing_is_none() {
+        assert!(Histogram::of_samples(&[], 10, ESTIMATOR).is_none());
+        assert!(Histogram::of_samples(&[f64::NAN, f64::INFINITY], 10, ESTIMATOR).is_none());
+        assert!(Histogram::of_samples(&[0.1, 0.2], 0, ESTIMATOR).is_none());
+    }
+
+    #[test]
+    fn equal_samples_fill_one_bin() {
+        let histogram = Histogram::of_samples(&[0.25; 50], 10, ESTIMATOR).unwrap();
+        assert_eq!(histogram.counts, vec![50]);
+        assert_eq!((histogram.lower_ms, histogram.bin_width_ms, histogram.overflow), (0.25, 0.0, 0));
+
+        // A spike above an otherwise constant body lands in the overflow
+        let mut samples = vec![0.25; 99];
+        samples.push(5.0);
+        let histogram = Histogram::of_samples(&samples, 10, ESTIMATOR).unwrap();
+        assert_eq!((histogram.counts.clone(), histogram.overflow), (vec![99], 1));
+    }
+
+    #[test]
+    fn tail_beyond_p99_overflows() {
+        let mut samples: Vec<f64> = (0..1000).map(|i| i as f64 / 1000.0).collect();
+        samples.extend([10.0; 5]);
+        let histogram = Histogram::of_samples(&samples, 10, ESTIMATOR).unwrap();
+
+        // P99 of 1005 samples falls between 0.993 and 0.994
+        assert_eq!(histogram.counts.len(), 10);
+        assert!((histogram.upper_ms() - 0.993).abs() < 1e-12);
+        assert_eq!(histogram.overflow, 11);
+        assert_eq!(histogram.counts.iter().sum::<usize>(), 994);
+        assert!(histogram.counts.iter().all(|&count| (99..=101).contains(&count)));
+    }
+
+    /// Shape of sorted samples with every sample in the body
+    fn shape(mut samples: Vec<f64>) -> DistributionShape {
+        samples.sort_by(f64::total_cmp);
+        let max = samples[samples.len() - 1];
+        DistributionShape::of_sorted(&samples, max, max)
+    }
+
+    #[test]
+    fn two_modes_are_bimodal() {
+        let samples = (0..200).map(|i| if i % 2 == 0 { 1.0 } else { 2.0 } + (i % 7) as f64 * 0.01).collect();
+        let shape = shape(samples);
+        assert!(shape.is_bimodal(), "coefficient {}", shape.bimodality_coefficient);
+    }
+
+    #[test]
+    fn one_mode_is_not_bimodal() {
+        // Sum of two uniforms: a triangle peaking at 1.0
+        let samples = (0..400).map(|i| (i % 20) as f64 / 20.0 + (i / 20) as f64 / 20.0).collect();
+        let shape = shape(samples);
+        assert!(!shape.is_bimodal(), "coefficient {}", shape.bimodality_coefficient);
+        assert!(shape.skewness.abs() < 0.1);
+    }
+}
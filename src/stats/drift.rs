@@ -0,0 +1,322 @@
+//! Warm-up and drift within a measurement run
+//!
+//! The first samples after a resolution change often overshoot differently
+//! while the system settles, and a run can drift as power states or background
+//! load change. Samples are analysed in the order they were taken: Pettitt's
+//! test (a CUSUM of ranks, so spikes do not sway it) locates a change point,
+//! and a change in the first half of the run is cut off as warm-up when the
+//! samples before it overshoot more (higher median) and the rest is free of
+//! trend. Any other change point is drift: all samples are kept. The
+//! Mann–Kendall test then looks for a monotonic trend in what remains.
+
+use std::cmp::Ordering;
+
+use serde::{Deserialize, Serialize};
+
+use crate::stats::quantile::{normal_cdf, QuantileEstimator};
+
+/// Significance level of both tests
+pub const SIGNIFICANCE: f64 = 0.01;
+
+/// Longest prefix cut off as warm-up, as a share of the run
+const MAX_WARMUP_SHARE: f64 = 0.5;
+
+/// Fewest samples the tests are run on
+const MIN_RUN_SAMPLES: usize = 10;
+
+/// Runs longer than this are reduced to block means for the O(n²) trend test
+const MAX_TREND_POINTS: usize = 1000;
+
+/// Settle times are suggested in steps of this many milliseconds
+const SETTLE_STEP_MS: f64 = 50.0;
+
+/// Monotonic trend found by the Mann–Kendall test
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct Trend {
+    pub tau: f64,  // Kendall's tau against sample order, positive when overshoot grows
+    pub p_value: f64,
+}
+
+/// Warm-up and trend of one run
+#[derive(Debug, Clone, Copy)]
+pub struct RunAnalysis {
+    pub warmup_samples: usize,
+    /// Time from the start of sampling to the end of the warm-up, when the probe reports timestamps
+    pub warmup_ms: Option<f64>,
+    /// Samples before a change point that was not cut off as warm-up
+    pub shift_at: Option<usize>,
+    pub trend: Option<Trend>,
+}
+
+impl RunAnalysis {
+    /// The overshoot shifts or trends in the samples kept
+    pub fn drifts(&self) -> bool {
+        self.shift_at.is_some() || self.trend.is_some()
+    }
+}
+
+/// Find the warm-up prefix of a run and test the rest for drift
+///
+/// `offsets_ms` are the wakeup times from the start of sampling, one per
+/// sample, or empty when the probe does not report them.
+pub fn analyze_run(deltas: &[f64], offsets_ms: &[f64]) -> RunAnalysis {
+    let mut analysis = RunAnalysis { warmup_samples: 0, warmup_ms: None, shift_at: None, trend: None };
+    if deltas.len() < MIN_RUN_SAMPLES {
+        return analysis;
+    }
+    if let Some(split) = pettitt_change_point(deltas) {
+        let (prefix, rest) = deltas.split_at(split);
+        if split as f64 <= deltas.len() as f64 * MAX_WARMUP_SHARE
+            && median(prefix) > median(rest)
+            && mann_kendall(rest).is_none()
+        {
+            analysis.warmup_samples = split;
+            analysis.warmup_ms = (offsets_ms.len() == deltas.len()).then(|| offsets_ms[split - 1]);
+        } else {
+            analysis.shift_at = Some(split);
+        }
+    }
+    analysis.trend = mann_kendall(&deltas[analysis.warmup_samples..]);
+    analysis
+}
+
+/// Warm-up and drift over the runs of one measurement
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Stationarity {
+    pub runs: usize,
+    /// Warm-up samples dropped over all runs
+    pub warmup_samples: usize,
+    /// Longest warm-up of a run, from the start of sampling
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub warmup_ms: Option<f64>,
+    /// Settle time between applying the resolution and sampling
+    pub settle_ms: u64,
+    /// Runs with a significant shift or trend after the warm-up
+    pub drifting_runs: usize,
+}
+
+impl Stationarity {
+    pub fn new(settle_ms: u64) -> Self {
+        Self { settle_ms, ..Self::default() }
+    }
+
+    pub fn add_run(&mut self, run: &RunAnalysis) {
+        self.runs += 1;
+        self.warmup_samples += run.warmup_samples;
+        self.warmup_ms = max_option(self.warmup_ms, run.warmup_ms);
+        self.drifting_runs += usize::from(run.drifts());
+    }
+
+    pub fn merge(&mut self, other: &Self) {
+        self.runs += other.runs;
+        self.warmup_samples += other.warmup_samples;
+        self.warmup_ms = max_option(self.warmup_ms, other.warmup_ms);
+        self.settle_ms = self.settle_ms.max(other.settle_ms);
+        self.drifting_runs += other.drifting_runs;
+    }
+}
+
+/// Settle time that would have covered the longest warm-up seen, rounded up
+/// to 50 ms; `None` when no timed warm-up was found
+pub fn suggested_settle_ms<'a>(points: impl IntoIterator<Item = &'a Stationarity>) -> Option<u64> {
+    points.into_iter()
+        .filter_map(|s| s.warmup_ms.map(|warmup| s.settle_ms as f64 + warmup))
+        .max_by(f64::total_cmp)
+        .map(|ms| ((ms / SETTLE_STEP_MS).ceil() * SETTLE_STEP_MS) as u64)
+}
+
+fn median(x: &[f64]) -> f64 {
+    let mut sorted = x.to_vec();
+    sorted.sort_by(f64::total_cmp);
+    QuantileEstimator::Type7.percentile(&sorted, 50.0)
+}
+
+fn max_option(a: Option<f64>, b: Option<f64>) -> Option<f64> {
+    match (a, b) {
+        (Some(a), Some(b)) => Some(a.max(b)),
+        (a, b) => a.or(b),
+    }
+}
+
+/// Pettitt's test: number of samples before the most likely change point,
+/// `None` unless the change is significant
+fn pettitt_change_point(x: &[f64]) -> Option<usize> {
+    let n = x.len();
+    let ranks = average_ranks(x);
+    let mut rank_sum = 0.0;
+    let mut best = (0, 0.0);
+    for (k, rank) in ranks.iter().enumerate().take(n - 1) {
+        rank_sum += rank;
+        let u = (2.0 * rank_sum - (k + 1) as f64 * (n + 1) as f64).abs();
+        if u > best.1 {
+            best = (k + 1, u);
+        }
+    }
+    let (split, k) = best;
+    let n = n as f64;
+    let p_value = (2.0 * (-6.0 * k * k / (n * n * n + n * n)).exp()).min(1.0);
+    (split > 0 && p_value < SIGNIFICANCE).then_some(split)
+}
+
+/// Ranks from 1 with ties sharing their average rank
+fn average_ranks(x: &[f64]) -> Vec<f64> {
+    let mut order: Vec<usize> = (0..x.len()).collect();
+    order.sort_by(|&a, &b| x[a].total_cmp(&x[b]));
+    let mut ranks = vec![0.0; x.len()];
+    let mut start = 0;
+    while start < order.len() {
+        let mut end = start + 1;
+        while end < order.len() && x[order[end]] == x[order[start]] {
+            end += 1;
+        }
+        let rank = (start + end + 1) as f64 / 2.0;
+        for &i in &order[start..end] {
+            ranks[i] = rank;
+        }
+        start = end;
+    }
+    ranks
+}
+
+/// Mann–Kendall trend test with the tie correction, `None` unless significant
+///
+/// Long runs are reduced to `MAX_TREND_POINTS` block means first.
+fn mann_kendall(x: &[f64]) -> Option<Trend> {
+    if x.len() < MIN_RUN_SAMPLES {
+        return None;
+    }
+    let reduced;
+    let x = if x.len() > MAX_TREND_POINTS {
+        let block = x.len().div_ceil(MAX_TREND_POINTS);
+        reduced = x.chunks(block).map(|c| c.iter().sum::<f64>() / c.len() as f64).collect::<Vec<f64>>();
+        &reduced[..]
+    } else {
+        x
+    };
+
+    let n = x.len();
+    let mut s = 0i64;
+    for i in 0..n {
+        for j in i + 1..n {
+            s += match x[j].partial_cmp(&x[i]) {
+                Some(Ordering::Greater) => 1,
+                Some(Ordering::Less) => -1,
+                _ => 0,
+            };
+        }
+    }
+    let mut sorted = x.to_vec();
+    sorted.sort_by(f64::total_cmp);
+    let ties: f64 = sorted.chunk_by(|a, b| a == b)
+        .map(|group| group.len() as f64)
+        .filter(|&t| t > 1.0)
+        .map(|t| t * (t - 1.0) * (2.0 * t + 5.0))
+        .sum();
+    let nf = n as f64;
+    let variance = (nf * (nf - 1.0) * (2.0 * nf + 5.0) - ties) / 18.0;
+    if variance <= 0.0 {
+        return None;
+    }
+    let z = match s.cmp(&0) {
+        Ordering::Greater => (s - 1) as f64 / variance.sqrt(),
+        Ordering::Less => (s + 1) as f64 / variance.sqrt(),
+        Ordering::Equal => 0.0,
+    };
+    let p_value = 2.0 * (1.0 - normal_cdf(z.abs()));
+    let tau = s as f64 / (nf * (nf - 1.0) / 2.0);
+    (p_value < SIGNIFICANCE).then_some(Trend { tau, p_value })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::rng::SplitMix64;
+
+    /// Stationary overshoot: 0.05 ms plus seeded Gaussian noise
+    fn noise(n: usize, seed: u64) -> Vec<f64> {
+        let mut rng = SplitMix64::new(seed);
+        (0..n).map(|_| 0.05 + 0.002 * rng.next_gaussian()).collect()
+    }
+
+    #[test]
+    fn stationary_run_has_no_change_point_or_trend() {
+        let deltas = noise(400, 1);
+        assert_eq!(pettitt_change_point(&deltas), None);
+        assert!(mann_kendall(&deltas).is_none());
+        let analysis = analyze_run(&deltas, &[]);
+        assert_eq!(analysis.warmup_samples, 0);
+        assert!(!analysis.drifts());
+    }
+
+    #[test]
+    fn pettitt_locates_a_step() {
+        let mut deltas = noise(300, 2);
+        for d in &mut deltas[..60] {
+            *d += 0.02;
+        }
+        let split = pettitt_change_point(&deltas).unwrap();
+        assert!((58..=62).contains(&split), "change point at {}", split);
+    }
+
+    #[test]
+    fn mann_kendall_finds_the_direction_of_a_trend() {
+        let rising: Vec<f64> = noise(300, 3).iter().enumerate().map(|(i, d)| d + 0.0001 * i as f64).collect();
+        let trend = mann_kendall(&rising).unwrap();
+        assert!(trend.tau > 0.0 && trend.p_value < SIGNIFICANCE);
+        let falling: Vec<f64> = rising.iter().rev().copied().collect();
+        assert!(mann_kendall(&falling).unwrap().tau < 0.0);
+        // Block means keep long runs tractable and the trend visible
+        let long: Vec<f64> = noise(5000, 4).iter().enumerate().map(|(i, d)| d + 0.00001 * i as f64).collect();
+        assert!(mann_kendall(&long).unwrap().tau > 0.0);
+    }
+
+    #[test]
+    fn worse_prefix_is_cut_off_as_warm_up() {
+        let mut deltas = noise(300, 5);
+        for d in &mut deltas[..50] {
+            *d += 0.03;
+        }
+        let offsets: Vec<f64> = (1..=deltas.len()).map(|i| i as f64 * 1.05).collect();
+        let analysis = analyze_run(&deltas, &offsets);
+        assert!((48..=52).contains(&analysis.warmup_samples), "warm-up of {}", analysis.warmup_samples);
+        assert_eq!(analysis.warmup_ms, Some(offsets[analysis.warmup_samples - 1]));
+        assert!(!analysis.drifts());
+    }
+
+    #[test]
+    fn better_prefix_is_drift_not_warm_up() {
+        let mut deltas = noise(300, 6);
+        for d in &mut deltas[50..] {
+            *d += 0.03;
+        }
+        let analysis = analyze_run(&deltas, &[]);
+        assert_eq!(analysis.warmup_samples, 0);
+        assert!(analysis.shift_at.is_some());
+        assert!(analysis.drifts());
+    }
+
+    #[test]
+    fn late_change_point_is_drift_not_warm_up() {
+        let mut deltas = noise(300, 7);
+        for d in &mut deltas[..250] {
+            *d += 0.03;
+        }
+        let analysis = analyze_run(&deltas, &[]);
+        assert_eq!(analysis.warmup_samples, 0);
+        assert!(analysis.drifts());
+    }
+
+    #[test]
+    fn settle_time_covers_the_longest_warm_up() {
+        let mut first = Stationarity::new(350);
+        first.add_run(&RunAnalysis { warmup_samples: 10, warmup_ms: Some(12.0), shift_at: None, trend: None });
+        let mut second = Stationarity::new(350);
+        second.add_run(&RunAnalysis { warmup_samples: 40, warmup_ms: Some(70.0), shift_at: Some(3), trend: None });
+        assert_eq!(suggested_settle_ms([&first, &second]), Some(450));
+        assert_eq!(suggested_settle_ms([&Stationarity::new(350)]), None);
+
+        first.merge(&second);
+        assert_eq!((first.runs, first.warmup_samples, first.drifting_runs), (2, 50, 1));
+        assert_eq!(first.warmup_ms, Some(70.0));
+    }
+}
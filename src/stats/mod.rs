@@ -1,4 +1,9 @@
 //! Statistics modules for timer resolution benchmarking
 
+pub mod accumulator;
+pub mod distribution;
+pub mod drift;
+pub mod quantile;
 pub mod robust_statistics;
-pub mod timer_measurement;
\ No newline at end of file
+pub mod timer_measurement;
+pub mod significance;
@@ -0,0 +1,304 @@
+//! Quantile estimators and the distribution functions they need
+//!
+//! Every estimator here is a weighted sum of order statistics, so the weights
+//! for a sample size can be computed once and reused across bootstrap
+//! resamples of the same size.
+
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+/// How percentiles are estimated from a sample
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize, clap::ValueEnum)]
+#[serde(rename_all = "kebab-case")]
+pub enum QuantileEstimator {
+    /// Nearest rank (results of earlier versions); P99 of a small sample is its maximum
+    NearestRank,
+    /// Hyndman–Fan type 7: linear interpolation between order statistics
+    #[default]
+    Type7,
+    /// Harrell–Davis: Beta-weighted average of all order statistics, smoothest for small samples
+    HarrellDavis,
+    /// Streaming sketch of `SampleAccumulator`; not selectable, on raw samples it acts as type 7
+    #[value(skip)]
+    Sketch,
+}
+
+impl QuantileEstimator {
+    pub fn name(&self) -> &'static str {
+        match self {
+            QuantileEstimator::NearestRank => "nearest rank",
+            QuantileEstimator::Type7 => "Hyndman–Fan type 7",
+            QuantileEstimator::HarrellDavis => "Harrell–Davis",
+            QuantileEstimator::Sketch => "streaming sketch",
+        }
+    }
+
+    /// `p`-th percentile (0..=100) of sorted data, NaN when empty
+    pub fn percentile(&self, sorted: &[f64], p: f64) -> f64 {
+        self.weights(sorted.len(), p).apply(sorted)
+    }
+
+    /// Order-statistic weights of the `p`-th percentile for `n` samples
+    pub fn weights(&self, n: usize, p: f64) -> QuantileWeights {
+        let q = (p / 100.0).clamp(0.0, 1.0);
+        let terms = match (n, self) {
+            (0, _) => Vec::new(),
+            (1, _) => vec![(0, 1.0)],
+            (_, QuantileEstimator::NearestRank) => {
+                vec![(((q * (n - 1) as f64).round() as usize).min(n - 1), 1.0)]
+            }
+            (_, QuantileEstimator::Type7 | QuantileEstimator::Sketch) => {
+                let h = q * (n - 1) as f64;
+                let lower = (h.floor() as usize).min(n - 1);
+                let upper = (lower + 1).min(n - 1);
+                let fraction = h - lower as f64;
+                vec![(lower, 1.0 - fraction), (upper, fraction)]
+            }
+            (_, QuantileEstimator::HarrellDavis) => {
+                if q <= 0.0 {
+                    vec![(0, 1.0)]
+                } else if q >= 1.0 {
+                    vec![(n - 1, 1.0)]
+                } else {
+                    let a = q * (n + 1) as f64;
+                    let b = (1.0 - q) * (n + 1) as f64;
+                    let cdf: Vec<f64> = (0..=n)
+                        .map(|i| regularized_incomplete_beta(i as f64 / n as f64, a, b))
+                        .collect();
+                    cdf.windows(2).enumerate().map(|(i, w)| (i, w[1] - w[0])).collect()
+                }
+            }
+        };
+        QuantileWeights { terms }
+    }
+}
+
+/// Percentile as a weighted sum of order statistics
+#[derive(Debug, Clone)]
+pub struct QuantileWeights {
+    terms: Vec<(usize, f64)>,
+}
+
+impl QuantileWeights {
+    pub fn apply(&self, sorted: &[f64]) -> f64 {
+        if self.terms.is_empty() {
+            return f64::NAN;
+        }
+        self.terms.iter().map(|&(i, w)| w * sorted[i]).sum()
+    }
+}
+
+/// Percentiles with the weights of each (size, percentile) pair kept for reuse
+///
+/// Only Harrell–Davis weights are costly; they are what the cache is for.
+pub struct QuantileCache {
+    estimator: QuantileEstimator,
+    weights: HashMap<(usize, u64), QuantileWeights>,
+}
+
+impl QuantileCache {
+    pub fn new(estimator: QuantileEstimator) -> Self {
+        Self { estimator, weights: HashMap::new() }
+    }
+
+    pub fn percentile(&mut self, sorted: &[f64], p: f64) -> f64 {
+        if self.estimator != QuantileEstimator::HarrellDavis {
+            return self.estimator.percentile(sorted, p);
+        }
+        let estimator = self.estimator;
+        self.weights.entry((sorted.len(), p.to_bits()))
+            .or_insert_with(|| estimator.weights(sorted.len(), p))
+            .apply(sorted)
+    }
+}
+
+/// Two-sided 95% critical value of Student's t with `df` degrees of freedom
+///
+/// Table values up to 30 degrees of freedom, Cornish–Fisher expansion above.
+pub fn student_t_975(df: usize) -> f64 {
+    const TABLE: [f64; 30] = [
+        12.706, 4.303, 3.182, 2.776, 2.571, 2.447, 2.365, 2.306, 2.262, 2.228,
+        2.201, 2.179, 2.160, 2.145, 2.131, 2.120, 2.110, 2.101, 2.093, 2.086,
+        2.080, 2.074, 2.069, 2.064, 2.060, 2.056, 2.052, 2.048, 2.045, 2.042,
+    ];
+    if df == 0 {
+        return f64::INFINITY;
+    }
+    if df <= TABLE.len() {
+        return TABLE[df - 1];
+    }
+    let z: f64 = 1.959_964;
+    let v = df as f64;
+    z + (z.powi(3) + z) / (4.0 * v)
+        + (5.0 * z.powi(5) + 16.0 * z.powi(3) + 3.0 * z) / (96.0 * v * v)
+        + (3.0 * z.powi(7) + 19.0 * z.powi(5) + 17.0 * z.powi(3) - 15.0 * z) / (384.0 * v * v * v)
+}
+
+/// Standard normal CDF via the complementary error function
+pub fn normal_cdf(z: f64) -> f64 {
+    0.5 * erfc(-z / std::f64::consts::SQRT_2)
+}
+
+/// Complementary error function (Numerical Recipes erfcc, |error| < 1.2e-7)
+fn erfc(x: f64) -> f64 {
+    let z = x.abs();
+    let t = 1.0 / (1.0 + 0.5 * z);
+    let poly = -z * z - 1.265_512_23
+        + t * (1.000_023_68
+        + t * (0.374_091_96
+        + t * (0.096_784_18
+        + t * (-0.186_288_06
+        + t * (0.278_868_07
+        + t * (-1.135_203_98
+        + t * (1.488_515_87
+        + t * (-0.822_152_23
+        + t * 0.170_872_77))))))));
+    let result = t * poly.exp();
+    if x >= 0.0 { result } else { 2.0 - result }
+}
+
+/// Regularized incomplete beta function I_x(a, b) (Numerical Recipes betai)
+fn regularized_incomplete_beta(x: f64, a: f64, b: f64) -> f64 {
+    if x <= 0.0 {
+        return 0.0;
+    }
+    if x >= 1.0 {
+        return 1.0;
+    }
+    let ln_front = ln_gamma(a + b) - ln_gamma(a) - ln_gamma(b) + a * x.ln() + b * (1.0 - x).ln();
+    if x < (a + 1.0) / (a + b + 2.0) {
+        ln_front.exp() * beta_continued_fraction(x, a, b) / a
+    } else {
+        1.0 - ln_front.exp() * beta_continued_fraction(1.0 - x, b, a) / b
+    }
+}
+
+/// Continued fraction of the incomplete beta function (modified Lentz)
+fn beta_continued_fraction(x: f64, a: f64, b: f64) -> f64 {
+    const MAX_ITERATIONS: usize = 300;
+    const EPSILON: f64 = 3e-14;
+    const TINY: f64 = 1e-300;
+    let guard = |v: f64| if v.abs() < TINY { TINY } else { v };
+
+    let mut c = 1.0;
+    let mut d = 1.0 / guard(1.0 - (a + b) * x / (a + 1.0));
+    let mut h = d;
+    for m in 1..=MAX_ITERATIONS {
+        let m = m as f64;
+        let even = m * (b - m) * x / ((a + 2.0 * m - 1.0) * (a + 2.0 * m));
+        d = 1.0 / guard(1.0 + even * d);
+        c = guard(1.0 + even / c);
+        h *= d * c;
+        let odd = -(a + m) * (a + b + m) * x / ((a + 2.0 * m) * (a + 2.0 * m + 1.0));
+        d = 1.0 / guard(1.0 + odd * d);
+        c = guard(1.0 + odd / c);
+        let step = d * c;
+        h *= step;
+        if (step - 1.0).abs() < EPSILON {
+            break;
+        }
+    }
+    h
+}
+
+/// Natural log of the gamma function (Lanczos approximation, g = 7)
+fn ln_gamma(x: f64) -> f64 {
+    const COEFFICIENTS: [f64; 9] = [
+        0.999_999_999_999_809_9,
+        676.520_368_121_885_1,
+        -1_259.139_216_722_402_8,
+        771.323_428_777_653_1,
+        -176.615_029_162_140_6,
+        12.507_343_278_686_905,
+        -0.138_571_095_265_720_12,
+        9.984_369_578_019_572e-6,
+        1.505_632_735_149_311_6e-7,
+    ];
+    if x < 0.5 {
+        // Reflection formula
+        return (std::f64::consts::PI / (std::f64::consts::PI * x).sin()).ln() - ln_gamma(1.0 - x);
+    }
+    let x = x - 1.0;
+    let t = x + 7.5;
+    let series = COEFFICIENTS.iter().enumerate().skip(1)
+        .fold(COEFFICIENTS[0], |sum, (i, c)| sum + c / (x + i as f64));
+    0.5 * (2.0 * std::f64::consts::PI).ln() + (x + 0.5) * t.ln() - t + series.ln()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ALL: [QuantileEstimator; 3] =
+        [QuantileEstimator::NearestRank, QuantileEstimator::Type7, QuantileEstimator::HarrellDavis];
+
+    fn assert_close(actual: f64, expected: f64, tolerance: f64) {
+        assert!((actual - expected).abs() <= tolerance, "{} is not within {} of {}", actual, tolerance, expected);
+    }
+
+    #[test]
+    fn type7_interpolates_between_order_statistics() {
+        let sorted = [1.0, 2.0, 3.0, 4.0];
+        assert_close(QuantileEstimator::Type7.percentile(&sorted, 50.0), 2.5, 1e-12);
+        assert_close(QuantileEstimator::Type7.percentile(&sorted, 25.0), 1.75, 1e-12);
+        assert_close(QuantileEstimator::Type7.percentile(&sorted, 100.0), 4.0, 1e-12);
+    }
+
+    #[test]
+    fn nearest_rank_returns_a_sample() {
+        let sorted: Vec<f64> = (1..=10).map(f64::from).collect();
+        assert_eq!(QuantileEstimator::NearestRank.percentile(&sorted, 50.0), 6.0);
+        // P99 of a small sample is its maximum
+        assert_eq!(QuantileEstimator::NearestRank.percentile(&sorted, 99.0), 10.0);
+    }
+
+    #[test]
+    fn harrell_davis_weights_average_all_order_statistics() {
+        let weights = QuantileEstimator::HarrellDavis.weights(20, 90.0);
+        assert_eq!(weights.terms.len(), 20);
+        assert_close(weights.terms.iter().map(|&(_, w)| w).sum(), 1.0, 1e-9);
+        assert!(weights.terms.iter().all(|&(_, w)| w >= 0.0));
+
+        let sorted: Vec<f64> = (1..=9).map(f64::from).collect();
+        assert_close(QuantileEstimator::HarrellDavis.percentile(&sorted, 50.0), 5.0, 1e-9);
+        // Unlike nearest rank, the upper tail is not pinned to the maximum
+        let p99 = QuantileEstimator::HarrellDavis.percentile(&sorted, 99.0);
+        assert!(p99 > 8.0 && p99 < 9.0);
+    }
+
+    #[test]
+    fn degenerate_samples() {
+        for estimator in ALL {
+            assert!(estimator.percentile(&[], 50.0).is_nan());
+            assert_eq!(estimator.percentile(&[0.7], 95.0), 0.7);
+            assert_close(estimator.percentile(&[2.0, 2.0, 2.0], 99.0), 2.0, 1e-12);
+        }
+    }
+
+    #[test]
+    fn cache_matches_direct_evaluation() {
+        let sorted: Vec<f64> = (0..50).map(|i| (i as f64).sqrt()).collect();
+        for estimator in ALL {
+            let mut cache = QuantileCache::new(estimator);
+            for p in [5.0, 50.0, 95.0, 99.0] {
+                assert_eq!(cache.percentile(&sorted, p), estimator.percentile(&sorted, p));
+                assert_eq!(cache.percentile(&sorted, p), estimator.percentile(&sorted, p));
+            }
+        }
+    }
+
+    #[test]
+    fn distribution_functions() {
+        assert_close(normal_cdf(0.0), 0.5, 1e-7);
+        assert_close(normal_cdf(1.959_964), 0.975, 1e-6);
+        assert_close(normal_cdf(-1.959_964), 0.025, 1e-6);
+        assert_eq!(student_t_975(1), 12.706);
+        assert_eq!(student_t_975(0), f64::INFINITY);
+        // The expansion continues the table and tends to the normal quantile
+        assert_close(student_t_975(31), 2.040, 1e-3);
+        assert_close(student_t_975(100_000), 1.96, 1e-3);
+        assert_close(regularized_incomplete_beta(0.3, 1.0, 1.0), 0.3, 1e-9);
+        assert_close(regularized_incomplete_beta(0.5, 4.0, 4.0), 0.5, 1e-9);
+    }
+}
@@ -2,8 +2,24 @@
 //!
 //! This module provides robust statistical methods for accurate timer resolution measurements.
 
+use std::fmt;
+use std::io;
+
+use serde::{Deserialize, Serialize};
+
+use crate::stats::accumulator::{weighted_percentile, SampleAccumulator};
+use crate::stats::distribution::DistributionShape;
+use crate::stats::quantile::{student_t_975, QuantileCache, QuantileEstimator};
+use crate::utils::rng::SplitMix64;
+
+/// Fewest samples the statistics are defined for (spread and the Student t interval need two)
+pub const MIN_SAMPLES: usize = 2;
+
+/// Fixed seed so the quantile intervals of a run are reproducible
+const BOOTSTRAP_SEED: u64 = 0xC1_B007_5EED;
+
 /// Robust statistics struct for reliable measurements
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct RobustStatistics {
     pub mean: f64,
     pub median: f64,
@@ -13,55 +29,172 @@ pub struct RobustStatistics {
     pub p99: f64,              // 99th percentile
     pub outliers_removed: usize,
     pub confidence_interval_95: (f64, f64),
+    /// Estimator behind median, MAD, P95 and P99
+    #[serde(default = "legacy_quantile_estimator")]
+    pub quantile_estimator: QuantileEstimator,
+    /// How `confidence_interval_95` was computed
+    #[serde(default = "legacy_mean_interval")]
+    pub mean_interval: MeanInterval,
+    /// Bootstrap 95% intervals of median, P95 and P99 (final results only)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub quantile_intervals: Option<QuantileIntervals>,
+    /// Skewness, kurtosis, bimodality and tail of all samples, outliers included
+    #[serde(default)]
+    pub shape: DistributionShape,
 }
 
-impl RobustStatistics {
-    /// Create robust statistics from a vector of samples
-    pub fn from_samples(samples: Vec<f64>) -> Self {
-        if samples.is_empty() {
-            panic!("Cannot compute statistics from empty samples");
+/// Interval method of the mean
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum MeanInterval {
+    /// mean ± 1.96·SE (results of earlier versions)
+    Normal,
+    /// mean ± t(0.975, n−1)·SE with the sample standard deviation
+    StudentT,
+}
+
+impl MeanInterval {
+    pub fn name(&self) -> &'static str {
+        match self {
+            MeanInterval::Normal => "normal",
+            MeanInterval::StudentT => "Student t",
         }
-        let mut sorted = samples.clone();
-        sorted.sort_by(|a, b| a.partial_cmp(b).unwrap());
+    }
+}
 
+/// Percentile bootstrap 95% intervals of the quantiles
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct QuantileIntervals {
+    pub resamples: usize,
+    pub median: (f64, f64),
+    pub p95: (f64, f64),
+    pub p99: (f64, f64),
+}
+
+/// Why statistics could not be computed from a set of samples
+#[derive(Debug, Clone, PartialEq)]
+pub enum StatisticsError {
+    Empty,
+    /// NaN or infinite values, e.g. from a malformed probe line
+    NonFinite { count: usize, total: usize },
+    TooFewSamples { required: usize, actual: usize },
+}
+
+impl fmt::Display for StatisticsError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            StatisticsError::Empty => write!(f, "no samples"),
+            StatisticsError::NonFinite { count, total } => write!(f, "{} of {} samples are not finite", count, total),
+            StatisticsError::TooFewSamples { required, actual } => {
+                write!(f, "{} samples, at least {} required", actual, required)
+            }
+        }
+    }
+}
+
+impl std::error::Error for StatisticsError {}
+
+impl From<StatisticsError> for io::Error {
+    fn from(error: StatisticsError) -> Self {
+        io::Error::new(io::ErrorKind::InvalidData, error)
+    }
+}
+
+fn legacy_quantile_estimator() -> QuantileEstimator {
+    QuantileEstimator::NearestRank
+}
+
+fn legacy_mean_interval() -> MeanInterval {
+    MeanInterval::Normal
+}
+
+/// Median, MAD, outlier-free samples, P95 and P99 of sorted samples
+struct Quantiles {
+    median: f64,
+    mad: f64,
+    clean_samples: Vec<f64>,
+    p95: f64,
+    p99: f64,
+}
+
+impl Quantiles {
+    fn of_sorted(sorted: &[f64], cache: &mut QuantileCache) -> Self {
         // Median - robust central tendency
-        let median = Self::percentile(&sorted, 50.0);
+        let median = cache.percentile(sorted, 50.0);
 
         // MAD (Median Absolute Deviation) - robust measure of spread
-        let deviations: Vec<f64> = sorted.iter()
+        let mut dev_sorted: Vec<f64> = sorted.iter()
             .map(|&x| (x - median).abs())
             .collect();
-        let mut dev_sorted = deviations.clone();
-        dev_sorted.sort_by(|a, b| a.partial_cmp(b).unwrap());
-        let mad = Self::percentile(&dev_sorted, 50.0);
+        dev_sorted.sort_by(f64::total_cmp);
+        let mad = cache.percentile(&dev_sorted, 50.0);
 
         // Outlier removal using MAD method (more robust than Z-score)
         // Rule: |x - median| > k * MAD, where k = 3.5 (corresponds to ~3σ)
+        // More than half the samples equal to the median give MAD = 0, which would
+        // discard every other sample; the mean absolute deviation, scaled to MAD
+        // units for normal data, stands in then
         let k = 3.5;
-        let threshold = k * mad;
+        let scale = if mad > 0.0 {
+            mad
+        } else {
+            0.845 * dev_sorted.iter().sum::<f64>() / dev_sorted.len().max(1) as f64
+        };
+        let threshold = k * scale;
         let clean_samples: Vec<f64> = sorted.iter()
             .filter(|&&x| (x - median).abs() <= threshold)
             .copied()
             .collect();
+
+        // Percentiles - critical for understanding distribution
+        let p95 = cache.percentile(&clean_samples, 95.0);
+        let p99 = cache.percentile(&clean_samples, 99.0);
+        Self { median, mad, clean_samples, p95, p99 }
+    }
+}
+
+impl RobustStatistics {
+    /// Create robust statistics from a vector of samples, percentiles by `estimator`
+    ///
+    /// Fails on empty input, on NaN or infinite values and on fewer than
+    /// `MIN_SAMPLES` samples.
+    pub fn try_from_samples(samples: Vec<f64>, estimator: QuantileEstimator) -> Result<Self, StatisticsError> {
+        if samples.is_empty() {
+            return Err(StatisticsError::Empty);
+        }
+        let non_finite = samples.iter().filter(|x| !x.is_finite()).count();
+        if non_finite > 0 {
+            return Err(StatisticsError::NonFinite { count: non_finite, total: samples.len() });
+        }
+        if samples.len() < MIN_SAMPLES {
+            return Err(StatisticsError::TooFewSamples { required: MIN_SAMPLES, actual: samples.len() });
+        }
+        let mut sorted = samples.clone();
+        sorted.sort_by(f64::total_cmp);
+
+        let mut cache = QuantileCache::new(estimator);
+        let Quantiles { median, mad, clean_samples, p95, p99 } = Quantiles::of_sorted(&sorted, &mut cache);
+        let shape = DistributionShape::of_sorted(&sorted, cache.percentile(&sorted, 99.0), cache.percentile(&sorted, 99.9));
         let outliers_removed = samples.len() - clean_samples.len();
 
         // Recalculate on cleaned data
-        let clean_mean = clean_samples.iter().sum::<f64>() / clean_samples.len() as f64;
-        let variance = clean_samples.iter()
+        let n = clean_samples.len() as f64;
+        let clean_mean = clean_samples.iter().sum::<f64>() / n;
+        let sum_sq = clean_samples.iter()
             .map(|x| (x - clean_mean).powi(2))
-            .sum::<f64>() / clean_samples.len() as f64;
-        let stdev = variance.sqrt();
+            .sum::<f64>();
+        let stdev = (sum_sq / n).sqrt();
 
-        // Percentiles - critical for understanding distribution
-        let p95 = Self::percentile(&clean_samples, 95.0);
-        let p99 = Self::percentile(&clean_samples, 99.0);
-
-        // 95% confidence interval for mean
-        let se = stdev / (clean_samples.len() as f64).sqrt();
-        let ci_margin = 1.96 * se; // z-score for 95% CI
+        // 95% confidence interval for mean: Student t, which matters for small samples
+        let ci_margin = if clean_samples.len() < 2 {
+            0.0
+        } else {
+            let se = (sum_sq / (n - 1.0)).sqrt() / n.sqrt();
+            student_t_975(clean_samples.len() - 1) * se
+        };
         let confidence_interval_95 = (clean_mean - ci_margin, clean_mean + ci_margin);
 
-        Self {
+        Ok(Self {
             mean: clean_mean,
             median,
             stdev,
@@ -70,16 +203,113 @@ impl RobustStatistics {
             p99,
             outliers_removed,
             confidence_interval_95,
+            quantile_estimator: estimator,
+            mean_interval: MeanInterval::StudentT,
+            quantile_intervals: None,
+            shape,
+        })
+    }
+
+    /// Approximate robust statistics from a streaming accumulator
+    ///
+    /// Quantiles, MAD and the outlier filter work on the sketch buckets, so they
+    /// are within `SKETCH_ACCURACY` of the exact values; mean and stdev are exact
+    /// when no outliers are removed. Cost depends on the number of buckets, not
+    /// on the number of samples.
+    pub fn try_from_accumulator(accumulator: &SampleAccumulator) -> Result<Self, StatisticsError> {
+        let total = accumulator.count() as usize + accumulator.non_finite() as usize;
+        if total == 0 {
+            return Err(StatisticsError::Empty);
         }
+        if accumulator.non_finite() > 0 {
+            return Err(StatisticsError::NonFinite { count: accumulator.non_finite() as usize, total });
+        }
+        if total < MIN_SAMPLES {
+            return Err(StatisticsError::TooFewSamples { required: MIN_SAMPLES, actual: total });
+        }
+
+        let buckets = accumulator.buckets();
+        let median = weighted_percentile(&buckets, 50.0);
+        let mut deviations: Vec<(f64, u64)> = buckets.iter().map(|&(v, count)| ((v - median).abs(), count)).collect();
+        deviations.sort_by(|a, b| a.0.total_cmp(&b.0));
+        let mad = weighted_percentile(&deviations, 50.0);
+        let scale = if mad > 0.0 {
+            mad
+        } else {
+            0.845 * deviations.iter().map(|&(d, count)| d * count as f64).sum::<f64>() / total as f64
+        };
+        let threshold = 3.5 * scale;
+        let is_clean = |v: f64| (v - median).abs() <= threshold;
+        let clean: Vec<(f64, u64)> = buckets.iter().copied().filter(|&(v, _)| is_clean(v)).collect();
+        let removed: Vec<(f64, u64)> = buckets.iter().copied().filter(|&(v, _)| !is_clean(v)).collect();
+
+        // Exact sums minus the removed buckets' representatives
+        let outliers_removed: u64 = removed.iter().map(|&(_, count)| count).sum();
+        let n = (accumulator.count() - outliers_removed) as f64;
+        let sum = accumulator.mean() * accumulator.count() as f64
+            - removed.iter().map(|&(v, count)| v * count as f64).sum::<f64>();
+        let sum_sq = accumulator.sum_of_squares()
+            - removed.iter().map(|&(v, count)| v * v * count as f64).sum::<f64>();
+        let clean_mean = sum / n;
+        let deviation_sq = (sum_sq - n * clean_mean * clean_mean).max(0.0);
+        let stdev = (deviation_sq / n).sqrt();
+        let ci_margin = if n < 2.0 {
+            0.0
+        } else {
+            student_t_975(n as usize - 1) * (deviation_sq / (n - 1.0)).sqrt() / n.sqrt()
+        };
+
+        Ok(Self {
+            mean: clean_mean,
+            median,
+            stdev,
+            mad,
+            p95: weighted_percentile(&clean, 95.0),
+            p99: weighted_percentile(&clean, 99.0),
+            outliers_removed: outliers_removed as usize,
+            confidence_interval_95: (clean_mean - ci_margin, clean_mean + ci_margin),
+            quantile_estimator: QuantileEstimator::Sketch,
+            mean_interval: MeanInterval::StudentT,
+            quantile_intervals: None,
+            shape: accumulator.shape(),
+        })
     }
 
-    /// Calculate percentile of sorted data
-    fn percentile(sorted_data: &[f64], p: f64) -> f64 {
-        if sorted_data.is_empty() {
-            return 0.0;
+    /// Add percentile bootstrap intervals of median, P95 and P99 over `samples`
+    ///
+    /// Every resample goes through the same outlier filter as the estimate
+    /// itself. `resamples` of 0 or fewer than two samples leave the intervals unset.
+    pub fn with_quantile_intervals(mut self, samples: &[f64], resamples: usize) -> Self {
+        if resamples == 0 || samples.len() < 2 {
+            return self;
         }
-        let idx = (p / 100.0 * (sorted_data.len() - 1) as f64).round() as usize;
-        sorted_data[idx.min(sorted_data.len() - 1)]
+        let mut rng = SplitMix64::new(BOOTSTRAP_SEED);
+        let mut cache = QuantileCache::new(self.quantile_estimator);
+        let mut resample = vec![0.0; samples.len()];
+        let mut medians = Vec::with_capacity(resamples);
+        let mut p95s = Vec::with_capacity(resamples);
+        let mut p99s = Vec::with_capacity(resamples);
+        for _ in 0..resamples {
+            for slot in resample.iter_mut() {
+                *slot = samples[rng.next_below(samples.len())];
+            }
+            resample.sort_by(f64::total_cmp);
+            let quantiles = Quantiles::of_sorted(&resample, &mut cache);
+            medians.push(quantiles.median);
+            p95s.push(quantiles.p95);
+            p99s.push(quantiles.p99);
+        }
+        let interval = |mut values: Vec<f64>| {
+            values.sort_by(f64::total_cmp);
+            (QuantileEstimator::Type7.percentile(&values, 2.5), QuantileEstimator::Type7.percentile(&values, 97.5))
+        };
+        self.quantile_intervals = Some(QuantileIntervals {
+            resamples,
+            median: interval(medians),
+            p95: interval(p95s),
+            p99: interval(p99s),
+        });
+        self
     }
 
     /// Composite performance score for optimization (uses p95 instead of mean!)
@@ -109,4 +339,39 @@ impl Default for PerformanceWeights {
             worst_case: 0.10,  // 10% - protection from outliers
         }
     }
-}
\ No newline at end of file
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ESTIMATOR: QuantileEstimator = QuantileEstimator::Type7;
+
+    #[test]
+    fn empty_samples_are_rejected() {
+        assert_eq!(RobustStatistics::try_from_samples(Vec::new(), ESTIMATOR).unwrap_err(), StatisticsError::Empty);
+    }
+
+    #[test]
+    fn non_finite_samples_are_rejected() {
+        let error = RobustStatistics::try_from_samples(vec![0.01, f64::NAN, 0.02, f64::INFINITY], ESTIMATOR).unwrap_err();
+        assert_eq!(error, StatisticsError::NonFinite { count: 2, total: 4 });
+        assert_eq!(error.to_string(), "2 of 4 samples are not finite");
+    }
+
+    #[test]
+    fn too_few_samples_are_rejected() {
+        let error = RobustStatistics::try_from_samples(vec![0.01], ESTIMATOR).unwrap_err();
+        assert_eq!(error, StatisticsError::TooFewSamples { required: MIN_SAMPLES, actual: 1 });
+    }
+
+    #[test]
+    fn outlier_filter_keeps_the_body() {
+        // Mostly equal samples give MAD = 0; the filter must still keep them
+        let mut samples = vec![0.02; 20];
+        samples.extend([0.021, 0.019, 5.0]);
+        let statistics = RobustStatistics::try_from_samples(samples, ESTIMATOR).unwrap();
+        assert_eq!(statistics.mad, 0.0);
+        assert_eq!(statistics.outliers_removed, 1);
+        assert!((statistics.mean - 0.02).abs() < 1e-12);
+    }
+}
@@ -0,0 +1,135 @@
+//! Significance tests between two sets of raw sleep deltas
+//!
+//! Tests run on the raw samples (before outlier removal), so they do not depend
+//! on the MAD filter applied by `RobustStatistics`.
+
+use crate::stats::quantile::{QuantileCache, QuantileEstimator};
+use crate::utils::rng::SplitMix64;
+
+/// Number of bootstrap resamples used by default
+pub const DEFAULT_RESAMPLES: usize = 2000;
+
+/// Fixed seed so repeated comparisons give the same answer
+const BOOTSTRAP_SEED: u64 = 0x5EED_B007;
+
+/// Statistic compared between two sample sets
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Statistic {
+    Median,
+    P95,
+    P99,
+    Mad,
+}
+
+impl Statistic {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Statistic::Median => "P50",
+            Statistic::P95 => "P95",
+            Statistic::P99 => "P99",
+            Statistic::Mad => "MAD",
+        }
+    }
+
+    /// Evaluate the statistic on sorted samples, percentiles by `estimator`
+    pub fn of_sorted(&self, sorted: &[f64], estimator: QuantileEstimator) -> f64 {
+        self.evaluate(sorted, &mut QuantileCache::new(estimator))
+    }
+
+    /// Evaluate the statistic on unsorted samples
+    pub fn of(&self, samples: &[f64], estimator: QuantileEstimator) -> f64 {
+        let mut sorted = samples.to_vec();
+        sorted.sort_by(f64::total_cmp);
+        self.of_sorted(&sorted, estimator)
+    }
+
+    fn evaluate(&self, sorted: &[f64], cache: &mut QuantileCache) -> f64 {
+        match self {
+            Statistic::Median => cache.percentile(sorted, 50.0),
+            Statistic::P95 => cache.percentile(sorted, 95.0),
+            Statistic::P99 => cache.percentile(sorted, 99.0),
+            Statistic::Mad => {
+                let median = cache.percentile(sorted, 50.0);
+                let mut deviations: Vec<f64> = sorted.iter().map(|x| (x - median).abs()).collect();
+                deviations.sort_by(f64::total_cmp);
+                cache.percentile(&deviations, 50.0)
+            }
+        }
+    }
+}
+
+/// Bootstrap estimate of `statistic(b) - statistic(a)`
+#[derive(Debug, Clone)]
+pub struct BootstrapDifference {
+    pub statistic: Statistic,
+    pub difference: f64,             // Observed difference (b - a)
+    pub confidence: f64,             // Level of the interval, e.g. 0.95
+    pub confidence_interval: (f64, f64),
+    pub significant: bool,           // Interval excludes zero
+}
+
+/// Percentile bootstrap of the difference in `statistic` between two sample sets, 95% interval
+pub fn bootstrap_difference(
+    a: &[f64],
+    b: &[f64],
+    statistic: Statistic,
+    estimator: QuantileEstimator,
+    resamples: usize,
+) -> BootstrapDifference {
+    bootstrap_difference_at(a, b, statistic, estimator, resamples, 0.95)
+}
+
+/// Percentile bootstrap of the difference in `statistic`, interval at `confidence` (0..1)
+///
+/// Interval bounds are percentiles of the bootstrap distribution by `estimator`.
+pub fn bootstrap_difference_at(
+    a: &[f64],
+    b: &[f64],
+    statistic: Statistic,
+    estimator: QuantileEstimator,
+    resamples: usize,
+    confidence: f64,
+) -> BootstrapDifference {
+    let difference = statistic.of(b, estimator) - statistic.of(a, estimator);
+    if a.len() < 2 || b.len() < 2 || resamples == 0 {
+        return BootstrapDifference {
+            statistic,
+            difference,
+            confidence,
+            confidence_interval: (f64::NEG_INFINITY, f64::INFINITY),
+            significant: false,
+        };
+    }
+
+    let mut rng = SplitMix64::new(BOOTSTRAP_SEED);
+    let mut cache = QuantileCache::new(estimator);
+    let mut resample_a = vec![0.0; a.len()];
+    let mut resample_b = vec![0.0; b.len()];
+    let mut differences: Vec<f64> = (0..resamples)
+        .map(|_| {
+            fill_resample(&mut rng, a, &mut resample_a);
+            fill_resample(&mut rng, b, &mut resample_b);
+            statistic.evaluate(&resample_b, &mut cache) - statistic.evaluate(&resample_a, &mut cache)
+        })
+        .collect();
+    differences.sort_by(f64::total_cmp);
+
+    let tail = (1.0 - confidence) / 2.0 * 100.0;
+    let lower = estimator.percentile(&differences, tail);
+    let upper = estimator.percentile(&differences, 100.0 - tail);
+    BootstrapDifference {
+        statistic,
+        difference,
+        confidence,
+        confidence_interval: (lower, upper),
+        significant: lower > 0.0 || upper < 0.0,
+    }
+}
+
+/// Draw a sorted resample with replacement
+fn fill_resample(rng: &mut SplitMix64, samples: &[f64], out: &mut [f64]) {
+    for slot in out.iter_mut() {
+        *slot = samples[rng.next_below(samples.len())];
+    }
+    out.sort_by(f64::total_cmp);
+}
@@ -2,12 +2,61 @@
 //!
 //! This module handles timer measurements with robust statistical analysis.
 
+use serde::{Deserialize, Serialize};
+
+use crate::stats::distribution::Histogram;
+use crate::stats::drift::Stationarity;
 use crate::stats::robust_statistics::RobustStatistics;
 
 /// Timer measurement with all statistical data
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TimerMeasurement {
     pub resolution_ms: f64,
     pub statistics: RobustStatistics,
     pub raw_samples: Vec<f64>,
-}
\ No newline at end of file
+    /// Number of samples taken in each measurement run, in order
+    #[serde(default)]
+    pub run_lengths: Vec<usize>,
+    /// Histogram of all samples (final results only)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub histogram: Option<Histogram>,
+    /// Warm-up samples dropped and drift found in the runs
+    #[serde(default)]
+    pub stationarity: Stationarity,
+}
+
+impl TimerMeasurement {
+    /// Raw samples split into measurement runs
+    ///
+    /// Falls back to a single run when run boundaries were not recorded.
+    pub fn runs(&self) -> Vec<&[f64]> {
+        if self.run_lengths.iter().sum::<usize>() != self.raw_samples.len() {
+            return vec![&self.raw_samples];
+        }
+        let mut runs = Vec::with_capacity(self.run_lengths.len());
+        let mut rest = self.raw_samples.as_slice();
+        for &len in &self.run_lengths {
+            let (run, tail) = rest.split_at(len);
+            runs.push(run);
+            rest = tail;
+        }
+        runs
+    }
+}
+
+#[cfg(test)]
+impl TimerMeasurement {
+    /// Single-run measurement of `samples`, percentiles by type 7
+    pub(crate) fn from_samples(resolution_ms: f64, samples: Vec<f64>) -> Self {
+        use crate::stats::quantile::QuantileEstimator;
+
+        Self {
+            resolution_ms,
+            statistics: RobustStatistics::try_from_samples(samples.clone(), QuantileEstimator::Type7).unwrap(),
+            run_lengths: vec![samples.len()],
+            raw_samples: samples,
+            histogram: None,
+            stationarity: Stationarity::default(),
+        }
+    }
+}
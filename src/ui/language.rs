@@ -34,4 +34,9 @@ impl Language {
             Language::Chinese => "zh",
         }
     }
+
+    /// Find a language by its code (ISO 639-1)
+    pub fn from_code(code: &str) -> Option<Language> {
+        Language::all().iter().copied().find(|lang| lang.code().eq_ignore_ascii_case(code))
+    }
 }
\ No newline at end of file
@@ -3,6 +3,9 @@
 //! This module provides internationalization support for all UI elements
 //! in the timer resolution benchmark tool.
 
+use crate::optimization::criteria::{Criterion, RankingCriteria, WeightingMethod};
+use crate::report::ResultDocument;
+use crate::stats::distribution::DistributionShape;
 use crate::ui::language::Language;
 pub use crate::ui::localization_key::LocalizationKey;
 
@@ -41,8 +44,8 @@ impl Localization {
 
     pub fn get_range(&self, low: f64, high: f64) -> String {
         self.get(LocalizationKey::Range)
-            .replace("{:.4}", &format!("{:.4}", low))
-            .replace("{:.4}", &format!("{:.4}", high))
+            .replacen("{:.4}", &format!("{:.4}", low), 1)
+            .replacen("{:.4}", &format!("{:.4}", high), 1)
     }
     
     pub fn get_current_best(&self, value: f64, score: f64) -> String {
@@ -210,6 +213,26 @@ impl Localization {
         self.get(LocalizationKey::CiWidth).replace("{:.4}", &format!("{:.4}", width))
     }
 
+    /// Console line for the value of one ranking criterion
+    pub fn get_criterion_score(&self, criterion: Criterion, value: f64) -> String {
+        let key = match criterion {
+            Criterion::P50 => LocalizationKey::P50Delta,
+            Criterion::P95 => LocalizationKey::P95Delta,
+            Criterion::P99 => LocalizationKey::P99Delta,
+            Criterion::Mean => LocalizationKey::MeanDelta,
+            Criterion::Stdev => LocalizationKey::StdevDelta,
+            Criterion::Mad => LocalizationKey::Mad,
+            Criterion::CiWidth => LocalizationKey::CiWidth,
+            Criterion::Outliers => {
+                return self.get(LocalizationKey::OutliersRemoved).replace("{}", &format!("{:.0}", value));
+            }
+            Criterion::Bimodality => {
+                return self.get(LocalizationKey::BimodalityCoefficient).replace("{:.3}", &format!("{:.3}", value));
+            }
+        };
+        self.get(key).replace("{:.4}", &format!("{:.4}", value))
+    }
+
     pub fn get_mutex_error_message(&self, message: &str) -> String {
         self.get(LocalizationKey::MutexErrorMessage).replace("{}", message)
     }
@@ -302,6 +325,300 @@ impl Localization {
     pub fn get_diff(&self, value: f64) -> String {
         self.get(LocalizationKey::Diff).replace("{:.4}", &format!("{:.4}", value))
     }
+
+    /// `items` joined with the localized "and"
+    pub fn join_and(&self, items: &[&str]) -> String {
+        items.join(self.get(LocalizationKey::ListAnd))
+    }
+
+    pub fn get_significance_comparison(&self, rank: usize, resolution: f64, median_difference: f64, p95_difference: f64, verdict: &str) -> String {
+        self.get(LocalizationKey::SignificanceComparison)
+            .replacen("{}", &rank.to_string(), 1)
+            .replacen("{:.4}", &format!("{:.4}", resolution), 1)
+            .replacen("{:+.4}", &format!("{:+.4}", median_difference), 1)
+            .replacen("{:+.4}", &format!("{:+.4}", p95_difference), 1)
+            .replacen("{}", verdict, 1)
+    }
+
+    pub fn get_verdict_runner_up_better(&self, criteria: &[&str]) -> String {
+        self.get(LocalizationKey::VerdictRunnerUpBetter).replace("{}", &self.join_and(criteria))
+    }
+
+    pub fn get_significance_tie(&self, resolutions: &str) -> String {
+        self.get(LocalizationKey::SignificanceTie).replace("{}", resolutions)
+    }
+
+    pub fn get_significance_not_clear_win(&self, resolution: f64, criteria: &[&str]) -> String {
+        self.get(LocalizationKey::SignificanceNotClearWin)
+            .replace("{:.4}", &format!("{:.4}", resolution))
+            .replace("{}", &self.join_and(criteria))
+    }
+
+    pub fn get_significance_clear_win(&self, runners_up: usize) -> String {
+        self.get(LocalizationKey::SignificanceClearWin).replace("{}", &runners_up.to_string())
+    }
+
+    pub fn get_report_points_in_file(&self, points: usize) -> String {
+        self.get(LocalizationKey::ReportPointsInFile).replace("{}", &points.to_string())
+    }
+
+    pub fn get_racing_title(&self, candidates: usize, budget_secs: u64) -> String {
+        self.get(LocalizationKey::RacingTitle)
+            .replacen("{}", &candidates.to_string(), 1)
+            .replacen("{}", &budget_secs.to_string(), 1)
+    }
+
+    pub fn get_racing_candidates(&self, resolutions: &str) -> String {
+        self.get(LocalizationKey::RacingCandidates).replace("{}", resolutions)
+    }
+
+    pub fn get_racing_round(&self, round: usize, leader: f64, left: usize) -> String {
+        self.get(LocalizationKey::RacingRound)
+            .replacen("{}", &round.to_string(), 1)
+            .replacen("{:.4}", &format!("{:.4}", leader), 1)
+            .replacen("{}", &left.to_string(), 1)
+    }
+
+    pub fn get_racing_dropped(&self, resolutions: &str) -> String {
+        self.get(LocalizationKey::RacingDropped).replace("{}", resolutions)
+    }
+
+    pub fn get_racing_finished(&self, stop_reason: LocalizationKey, extra_runs: usize, seconds: f64) -> String {
+        self.get(LocalizationKey::RacingFinished)
+            .replacen("{}", self.get(stop_reason), 1)
+            .replacen("{}", &extra_runs.to_string(), 1)
+            .replacen("{:.1}", &format!("{:.1}", seconds), 1)
+    }
+
+    pub fn get_racing_confirmed(&self, resolution: f64) -> String {
+        self.get(LocalizationKey::RacingConfirmed).replace("{:.4}", &format!("{:.4}", resolution))
+    }
+
+    pub fn get_racing_changed(&self, from: f64, to: f64) -> String {
+        self.get(LocalizationKey::RacingChanged)
+            .replacen("{:.4}", &format!("{:.4}", from), 1)
+            .replacen("{:.4}", &format!("{:.4}", to), 1)
+    }
+
+    pub fn get_warmup_dropped(&self, samples: usize, warmup_ms: Option<f64>) -> String {
+        let duration = warmup_ms.map_or(String::new(), |ms| {
+            self.get(LocalizationKey::WarmupDroppedMs).replace("{:.0}", &format!("{:.0}", ms))
+        });
+        format!("{}{}", self.get(LocalizationKey::WarmupDropped).replace("{}", &samples.to_string()), duration)
+    }
+
+    pub fn get_drift_runs(&self, drifting: usize, runs: usize) -> String {
+        self.get(LocalizationKey::DriftRuns)
+            .replacen("{}", &drifting.to_string(), 1)
+            .replacen("{}", &runs.to_string(), 1)
+    }
+
+    pub fn get_stationarity_dropped(&self, samples: usize, points_with_warmup: usize, points: usize) -> String {
+        self.get(LocalizationKey::StationarityDropped)
+            .replacen("{}", &samples.to_string(), 1)
+            .replacen("{}", &points_with_warmup.to_string(), 1)
+            .replacen("{}", &points.to_string(), 1)
+    }
+
+    pub fn get_stationarity_settle(&self, suggested_ms: u64, current_ms: u64) -> String {
+        self.get(LocalizationKey::StationaritySettle)
+            .replacen("{}", &suggested_ms.to_string(), 1)
+            .replacen("{}", &current_ms.to_string(), 1)
+    }
+
+    pub fn get_stationarity_drifting(&self, resolutions: &str) -> String {
+        self.get(LocalizationKey::StationarityDrifting).replace("{}", resolutions)
+    }
+
+    pub fn get_refine_coarse_grid(&self, points: usize, step: f64) -> String {
+        self.get(LocalizationKey::RefineCoarseGrid)
+            .replacen("{}", &points.to_string(), 1)
+            .replacen("{:.4}", &format!("{:.4}", step), 1)
+    }
+
+    pub fn get_refine_coarse_best(&self, best: f64, low: f64, high: f64) -> String {
+        [best, low, high].iter().fold(self.get(LocalizationKey::RefineCoarseBest).to_string(), |text, value| {
+            text.replacen("{:.4}", &format!("{:.4}", value), 1)
+        })
+    }
+
+    pub fn get_refine_step(&self, step: usize, lower: f64, upper: f64, low: f64, high: f64) -> String {
+        let text = self.get(LocalizationKey::RefineStep).replacen("{}", &step.to_string(), 1);
+        [lower, upper, low, high].iter().fold(text, |text, value| {
+            text.replacen("{:.4}", &format!("{:.4}", value), 1)
+        })
+    }
+
+    pub fn get_refine_minimum(&self, minimum: f64, steps: usize) -> String {
+        self.get(LocalizationKey::RefineMinimum)
+            .replacen("{:.4}", &format!("{:.4}", minimum), 1)
+            .replacen("{}", &steps.to_string(), 1)
+    }
+
+    pub fn get_refine_points_measured(&self, total: usize, coarse: usize, refinement: usize) -> String {
+        self.get(LocalizationKey::RefinePointsMeasured)
+            .replacen("{}", &total.to_string(), 1)
+            .replacen("{}", &coarse.to_string(), 1)
+            .replacen("{}", &refinement.to_string(), 1)
+    }
+
+    pub fn get_refine_total_time(&self, minutes: f64) -> String {
+        self.get(LocalizationKey::RefineTotalTime).replace("{:.1}", &format!("{:.1}", minutes))
+    }
+
+    pub fn get_resuming_run(&self, method: &str, path: &str, recorded: usize) -> String {
+        self.get(LocalizationKey::ResumingRun)
+            .replacen("{}", method, 1)
+            .replacen("{}", path, 1)
+            .replacen("{}", &recorded.to_string(), 1)
+    }
+
+    pub fn get_resuming_range(&self, start: f64, end: f64, step: f64, samples: i32) -> String {
+        self.get(LocalizationKey::ResumingRange)
+            .replacen("{:.4}", &format!("{:.4}", start), 1)
+            .replacen("{:.4}", &format!("{:.4}", end), 1)
+            .replacen("{:.4}", &format!("{:.4}", step), 1)
+            .replacen("{}", &samples.to_string(), 1)
+    }
+
+    pub fn get_point_restored(&self, resolution_ms: f64) -> String {
+        self.get(LocalizationKey::PointRestored).replace("{:.4}", &format!("{:.4}", resolution_ms))
+    }
+
+    pub fn get_ranker_winner(&self, ranker: &str, resolution_ms: f64, pareto_optimal: bool) -> String {
+        let dominated = if pareto_optimal { "" } else { self.get(LocalizationKey::RankerDominated) };
+        self.get(LocalizationKey::RankerWinner)
+            .replacen("{}", &format!("{:<13}", ranker), 1)
+            .replacen("{:.4}", &format!("{:.4}", resolution_ms), 1)
+            .replacen("{}", dominated, 1)
+    }
+
+    pub fn get_ranker_pareto_front(&self, resolutions: &str) -> String {
+        self.get(LocalizationKey::RankerParetoFront).replace("{}", resolutions)
+    }
+
+    pub fn get_sensitivity_title(&self, samples: usize) -> String {
+        self.get(LocalizationKey::SensitivityTitle).replace("{}", &samples.to_string())
+    }
+
+    pub fn get_sensitivity_win_rate(&self, resolution_ms: f64, fraction: f64) -> String {
+        self.get(LocalizationKey::SensitivityWinRate)
+            .replacen("{:.4}", &format!("{:.4}", resolution_ms), 1)
+            .replacen("{}", &format!("{:>5.1}", fraction * 100.0), 1)
+    }
+
+    pub fn get_sensitivity_weight_range(&self, criterion: &str, weight: f64, lower: f64, upper: f64) -> String {
+        self.get(LocalizationKey::SensitivityWeightRange)
+            .replacen("{}", &format!("{:<9}", criterion), 1)
+            .replacen("{:.2}", &format!("{:.2}", weight), 1)
+            .replacen("{:.2}", &format!("{:.2}", lower), 1)
+            .replacen("{:.2}", &format!("{:.2}", upper), 1)
+    }
+
+    /// Robust or fragile verdict on the winner's share of weight settings
+    pub fn get_sensitivity_verdict(&self, robust: bool, winner_ms: f64, rate: f64) -> String {
+        let key = if robust { LocalizationKey::SensitivityRobust } else { LocalizationKey::SensitivityFragile };
+        self.get(key)
+            .replacen("{:.4}", &format!("{:.4}", winner_ms), 1)
+            .replacen("{:.0}", &format!("{:.0}", rate * 100.0), 1)
+    }
+
+    pub fn get_distribution_title(&self, resolution_ms: f64, samples: usize) -> String {
+        self.get(LocalizationKey::DistributionTitle)
+            .replacen("{:.4}", &format!("{:.4}", resolution_ms), 1)
+            .replacen("{}", &samples.to_string(), 1)
+    }
+
+    pub fn get_distribution_shape(&self, shape: &DistributionShape) -> String {
+        self.get(LocalizationKey::DistributionShape)
+            .replacen("{:.2}", &format!("{:.2}", shape.skewness), 1)
+            .replacen("{:.2}", &format!("{:.2}", shape.excess_kurtosis), 1)
+            .replacen("{:.3}", &format!("{:.3}", shape.bimodality_coefficient), 1)
+            .replacen("{:.4}", &format!("{:.4}", shape.p999), 1)
+            .replacen("{:.4}", &format!("{:.4}", shape.max), 1)
+    }
+
+    pub fn get_distribution_bimodal(&self, threshold: f64) -> String {
+        self.get(LocalizationKey::DistributionBimodal).replace("{:.3}", &format!("{:.3}", threshold))
+    }
+
+    pub fn get_distribution_bimodal_elsewhere(&self, threshold: f64, resolutions: &str) -> String {
+        self.get(LocalizationKey::DistributionBimodalElsewhere)
+            .replacen("{:.3}", &format!("{:.3}", threshold), 1)
+            .replacen("{}", resolutions, 1)
+    }
+
+    pub fn get_ranking_criteria(&self, criteria: &RankingCriteria) -> String {
+        match criteria.weighting {
+            WeightingMethod::Manual => self.get(LocalizationKey::RankingCriteriaLine).replace("{}", &criteria.to_string()),
+            method => {
+                let names = criteria.criteria.iter().map(|c| c.criterion.name()).collect::<Vec<_>>().join(", ");
+                self.get(LocalizationKey::RankingCriteriaDerived)
+                    .replacen("{}", &names, 1)
+                    .replacen("{}", method.name(), 1)
+            }
+        }
+    }
+
+    pub fn get_acquisition_batch(&self, size: usize, resolutions: &str) -> String {
+        self.get(LocalizationKey::AcquisitionBatch)
+            .replacen("{}", &size.to_string(), 1)
+            .replacen("{}", resolutions, 1)
+    }
+
+    pub fn get_gp_surrogate(&self, kernel: &str, length_scale_ms: f64, noise_to_signal: f64, log_marginal_likelihood: f64) -> String {
+        self.get(LocalizationKey::GpSurrogate)
+            .replacen("{}", kernel, 1)
+            .replacen("{:.4}", &format!("{:.4}", length_scale_ms), 1)
+            .replacen("{:.3}", &format!("{:.3}", noise_to_signal), 1)
+            .replacen("{:.2}", &format!("{:.2}", log_marginal_likelihood), 1)
+    }
+
+    pub fn get_history_empty(&self, dir: &str) -> String {
+        self.get(LocalizationKey::HistoryEmpty).replace("{}", dir)
+    }
+
+    pub fn get_history_tie(&self, equivalent: usize) -> String {
+        self.get(LocalizationKey::HistoryTie).replace("{}", &equivalent.to_string())
+    }
+
+    pub fn get_history_count(&self, runs: usize, dir: &str) -> String {
+        self.get(LocalizationKey::HistoryCount)
+            .replacen("{}", &runs.to_string(), 1)
+            .replacen("{}", dir, 1)
+    }
+
+    /// One line describing a compared run, e.g. "   A: <id> (Linear, simulated, HPET: -, optimal 0.5000 ms)"
+    pub fn get_compare_run(&self, label: &str, id: &str, document: &ResultDocument) -> String {
+        let partial = if document.partial { self.get(LocalizationKey::CompareRunPartial) } else { "" };
+        self.get(LocalizationKey::CompareRun)
+            .replacen("{}", label, 1)
+            .replacen("{}", id, 1)
+            .replacen("{}", &format!("{:?}", document.run.method), 1)
+            .replacen("{}", &document.run.backend, 1)
+            .replacen("{}", document.run.hpet_status.as_deref().unwrap_or("-"), 1)
+            .replacen("{:.4}", &format!("{:.4}", document.optimal_resolution_ms), 1)
+            .replacen("{}", partial, 1)
+    }
+
+    pub fn get_compare_different_systems(&self, fingerprint_a: &str, fingerprint_b: &str) -> String {
+        self.get(LocalizationKey::CompareDifferentSystems)
+            .replacen("{}", fingerprint_a, 1)
+            .replacen("{}", fingerprint_b, 1)
+    }
+
+    pub fn get_compare_summary(&self, compared: usize, improved: usize, regressed: usize) -> String {
+        self.get(LocalizationKey::CompareSummary)
+            .replacen("{}", &compared.to_string(), 1)
+            .replacen("{}", &improved.to_string(), 1)
+            .replacen("{}", &regressed.to_string(), 1)
+    }
+
+    pub fn get_compare_optimal(&self, optimal_a_ms: f64, optimal_b_ms: f64) -> String {
+        self.get(LocalizationKey::CompareOptimal)
+            .replacen("{:.4}", &format!("{:.4}", optimal_a_ms), 1)
+            .replacen("{:.4}", &format!("{:.4}", optimal_b_ms), 1)
+    }
 }
 
 /// Language selection function that allows users to choose their preferred language
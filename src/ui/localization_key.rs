@@ -155,6 +155,11 @@ pub enum LocalizationKey {
     Mad,
     P99Delta,
     CiWidth,
+    P50Delta,
+    MeanDelta,
+    StdevDelta,
+    OutliersRemoved,
+    BimodalityCoefficient,
     ErrorMutexConflict,
     MutexErrorMessage,
     MutexErrorHint,
@@ -205,4 +210,117 @@ pub enum LocalizationKey {
     FastLinearMethodDesc3,
     FastLinearMethodDesc4,
     EarlyStopThreshold,
+
+    // Coarse-to-fine method
+    RefineMethod,
+    RefineMethodTitle,
+    RefineMethodDesc1,
+    RefineMethodDesc2,
+    RefineMethodDesc3,
+
+    // Winner significance
+    SignificanceTitle,
+    SignificanceComparison,
+    VerdictEquivalent,
+    VerdictWinnerBetter,
+    VerdictRunnerUpBetter,
+    SignificanceTie,
+    SignificanceTieBreak,
+    SignificanceNotClearWin,
+    SignificanceClearWin,
+    ListAnd,
+    ReportPartial,
+    ReportPointsInFile,
+
+    // Adaptive racing
+    RacingTitle,
+    RacingCandidates,
+    RacingRound,
+    RacingDropped,
+    RacingStopSeparated,
+    RacingStopRoundLimit,
+    RacingStopCancelled,
+    RacingStopBudget,
+    RacingStopNoMeasurements,
+    RacingFinished,
+    RacingConfirmed,
+    RacingChanged,
+
+    // Warm-up and drift
+    WarmupDropped,
+    WarmupDroppedMs,
+    DriftRuns,
+    StationarityTitle,
+    StationarityDropped,
+    StationaritySettle,
+    StationarityDrifting,
+
+    // Coarse-to-fine progress
+    RefineCoarseGrid,
+    RefineRefinement,
+    RefineCoarseCancelled,
+    RefineCoarseCompleted,
+    RefineCoarseBest,
+    RefineStep,
+    RefineMinimum,
+    RefineCompleted,
+    RefinePointsMeasured,
+    RefineTotalTime,
+
+    // Checkpoint resume
+    ResumingRun,
+    ResumingRange,
+    PointRestored,
+
+    // Ranker agreement
+    RankerTitle,
+    RankerWinner,
+    RankerDominated,
+    RankerParetoFront,
+    RankersAgree,
+    RankersDisagree,
+
+    // Weight sensitivity
+    SensitivityTitle,
+    SensitivityWinRate,
+    SensitivityWeightRange,
+    SensitivityRobust,
+    SensitivityFragile,
+
+    // Distribution at the optimum
+    DistributionTitle,
+    DistributionShape,
+    DistributionBimodal,
+    DistributionBimodalElsewhere,
+
+    // Ctrl+C
+    CtrlCStopping,
+    CtrlCAgain,
+    CtrlCAborted,
+    CtrlCReleaseFailed,
+
+    // Hybrid search setup and surrogate
+    RankingCriteriaLine,
+    RankingCriteriaDerived,
+    AcquisitionBatch,
+    GpSurrogate,
+
+    // Run history and comparison
+    HistoryEmpty,
+    HistoryId,
+    HistoryMethod,
+    HistoryBackend,
+    HistoryPoints,
+    HistoryOptimal,
+    HistoryTie,
+    HistoryPartial,
+    HistoryCount,
+    CompareTitle,
+    CompareRun,
+    CompareRunPartial,
+    CompareDifferentSystems,
+    CompareResolution,
+    CompareLegend,
+    CompareSummary,
+    CompareOptimal,
 }
\ No newline at end of file
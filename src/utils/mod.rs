@@ -1,3 +1,4 @@
 //! Utility modules for timer resolution benchmarking
 
-pub mod helpers;
\ No newline at end of file
+pub mod helpers;
+pub mod rng;
\ No newline at end of file
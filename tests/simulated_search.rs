@@ -0,0 +1,263 @@
+//! End-to-end searches against the seeded simulated timer
+//!
+//! The simulated overshoot has a known optimum, so every search method can be
+//! checked for finding it without touching the system timer.
+
+use std::io;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use serde_json::json;
+
+use timer_res_benchmark::backend::{ProbeReport, SimulatedTimer, SimulationModel, SleepProbe};
+use timer_res_benchmark::core::refine::coarse_to_fine_search;
+use timer_res_benchmark::core::{
+    fast_linear_search, linear_exhaustive_search, optimize_timer_resolution, BenchmarkingParameters,
+    CancellationToken, Checkpoint, OptimizationMethod, OptimizationResult,
+};
+use timer_res_benchmark::ui::language::Language;
+use timer_res_benchmark::optimization::acquisition::AcquisitionKind;
+use timer_res_benchmark::ui::localization::Localization;
+
+const START_MS: f64 = 0.5;
+const END_MS: f64 = 0.51;
+const STEP_MS: f64 = 0.001;
+
+/// The hybrid and coarse-to-fine searches are not tied to `STEP_MS`; they may
+/// settle one 0.0001 ms grid point beside the optimum
+const GRID_TOLERANCE_MS: f64 = 0.00015;
+
+fn parameters() -> BenchmarkingParameters {
+    serde_json::from_value(json!({
+        "StartValue": START_MS,
+        "IncrementValue": STEP_MS,
+        "EndValue": END_MS,
+        "SampleValue": 100,
+        "EarlyStopThreshold": 20,
+        "BootstrapResamples": 0,
+        "HistogramBins": 0,
+        "Backend": "simulated",
+        "Simulation": model(),
+    }))
+    .unwrap()
+}
+
+/// Default model without the tick sawtooth and with a steeper bowl, so the
+/// optimum stands out from its neighbours by more than the jitter
+fn model() -> SimulationModel {
+    SimulationModel { tick_weight: 0.0, bowl_slope: 5.0, ..SimulationModel::default() }
+}
+
+fn ground_truth() -> f64 {
+    model().ground_truth(START_MS, END_MS, STEP_MS)
+}
+
+/// Probe that cancels the search once it has been sampled `limit` times, as a
+/// Ctrl+C would; the point being measured still finishes
+struct CancellingProbe<'a> {
+    timer: &'a SimulatedTimer,
+    cancel: &'a CancellationToken,
+    remaining: AtomicUsize,
+}
+
+impl<'a> CancellingProbe<'a> {
+    fn new(timer: &'a SimulatedTimer, cancel: &'a CancellationToken, limit: usize) -> Self {
+        Self { timer, cancel, remaining: AtomicUsize::new(limit) }
+    }
+}
+
+impl SleepProbe for CancellingProbe<'_> {
+    fn name(&self) -> &'static str {
+        "cancelling"
+    }
+
+    fn sample(&self, samples: usize) -> io::Result<ProbeReport> {
+        if self.remaining.fetch_sub(1, Ordering::SeqCst) == 1 {
+            self.cancel.cancel();
+        }
+        self.timer.sample(samples)
+    }
+}
+
+/// Resolution, closeness and rank of every ranked point
+fn rankings(result: &OptimizationResult) -> Vec<(f64, f64, usize)> {
+    result.rankings().iter().map(|s| (s.resolution_ms, s.closeness_coefficient, s.rank)).collect()
+}
+
+#[tokio::test]
+async fn linear_search_finds_the_optimum() {
+    let timer = SimulatedTimer::new(model());
+    let result = linear_exhaustive_search(
+        &parameters(),
+        &timer,
+        &timer,
+        &Localization::new(Language::English),
+        &Checkpoint::disabled(),
+        &CancellationToken::new(),
+    ).await.unwrap();
+    assert!((result.optimal_resolution - ground_truth()).abs() < 1e-9,
+        "found {:.4} ms, optimum {:.4} ms", result.optimal_resolution, ground_truth());
+}
+
+#[tokio::test]
+async fn fast_search_finds_the_optimum() {
+    let timer = SimulatedTimer::new(model());
+    let result = fast_linear_search(
+        &parameters(),
+        &timer,
+        &timer,
+        &Localization::new(Language::English),
+        &Checkpoint::disabled(),
+        &CancellationToken::new(),
+    ).await.unwrap();
+    assert!((result.optimal_resolution - ground_truth()).abs() < 1e-9,
+        "found {:.4} ms, optimum {:.4} ms", result.optimal_resolution, ground_truth());
+}
+
+/// Run the hybrid search with `acquisition` suggesting `batch` points at a time
+/// and check that it settles next to the optimum
+async fn assert_hybrid_converges(acquisition: AcquisitionKind, batch: usize) {
+    let mut params = parameters();
+    params.acquisition = acquisition;
+    params.acquisition_batch = batch;
+    let timer = SimulatedTimer::new(model());
+    let result = optimize_timer_resolution(
+        &params,
+        &timer,
+        &timer,
+        &Localization::new(Language::English),
+        &Checkpoint::disabled(),
+        &CancellationToken::new(),
+    ).await.unwrap();
+    assert!((result.optimal_resolution - ground_truth()).abs() < GRID_TOLERANCE_MS,
+        "{:?} with batches of {}: found {:.4} ms, optimum {:.4} ms",
+        acquisition, batch, result.optimal_resolution, ground_truth());
+}
+
+/// The default acquisition, one point at a time
+#[tokio::test]
+async fn hybrid_search_finds_the_optimum() {
+    assert_hybrid_converges(AcquisitionKind::Ucb, 1).await;
+}
+
+#[tokio::test]
+async fn hybrid_search_converges_with_ei() {
+    assert_hybrid_converges(AcquisitionKind::Ei, 1).await;
+}
+
+#[tokio::test]
+async fn hybrid_search_converges_with_pi() {
+    assert_hybrid_converges(AcquisitionKind::Pi, 1).await;
+}
+
+#[tokio::test]
+async fn hybrid_search_converges_with_thompson() {
+    assert_hybrid_converges(AcquisitionKind::Thompson, 1).await;
+}
+
+#[tokio::test]
+async fn hybrid_search_converges_in_batches() {
+    assert_hybrid_converges(AcquisitionKind::Ucb, 4).await;
+}
+
+#[tokio::test]
+async fn refine_search_finds_the_optimum() {
+    let timer = SimulatedTimer::new(model());
+    let result = coarse_to_fine_search(
+        &parameters(),
+        &timer,
+        &timer,
+        &Localization::new(Language::English),
+        &Checkpoint::disabled(),
+        &CancellationToken::new(),
+    ).await.unwrap();
+    assert!((result.optimal_resolution - ground_truth()).abs() < GRID_TOLERANCE_MS,
+        "found {:.4} ms, optimum {:.4} ms", result.optimal_resolution, ground_truth());
+}
+
+#[tokio::test]
+async fn resumed_linear_search_matches_a_full_run() {
+    let localization = Localization::new(Language::English);
+    let timer = SimulatedTimer::new(model());
+    let full = linear_exhaustive_search(
+        &parameters(),
+        &timer,
+        &timer,
+        &localization,
+        &Checkpoint::disabled(),
+        &CancellationToken::new(),
+    ).await.unwrap();
+
+    let dir = std::env::temp_dir().join(format!("timer_res_benchmark-resume-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let path = dir.join("checkpoint.jsonl");
+
+    // Stop part-way through the sweep; the same timer then carries on, so the
+    // remaining points see the samples a full run would have drawn
+    let timer = SimulatedTimer::new(model());
+    let cancel = CancellationToken::new();
+    let checkpoint = Checkpoint::create(&path, OptimizationMethod::Linear, &parameters()).unwrap();
+    let partial = linear_exhaustive_search(
+        &parameters(),
+        &timer,
+        &CancellingProbe::new(&timer, &cancel, 10),
+        &localization,
+        &checkpoint,
+        &cancel,
+    ).await.unwrap();
+    assert!(partial.partial);
+    drop(checkpoint);
+
+    let (checkpoint, header) = Checkpoint::resume(&path).unwrap();
+    assert_eq!(header.method, OptimizationMethod::Linear);
+    let recorded = checkpoint.pending();
+    assert!(recorded > 0 && recorded < full.rankings().len(), "{} points recorded", recorded);
+    let resumed = linear_exhaustive_search(
+        &header.parameters,
+        &timer,
+        &timer,
+        &localization,
+        &checkpoint,
+        &CancellationToken::new(),
+    ).await.unwrap();
+    std::fs::remove_dir_all(&dir).unwrap();
+
+    assert!(!resumed.partial);
+    assert_eq!(checkpoint.pending(), 0);
+    // Replayed samples pass through JSON, which may move a closeness by an ulp
+    let (resumed, full) = (rankings(&resumed), rankings(&full));
+    assert_eq!(resumed.len(), full.len());
+    for (r, f) in resumed.iter().zip(&full) {
+        assert_eq!((r.0, r.2), (f.0, f.2));
+        assert!((r.1 - f.1).abs() < 1e-12, "{:.4} ms: closeness {} vs {}", r.0, r.1, f.1);
+    }
+}
+
+#[tokio::test]
+async fn cancelled_search_keeps_partial_results() {
+    let localization = Localization::new(Language::English);
+    let timer = SimulatedTimer::new(model());
+    let cancel = CancellationToken::new();
+    let result = fast_linear_search(
+        &parameters(),
+        &timer,
+        &CancellingProbe::new(&timer, &cancel, 10),
+        &localization,
+        &Checkpoint::disabled(),
+        &cancel,
+    ).await.unwrap();
+    assert!(result.partial);
+    let ranked = result.rankings().len();
+    assert!(ranked > 0 && ranked < 11, "{} points ranked", ranked);
+    assert_eq!(result.optimal_resolution, result.rankings()[0].resolution_ms);
+
+    // Cancelled before the first point there is nothing to rank
+    let error = linear_exhaustive_search(
+        &parameters(),
+        &timer,
+        &timer,
+        &localization,
+        &Checkpoint::disabled(),
+        &cancel,
+    ).await.err().unwrap();
+    assert_eq!(error.kind(), io::ErrorKind::Interrupted);
+}